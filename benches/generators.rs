@@ -0,0 +1,91 @@
+//Benchmarks of maze generators on different maze sizes
+//Run with: cargo bench --bench generators
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+
+use dsdmaze::maze_generator::{generator_dfs::GeneratorDFS, generator_eller::GeneratorEller, generator_rd::GeneratorRD, generator_wilson::GeneratorWilson};
+
+const DFS_SIZES: [usize; 3] = [50, 500, 5000];
+const RD_SIZES: [usize; 3] = [51, 501, 5001]; //RD and Eller need odd size
+const PARALLEL_THREADS: usize = 4; //Parallel RD generates the same maze for any thread count, only speed changes
+const WILSON_SIZES: [usize; 3] = [51, 201, 501]; //Random walks get slow quickly so Wilson uses smaller sizes
+
+fn bench_dfs(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("DFS");
+    group.sample_size(10);
+
+    for size in DFS_SIZES {
+        group.bench_with_input(BenchmarkId::new("generate", size), &size, |bencher, &size| {
+            let mut random_engine: Pcg64 = Seeder::from("benchmark").make_rng();
+
+            bencher.iter(|| GeneratorDFS::new(black_box(size), &mut random_engine).generate());
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_rd(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("RD");
+    group.sample_size(10);
+
+    for size in RD_SIZES {
+        group.bench_with_input(BenchmarkId::new("generate", size), &size, |bencher, &size| {
+            let mut random_engine: Pcg64 = Seeder::from("benchmark").make_rng();
+
+            bencher.iter(|| GeneratorRD::new(black_box(size), &mut random_engine).generate());
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_rd_parallel(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("RD parallel");
+    group.sample_size(10);
+
+    for size in RD_SIZES {
+        group.bench_with_input(BenchmarkId::new("generate", size), &size, |bencher, &size| {
+            let mut random_engine: Pcg64 = Seeder::from("benchmark").make_rng();
+
+            bencher.iter(|| GeneratorRD::new(black_box(size), &mut random_engine).generate_parallel(PARALLEL_THREADS));
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_eller(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("Eller");
+    group.sample_size(10);
+
+    for size in RD_SIZES {
+        group.bench_with_input(BenchmarkId::new("generate", size), &size, |bencher, &size| {
+            let mut random_engine: Pcg64 = Seeder::from("benchmark").make_rng();
+
+            bencher.iter(|| GeneratorEller::new(black_box(size), &mut random_engine).generate());
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_wilson(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("Wilson");
+    group.sample_size(10);
+
+    for size in WILSON_SIZES {
+        group.bench_with_input(BenchmarkId::new("generate", size), &size, |bencher, &size| {
+            let mut random_engine: Pcg64 = Seeder::from("benchmark").make_rng();
+
+            bencher.iter(|| GeneratorWilson::new(black_box(size), &mut random_engine).generate());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(generators, bench_dfs, bench_rd, bench_rd_parallel, bench_eller, bench_wilson);
+criterion_main!(generators);
@@ -1,45 +1,146 @@
 #version 330 core
 
+#define MAX_LIGHTS 4
+#define MARKER_OUTLINE_WIDTH 0.06
+#define MARKER_PULSE_SPEED 4.0
+#define MARKER_DASHES 4.0
+
+struct Light {
+    vec3 position;
+    float radius;
+    vec3 color;
+};
+
 in vec2 textureCoords;
 in vec3 normalVector;
+in vec3 tangentVector;
 in vec3 fragmentPosition;
-in vec3 lightPosition;
+in vec4 lightSpacePosition;
 
 out vec4 FragColor;
 
-uniform vec3 lightColor;
+uniform mat4 view;
+uniform Light lights[MAX_LIGHTS];
+uniform int lightCount;
+uniform float gamma;
+uniform int normalMapping;
+uniform int shadows;
+uniform vec4 tint;
+uniform vec4 marker;
+uniform float markerTime;
 uniform sampler2D texture1;
+uniform sampler2D normalMap;
+uniform sampler2D shadowMap;
 
-void main()
+//Returns 1.0 for lit fragment and 0.0 for fully shadowed one
+//Shadow map is sampled with 3x3 PCF to soften edges, fragments outside of shadow map are lit
+float calculateShadow()
+{
+    if (lightSpacePosition.w <= 0.0) {
+        return 1.0;
+    }
+
+    vec3 projectedPosition = lightSpacePosition.xyz / lightSpacePosition.w;
+    projectedPosition = projectedPosition * 0.5 + 0.5;
+
+    if (projectedPosition.x < 0.0 || projectedPosition.x > 1.0 || projectedPosition.y < 0.0 || projectedPosition.y > 1.0 || projectedPosition.z > 1.0) {
+        return 1.0;
+    }
+
+    ivec2 shadowMapSize = textureSize(shadowMap, 0);
+    ivec2 texelPosition = ivec2(projectedPosition.xy * vec2(shadowMapSize));
+    float shadow = 0.0;
+
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            ivec2 samplePosition = clamp(texelPosition + ivec2(x, y), ivec2(0), shadowMapSize - 1);
+            float closestDepth = texelFetch(shadowMap, samplePosition, 0).r;
+
+            shadow += projectedPosition.z > closestDepth ? 0.0 : 1.0;
+        }
+    }
+
+    return shadow / 9.0;
+}
+
+//Phong shading of single point light, light position is in world space
+//Shadow only affects direct light, ambient stays so shadowed areas aren't completely black
+vec3 calculatePointLight(Light light, vec3 normal, float shadow)
 {
-    //Phong shading
+    vec3 lightPosition = vec3(view * vec4(light.position, 1.0));
 
     //Ambient
     float ambientStrenght = 0.1;
-    vec3 ambient = ambientStrenght * lightColor;
+    vec3 ambient = ambientStrenght * light.color;
 
     //Diffuse
-    vec3 normal = normalize(normalVector);
     vec3 lightDirection = normalize(lightPosition - fragmentPosition);
     float diff = max(dot(normal, lightDirection), 0.0);
-    vec3 diffuse = diff * lightColor;
+    vec3 diffuse = diff * light.color;
 
     //Specular
     float specularStrength = 0.5;
     vec3 viewDirection = normalize(-fragmentPosition);
-    vec3 reflectDirection = reflect(-lightDirection, normalVector);
+    vec3 reflectDirection = reflect(-lightDirection, normal);
     float spec = pow(max(dot(viewDirection, reflectDirection), 0.0), 64);
-    vec3 specular = specularStrength * spec * lightColor;
+    vec3 specular = specularStrength * spec * light.color;
 
-    //Point light attentuation (from Ogre3D)
+    //Point light attentuation, light fades out quadratically and reaches zero at light radius
     float distance = length(lightPosition - fragmentPosition);
-    float attenuation = 1.0 / (1.0 + 0.8 * distance + 2.4 * (distance * distance));
+    float attenuation = clamp(1.0 - distance / light.radius, 0.0, 1.0);
+    attenuation *= attenuation;
+
+    return (ambient + (diffuse + specular) * shadow) * attenuation;
+}
+
+//Accessibility marker drawn over exit textures, it isn't lit so it stays visible in the dark
+//Pattern 1 is solid outline with pulsing glow (exit), pattern 2 is dashed outline (decoy exits)
+vec3 applyMarker(vec3 color, vec4 marker, float time)
+{
+    if (marker.w < 0.5) {
+        return color;
+    }
+
+    vec2 edgeDistance = min(textureCoords, 1.0 - textureCoords);
+    bool outline = min(edgeDistance.x, edgeDistance.y) < MARKER_OUTLINE_WIDTH;
+
+    if (marker.w < 1.5) {
+        float pulse = 0.5 + 0.5 * sin(time * MARKER_PULSE_SPEED);
+
+        return outline ? marker.rgb : mix(color, marker.rgb, 0.4 * pulse);
+    }
+
+    //Dashes follow the nearest edge
+    float edgePosition = edgeDistance.x < edgeDistance.y ? textureCoords.y : textureCoords.x;
+
+    return (outline && fract(edgePosition * MARKER_DASHES) < 0.5) ? marker.rgb : color;
+}
+
+void main()
+{
+    //Normal from normal map is in tangent space so it's transformed to view space using TBN matrix
+    vec3 normal = normalize(normalVector);
+
+    if (normalMapping == 1) {
+        vec3 tangent = normalize(tangentVector - dot(tangentVector, normal) * normal);
+        vec3 bitangent = cross(normal, tangent);
+        vec3 mappedNormal = texture(normalMap, textureCoords).rgb * 2.0 - 1.0;
+
+        normal = normalize(mat3(tangent, bitangent, normal) * mappedNormal);
+    }
+
+    vec3 lighting = vec3(0.0);
+
+    //Only first light (torch) casts shadows
+    for (int i = 0; i < lightCount; i++) {
+        float shadow = (i == 0 && shadows == 1) ? calculateShadow() : 1.0;
+        lighting += calculatePointLight(lights[i], normal, shadow);
+    }
 
-    ambient *= attenuation;
-    diffuse *= attenuation;
-    specular *= attenuation;
+    vec3 lightResult = lighting * texture(texture1, textureCoords).rgb * tint.rgb;
 
-    vec3 lightResult = (ambient + diffuse + specular) * texture(texture1, textureCoords).rgb;
+    //Gamma adjustment, applied before conversion to sRGB
+    lightResult = pow(lightResult, vec3(1.0 / gamma));
 
-    FragColor = vec4(lightResult, 1.0);
+    FragColor = vec4(applyMarker(lightResult, marker, markerTime), 1.0);
 }
\ No newline at end of file
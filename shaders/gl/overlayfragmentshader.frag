@@ -0,0 +1,19 @@
+#version 330 core
+
+in vec2 textureCoords;
+
+out vec4 FragColor;
+
+uniform vec4 color;
+uniform int useTexture;
+uniform sampler2D texture1;
+
+void main()
+{
+    if (useTexture == 1) {
+        FragColor = texture(texture1, textureCoords) * color;
+    }
+    else {
+        FragColor = color;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+#version 330 core
+
+layout (location = 0) in vec3 aVertexPosition;
+layout (location = 1) in vec2 aTexturePosition;
+
+uniform mat4 transform;
+
+out vec2 textureCoords;
+
+void main()
+{
+    textureCoords = aTexturePosition;
+
+    gl_Position = transform * vec4(aVertexPosition, 1.0f);
+}
\ No newline at end of file
@@ -0,0 +1,13 @@
+#version 330 core
+
+out vec2 textureCoords;
+
+void main()
+{
+    //Fullscreen triangle without vertex buffer, vertices are (-1, -1), (3, -1) and (-1, 3)
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+
+    textureCoords = position;
+
+    gl_Position = vec4(position * 2.0f - 1.0f, 0.0f, 1.0f);
+}
\ No newline at end of file
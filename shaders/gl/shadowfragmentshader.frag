@@ -0,0 +1,6 @@
+#version 330 core
+
+//Only depth is written to shadow map
+void main()
+{
+}
@@ -0,0 +1,12 @@
+#version 330 core
+
+in vec3 textureCoords;
+
+out vec4 FragColor;
+
+uniform samplerCube skybox;
+
+void main()
+{
+    FragColor = texture(skybox, textureCoords);
+}
\ No newline at end of file
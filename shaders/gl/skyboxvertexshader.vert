@@ -0,0 +1,15 @@
+#version 330 core
+
+layout (location = 0) in vec3 aVertexPosition;
+
+uniform mat4 transform;
+
+out vec3 textureCoords;
+
+void main()
+{
+    textureCoords = aVertexPosition;
+
+    //Skybox is always at far plane so it stays behind everything else
+    gl_Position = (transform * vec4(aVertexPosition, 1.0f)).xyww;
+}
\ No newline at end of file
@@ -3,26 +3,28 @@
 layout (location = 0) in vec3 aVertexPosition;
 layout (location = 1) in vec2 aTexturePosition;
 layout (location = 2) in vec3 aNormalAttribute;
+layout (location = 3) in mat4 aModelMatrix;
+layout (location = 7) in vec3 aTangentAttribute;
 
 uniform mat4 projection;
 uniform mat4 view;
-uniform mat4 model;
-
-uniform vec3 lightVector;
+uniform mat4 lightSpaceMatrix;
 
 out vec2 textureCoords;
 out vec3 normalVector;
+out vec3 tangentVector;
 out vec3 fragmentPosition;
-out vec3 lightPosition;
+out vec4 lightSpacePosition;
 
 void main()
 {
     textureCoords = aTexturePosition;
-    normalVector = mat3(transpose(inverse(view * model))) * aNormalAttribute;
+    normalVector = mat3(transpose(inverse(view * aModelMatrix))) * aNormalAttribute;
+    tangentVector = mat3(view * aModelMatrix) * aTangentAttribute;
 
     //Change to view space before sending to fragment shader
-    fragmentPosition = vec3(view * model * vec4(aVertexPosition, 1.0));
-    lightPosition = vec3(view * vec4(lightVector, 1.0));
+    fragmentPosition = vec3(view * aModelMatrix * vec4(aVertexPosition, 1.0));
+    lightSpacePosition = lightSpaceMatrix * aModelMatrix * vec4(aVertexPosition, 1.0);
 
-    gl_Position = projection * view * model * vec4(aVertexPosition, 1.0f);
+    gl_Position = projection * view * aModelMatrix * vec4(aVertexPosition, 1.0f);
 }
\ No newline at end of file
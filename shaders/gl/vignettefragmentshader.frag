@@ -0,0 +1,20 @@
+#version 330 core
+
+in vec2 textureCoords;
+
+out vec4 FragColor;
+
+uniform sampler2D scene;
+
+const float vignetteStrength = 0.6f;
+
+void main()
+{
+    vec3 color = texture(scene, textureCoords).rgb;
+
+    //Darken screen towards corners
+    vec2 offset = textureCoords - 0.5f;
+    float vignette = 1.0f - vignetteStrength * smoothstep(0.2f, 0.8f, dot(offset, offset) * 2.0f);
+
+    FragColor = vec4(color * vignette, 1.0f);
+}
\ No newline at end of file
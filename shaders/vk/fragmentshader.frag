@@ -1,47 +1,155 @@
 #version 450 core
 
+#define MAX_LIGHTS 4
+#define MAX_TEXTURES 16
+#define MARKER_OUTLINE_WIDTH 0.06
+#define MARKER_PULSE_SPEED 4.0
+#define MARKER_DASHES 4.0
+
+struct Light {
+    vec3 position;
+    float radius;
+    vec3 color;
+};
+
+layout (binding = 0) uniform UniformBufferObject {
+    mat4 view_matrix;
+    mat4 projection_matrix;
+    Light lights[MAX_LIGHTS];
+    int light_count;
+    float gamma;
+    int normal_mapping;
+    int shadows;
+    mat4 light_space_matrix;
+    vec4 texture_tints[MAX_TEXTURES];
+    vec4 texture_markers[MAX_TEXTURES];
+    float marker_time;
+} ubo;
+
 layout (location = 0) in vec2 textureCoords;
 layout (location = 1) in vec3 normalVector;
 layout (location = 2) in vec3 fragmentPosition;
-layout (location = 3) in vec3 lightPosition;
-layout (location = 4) in vec3 lightColor;
-layout (location = 5) in flat int textureIndex;
+layout (location = 3) in flat int textureIndex;
+layout (location = 4) in vec3 tangentVector;
+layout (location = 5) in vec4 lightSpacePosition;
 
 layout (binding = 1) uniform sampler samp;
-layout (binding = 2) uniform texture2D textures[4];
+layout (binding = 2) uniform texture2D textures[MAX_TEXTURES];
+layout (binding = 3) uniform texture2D normalMaps[MAX_TEXTURES];
+layout (binding = 4) uniform texture2D shadowMap;
 
 layout(location = 0) out vec4 FragColor;
 
-void main()
+//Returns 1.0 for lit fragment and 0.0 for fully shadowed one
+//Shadow map is sampled with 3x3 PCF to soften edges, fragments outside of shadow map are lit
+float calculateShadow()
 {
-    //Phong shading
+    if (lightSpacePosition.w <= 0.0) {
+        return 1.0;
+    }
+
+    //Vulkan depth is already in 0 - 1 range, only XY needs to be mapped
+    vec3 projectedPosition = lightSpacePosition.xyz / lightSpacePosition.w;
+    projectedPosition.xy = projectedPosition.xy * 0.5 + 0.5;
+
+    if (projectedPosition.x < 0.0 || projectedPosition.x > 1.0 || projectedPosition.y < 0.0 || projectedPosition.y > 1.0 || projectedPosition.z > 1.0) {
+        return 1.0;
+    }
+
+    ivec2 shadowMapSize = textureSize(sampler2D(shadowMap, samp), 0);
+    ivec2 texelPosition = ivec2(projectedPosition.xy * vec2(shadowMapSize));
+    float shadow = 0.0;
+
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            ivec2 samplePosition = clamp(texelPosition + ivec2(x, y), ivec2(0), shadowMapSize - 1);
+            float closestDepth = texelFetch(sampler2D(shadowMap, samp), samplePosition, 0).r;
+
+            shadow += projectedPosition.z > closestDepth ? 0.0 : 1.0;
+        }
+    }
+
+    return shadow / 9.0;
+}
+
+//Phong shading of single point light, light position is in world space
+//Shadow only affects direct light, ambient stays so shadowed areas aren't completely black
+vec3 calculatePointLight(Light light, vec3 normal, float shadow)
+{
+    vec3 lightPosition = vec3(ubo.view_matrix * vec4(light.position, 1.0));
 
     //Ambient
     float ambientStrenght = 0.1;
-    vec3 ambient = ambientStrenght * lightColor;
+    vec3 ambient = ambientStrenght * light.color;
 
     //Diffuse
-    vec3 normal = normalize(normalVector);
     vec3 lightDirection = normalize(lightPosition - fragmentPosition);
     float diff = max(dot(normal, lightDirection), 0.0);
-    vec3 diffuse = diff * lightColor;
+    vec3 diffuse = diff * light.color;
 
     //Specular
     float specularStrength = 0.5;
     vec3 viewDirection = normalize(-fragmentPosition);
-    vec3 reflectDirection = reflect(-lightDirection, normalVector);
+    vec3 reflectDirection = reflect(-lightDirection, normal);
     float spec = pow(max(dot(viewDirection, reflectDirection), 0.0), 64);
-    vec3 specular = specularStrength * spec * lightColor;
+    vec3 specular = specularStrength * spec * light.color;
 
-    //Point light attentuation
+    //Point light attentuation, light fades out quadratically and reaches zero at light radius
     float distance = length(lightPosition - fragmentPosition);
-    float attenuation = 1.0 / (1.0 + 0.8 * distance + 2.4 * (distance * distance));
+    float attenuation = clamp(1.0 - distance / light.radius, 0.0, 1.0);
+    attenuation *= attenuation;
+
+    return (ambient + (diffuse + specular) * shadow) * attenuation;
+}
+
+//Accessibility marker drawn over exit textures, it isn't lit so it stays visible in the dark
+//Pattern 1 is solid outline with pulsing glow (exit), pattern 2 is dashed outline (decoy exits)
+vec3 applyMarker(vec3 color, vec4 marker, float time)
+{
+    if (marker.w < 0.5) {
+        return color;
+    }
+
+    vec2 edgeDistance = min(textureCoords, 1.0 - textureCoords);
+    bool outline = min(edgeDistance.x, edgeDistance.y) < MARKER_OUTLINE_WIDTH;
+
+    if (marker.w < 1.5) {
+        float pulse = 0.5 + 0.5 * sin(time * MARKER_PULSE_SPEED);
+
+        return outline ? marker.rgb : mix(color, marker.rgb, 0.4 * pulse);
+    }
+
+    //Dashes follow the nearest edge
+    float edgePosition = edgeDistance.x < edgeDistance.y ? textureCoords.y : textureCoords.x;
+
+    return (outline && fract(edgePosition * MARKER_DASHES) < 0.5) ? marker.rgb : color;
+}
+
+void main()
+{
+    //Normal from normal map is in tangent space so it's transformed to view space using TBN matrix
+    vec3 normal = normalize(normalVector);
+
+    if (ubo.normal_mapping == 1) {
+        vec3 tangent = normalize(tangentVector - dot(tangentVector, normal) * normal);
+        vec3 bitangent = cross(normal, tangent);
+        vec3 mappedNormal = texture(sampler2D(normalMaps[textureIndex], samp), textureCoords).rgb * 2.0 - 1.0;
+
+        normal = normalize(mat3(tangent, bitangent, normal) * mappedNormal);
+    }
+
+    vec3 lighting = vec3(0.0);
+
+    //Only first light (torch) casts shadows
+    for (int i = 0; i < ubo.light_count; i++) {
+        float shadow = (i == 0 && ubo.shadows == 1) ? calculateShadow() : 1.0;
+        lighting += calculatePointLight(ubo.lights[i], normal, shadow);
+    }
 
-    ambient *= attenuation;
-    diffuse *= attenuation;
-    specular *= attenuation;
+    vec3 fragmentResult = lighting * texture(sampler2D(textures[textureIndex], samp), textureCoords).rgb * ubo.texture_tints[textureIndex].rgb;
 
-    vec3 fragmentResult = (ambient + diffuse + specular) * texture(sampler2D(textures[textureIndex], samp), textureCoords).rgb;
+    //Gamma adjustment, applied before conversion to sRGB
+    fragmentResult = pow(fragmentResult, vec3(1.0 / ubo.gamma));
 
-    FragColor = vec4(fragmentResult, 1.0);
+    FragColor = vec4(applyMarker(fragmentResult, ubo.texture_markers[textureIndex], ubo.marker_time), 1.0);
 }
\ No newline at end of file
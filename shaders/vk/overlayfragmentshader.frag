@@ -0,0 +1,25 @@
+#version 450 core
+
+layout (push_constant) uniform constants
+{
+    mat4 transform;
+    vec4 color;
+    int texture_index;
+} pcs;
+
+layout (location = 0) in vec2 textureCoords;
+
+layout (binding = 1) uniform sampler samp;
+layout (binding = 2) uniform texture2D textures[16];
+
+layout(location = 0) out vec4 FragColor;
+
+void main()
+{
+    if (pcs.texture_index < 0) {
+        FragColor = pcs.color;
+    }
+    else {
+        FragColor = texture(sampler2D(textures[pcs.texture_index], samp), textureCoords) * pcs.color;
+    }
+}
\ No newline at end of file
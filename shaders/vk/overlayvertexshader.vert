@@ -0,0 +1,20 @@
+#version 450 core
+
+layout (push_constant) uniform constants
+{
+    mat4 transform;
+    vec4 color;
+    int texture_index;
+} pcs;
+
+layout (location = 0) in vec3 aVertexPosition;
+layout (location = 2) in vec2 aTexturePosition;
+
+layout (location = 0) out vec2 textureCoords;
+
+void main()
+{
+    textureCoords = aTexturePosition;
+
+    gl_Position = pcs.transform * vec4(aVertexPosition, 1.0f);
+}
\ No newline at end of file
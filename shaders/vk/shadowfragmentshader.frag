@@ -0,0 +1,6 @@
+#version 450 core
+
+//Only depth is written to shadow map
+void main()
+{
+}
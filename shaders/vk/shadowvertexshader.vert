@@ -0,0 +1,32 @@
+#version 450 core
+
+#define MAX_LIGHTS 4
+#define MAX_TEXTURES 16
+
+struct Light {
+    vec3 position;
+    float radius;
+    vec3 color;
+};
+
+layout (binding = 0) uniform UniformBufferObject {
+    mat4 view_matrix;
+    mat4 projection_matrix;
+    Light lights[MAX_LIGHTS];
+    int light_count;
+    float gamma;
+    int normal_mapping;
+    int shadows;
+    mat4 light_space_matrix;
+    vec4 texture_tints[MAX_TEXTURES];
+    vec4 texture_markers[MAX_TEXTURES];
+    float marker_time;
+} ubo;
+
+layout (location = 0) in vec3 aVertexPosition;
+layout (location = 3) in mat4 aModelMatrix;
+
+void main()
+{
+    gl_Position = ubo.light_space_matrix * aModelMatrix * vec4(aVertexPosition, 1.0f);
+}
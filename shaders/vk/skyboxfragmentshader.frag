@@ -0,0 +1,12 @@
+#version 450 core
+
+layout (location = 0) in vec3 textureCoords;
+
+layout (binding = 1) uniform samplerCube skybox;
+
+layout(location = 0) out vec4 FragColor;
+
+void main()
+{
+    FragColor = texture(skybox, textureCoords);
+}
\ No newline at end of file
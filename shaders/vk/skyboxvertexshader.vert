@@ -0,0 +1,17 @@
+#version 450 core
+
+layout (binding = 0) uniform UniformBufferObject {
+    mat4 transform;
+} ubo;
+
+layout (location = 0) in vec3 aVertexPosition;
+
+layout (location = 0) out vec3 textureCoords;
+
+void main()
+{
+    textureCoords = aVertexPosition;
+
+    //Skybox is always at far plane so it stays behind everything else
+    gl_Position = (ubo.transform * vec4(aVertexPosition, 1.0f)).xyww;
+}
\ No newline at end of file
@@ -1,40 +1,53 @@
 #version 450 core
 
-layout (push_constant) uniform constants
-{
-	mat4 model_matrix;
-    int texture_index;
-} pcs;
+#define MAX_LIGHTS 4
+#define MAX_TEXTURES 16
+
+struct Light {
+    vec3 position;
+    float radius;
+    vec3 color;
+};
 
 layout (binding = 0) uniform UniformBufferObject {
     mat4 view_matrix;
     mat4 projection_matrix;
-    vec3 light_positon;
-    vec3 light_color;
+    Light lights[MAX_LIGHTS];
+    int light_count;
+    float gamma;
+    int normal_mapping;
+    int shadows;
+    mat4 light_space_matrix;
+    vec4 texture_tints[MAX_TEXTURES];
+    vec4 texture_markers[MAX_TEXTURES];
+    float marker_time;
 } ubo;
 
 layout (location = 0) in vec3 aVertexPosition;
 layout (location = 1) in vec3 aNormalAttribute;
 layout (location = 2) in vec2 aTexturePosition;
+layout (location = 3) in mat4 aModelMatrix;
+layout (location = 7) in int aTextureIndex;
+layout (location = 8) in vec3 aTangentAttribute;
 
 layout (location = 0) out vec2 textureCoords;
 layout (location = 1) out vec3 normalVector;
 layout (location = 2) out vec3 fragmentPosition;
-layout (location = 3) out vec3 lightPosition;
-layout (location = 4) out vec3 lightColor;
-layout (location = 5) out flat int textureIndex;
+layout (location = 3) out flat int textureIndex;
+layout (location = 4) out vec3 tangentVector;
+layout (location = 5) out vec4 lightSpacePosition;
 
 void main()
 {
     textureCoords = aTexturePosition;
-    normalVector = mat3(transpose(inverse(ubo.view_matrix * pcs.model_matrix))) * aNormalAttribute;
+    normalVector = mat3(transpose(inverse(ubo.view_matrix * aModelMatrix))) * aNormalAttribute;
+    tangentVector = mat3(ubo.view_matrix * aModelMatrix) * aTangentAttribute;
 
     //Change to view space before sending to fragment shader
-    fragmentPosition = vec3(ubo.view_matrix * pcs.model_matrix * vec4(aVertexPosition, 1.0));
-    lightPosition = vec3(ubo.view_matrix * vec4(ubo.light_positon, 1.0));
-    lightColor = ubo.light_color;
+    fragmentPosition = vec3(ubo.view_matrix * aModelMatrix * vec4(aVertexPosition, 1.0));
+    lightSpacePosition = ubo.light_space_matrix * aModelMatrix * vec4(aVertexPosition, 1.0);
 
-    textureIndex = pcs.texture_index;
+    textureIndex = aTextureIndex;
 
-    gl_Position = ubo.projection_matrix * ubo.view_matrix * pcs.model_matrix * vec4(aVertexPosition, 1.0f);
+    gl_Position = ubo.projection_matrix * ubo.view_matrix * aModelMatrix * vec4(aVertexPosition, 1.0f);
 }
\ No newline at end of file
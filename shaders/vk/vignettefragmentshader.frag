@@ -0,0 +1,21 @@
+#version 450 core
+
+layout (location = 0) in vec2 textureCoords;
+
+//Scene is read from previous subpass, only pixel at fragment position can be loaded
+layout (input_attachment_index = 0, binding = 1) uniform subpassInput scene;
+
+layout (location = 0) out vec4 FragColor;
+
+const float vignetteStrength = 0.6f;
+
+void main()
+{
+    vec3 color = subpassLoad(scene).rgb;
+
+    //Darken screen towards corners
+    vec2 offset = textureCoords - 0.5f;
+    float vignette = 1.0f - vignetteStrength * smoothstep(0.2f, 0.8f, dot(offset, offset) * 2.0f);
+
+    FragColor = vec4(color * vignette, 1.0f);
+}
\ No newline at end of file
@@ -0,0 +1,60 @@
+//Scripted camera following the shortest path from start to exit, used for demos and comparing renderers
+//Camera position depends only on travelled distance so the same maze gives the same frames every time
+
+use dsdmaze::maze_generator::PointU32;
+
+const FLYTHROUGH_SPEED: f32 = 2.0; //Fields per second
+const LOOK_AHEAD_DISTANCE: f32 = 1.5; //Camera looks at point of path in front of it so it starts turning before corners
+
+pub struct Flythrough {
+    path: Vec<glm::Vec2>,
+    distance: f32 //Distance travelled from start, every step between neighbouring fields is one
+}
+
+impl Flythrough {
+    pub fn new(path: &[PointU32]) -> Self {
+        Self {
+            path: path.iter().map(|field| glm::vec2(field.0 as f32, field.1 as f32)).collect(),
+            distance: 0.0
+        }
+    }
+
+    pub fn update(&mut self, time_step: f32) {
+        self.distance = f32::min(self.distance + FLYTHROUGH_SPEED * time_step, self.get_length());
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.distance >= self.get_length()
+    }
+
+    //Position on the ground, x and z coordinates of camera
+    pub fn get_position(&self) -> glm::Vec2 {
+        self.get_point(self.distance)
+    }
+
+    //Yaw in degrees looking along the path, the last step of path is used at its end
+    pub fn get_yaw(&self) -> f32 {
+        let mut direction = self.get_point(self.distance + LOOK_AHEAD_DISTANCE) - self.get_position();
+
+        if direction.norm() < 0.01 && self.path.len() > 1 {
+            direction = self.path[self.path.len() - 1] - self.path[self.path.len() - 2];
+        }
+
+        direction.y.atan2(direction.x).to_degrees()
+    }
+
+    fn get_length(&self) -> f32 {
+        self.path.len().saturating_sub(1) as f32
+    }
+
+    //Point on path after travelling selected distance, path is straight between neighbouring fields
+    fn get_point(&self, distance: f32) -> glm::Vec2 {
+        let distance = f32::clamp(distance, 0.0, self.get_length());
+        let index = distance.floor() as usize;
+
+        match self.path.get(index + 1) {
+            Some(next_point) => glm::lerp(&self.path[index], next_point, distance - index as f32),
+            None => self.path[index]
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//Saved game session that can be resumed later
+//Maze is generated again from seed and generation options so only they and player state are stored
+
+use std::{error::Error, fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use dsdmaze::maze_generator::{SelectedGenerator, StartPlacement};
+
+use crate::program_config::ProgramConfig;
+
+const SESSION_FILE_NAME: &str = "session.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct GameSession {
+    seed: String,
+    generator: SelectedGenerator,
+    maze_size: usize,
+    memory_budget: usize,
+    #[serde(default = "default_threads")]
+    threads: usize, //Parallel RD generator creates different maze than serial one
+    rooms: usize,
+    weave_crossings: usize,
+    decoy_exits: usize,
+    wall_variety: usize,
+    start_placement: StartPlacement,
+    load_maze: Option<String>, //Maze file is loaded again instead of generating maze
+    #[serde(default)]
+    load_ascii: Option<String>,
+    pub camera_position: [f32; 3],
+    pub camera_yaw: f32,
+    pub camera_pitch: f32,
+    pub play_time: f32,
+    pub steps: u32
+}
+
+impl GameSession {
+    pub fn new(config: &ProgramConfig, camera_position: glm::Vec3, camera_yaw: f32, camera_pitch: f32, play_time: f32, steps: u32) -> Self {
+        Self {
+            seed: config.seed.clone(),
+            generator: config.selected_generator,
+            maze_size: config.maze_size,
+            memory_budget: config.memory_budget,
+            threads: config.threads,
+            rooms: config.rooms,
+            weave_crossings: config.weave_crossings,
+            decoy_exits: config.decoy_exits,
+            wall_variety: config.wall_variety,
+            start_placement: config.start_placement,
+            load_maze: config.load_maze.clone(),
+            load_ascii: config.load_ascii.clone(),
+            camera_position: [camera_position.x, camera_position.y, camera_position.z],
+            camera_yaw,
+            camera_pitch,
+            play_time,
+            steps
+        }
+    }
+
+    //Replace options that affect generated maze so the same maze is created again
+    pub fn apply_to_config(&self, config: &mut ProgramConfig) {
+        config.seed = self.seed.clone();
+        config.selected_generator = self.generator;
+        config.maze_size = self.maze_size;
+        config.memory_budget = self.memory_budget;
+        config.threads = self.threads;
+        config.rooms = self.rooms;
+        config.weave_crossings = self.weave_crossings;
+        config.decoy_exits = self.decoy_exits;
+        config.wall_variety = self.wall_variety;
+        config.start_placement = self.start_placement;
+        config.load_maze = self.load_maze.clone();
+        config.load_ascii = self.load_ascii.clone();
+    }
+}
+
+//Sessions saved before parallel generation was added used serial generator
+fn default_threads() -> usize {
+    1
+}
+
+//Session is stored next to config file, in portable mode (or with INI preset) in working directory
+pub fn get_session_file_path(config_file_path: &Option<PathBuf>) -> PathBuf {
+    match config_file_path.as_ref().and_then(|config_path| config_path.parent()) {
+        Some(config_dir) => config_dir.join(SESSION_FILE_NAME),
+        None => PathBuf::from(SESSION_FILE_NAME)
+    }
+}
+
+pub fn save_session(session_path: &Path, session: &GameSession) -> Result<(), Box<dyn Error>> {
+    fs::write(session_path, serde_json::to_string_pretty(session)?)?;
+
+    Ok(())
+}
+
+pub fn load_session(session_path: &Path) -> Result<GameSession, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(session_path)?)?)
+}
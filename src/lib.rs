@@ -0,0 +1,3 @@
+//Maze generation without renderer, used by dsdmaze binary and available for other programs
+//Generate maze with MazeGenerator and read it with is_wall(x, y) or cells() which goes through all fields row by row, true means wall
+pub mod maze_generator;
@@ -3,332 +3,1580 @@ extern crate gl;
 extern crate image;
 extern crate nalgebra_glm as glm;
 
-use std::{fs, cmp, env};
+use std::{fs, cmp, env, io, mem, process, thread};
+use std::sync::mpsc;
+use std::io::Write;
+use std::str::FromStr;
+use std::error::Error;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::*;
-use maze_renderer::RenderingAPI;
+use maze_renderer::{ColorSpace, PostEffect, RenderingAPI, TextureDescriptor, TextureFilter};
 use rand::{thread_rng, Rng};
 use rand::distributions::Alphanumeric;
 
-use ini::Ini;
 
-mod maze_generator;
+use arboard::Clipboard;
+
+use gilrs::{Axis, EventType, Gilrs};
+
+mod flythrough;
+mod game_session;
+mod maze_geometry;
 mod maze_renderer;
+mod program_config;
+
+use winit::dpi::{LogicalSize, PhysicalPosition};
+use winit::event::{DeviceEvent, Event, KeyEvent, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{EventLoop, EventLoopWindowTarget};
+use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
+use winit::monitor::MonitorHandle;
+use winit::window::{CursorGrabMode, Fullscreen, Icon, Window, WindowBuilder};
+
+use kira::{
+	dsp::Frame,
+	manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings, StaticSoundHandle},
+	tween::Tween,
+};
+
+use dsdmaze::maze_generator::{Direction, Maze, MazeGenerator, SelectedGenerator, StartPlacement, WeaveField, MAX_WALL_MATERIALS};
+use flythrough::Flythrough;
+use game_session::GameSession;
+use maze_geometry::MazeGeometry;
+use program_config::{parse_color, parse_hex_color, MinimapReveal, ProgramConfig, DEFAULT_COLLISION_MARGIN};
+
+use crate::maze_renderer::gl_renderer::GLRenderer;
+use crate::maze_renderer::vulkan_renderer::VulkanRenderer;
+use crate::maze_renderer::{Light, MazeRenderer, RenderResult, Renderer, RendererError, UniformData, MAX_LIGHTS, SKYBOX_FACES, SKYBOX_MESH_NAME};
+
+                                    //Vertex position   //Texture UV    //Normal vector     //Tangent vector
+static VERTEX_DATA: [f32; 44] =   [ 0.5,  0.5, 0.0,     1.0, 1.0,       0.0, 0.0, -1.0,     1.0, 0.0, 0.0,
+                                    0.5, -0.5, 0.0,     1.0, 0.0,       0.0, 0.0, -1.0,     1.0, 0.0, 0.0,
+                                    -0.5, -0.5, 0.0,    0.0, 0.0,       0.0, 0.0, -1.0,     1.0, 0.0, 0.0,
+                                    -0.5,  0.5, 0.0,    0.0, 1.0,       0.0, 0.0, -1.0,     1.0, 0.0, 0.0];
+
+static VERTEX_INDICES: [u32; 6] = [0, 1, 3, //First triangle
+                                   1, 2, 3]; //Second triangle
+
+//Allowed gamma range (also used for runtime adjustment)
+const MIN_GAMMA: f32 = 0.5;
+const MAX_GAMMA: f32 = 3.0;
+
+const MIN_WALL_HEIGHT: f32 = 0.25;
+const MAX_WALL_HEIGHT: f32 = 10.0;
+
+const EXIT_LIGHT_RADIUS: f32 = 3.0;
+
+//Physics simulates at most this much time per frame (number of steps depends on time step), time above it is dropped so game slows down instead of jumping
+const MAX_SIMULATED_TIME: f32 = 0.25;
+
+//Single frame longer than this after normal frames means program was stalled (for example by dragging window) and it isn't simulated at all
+//Consecutive long frames are slow machine and they are simulated up to MAX_SIMULATED_TIME
+const MAX_FRAME_TIME: f32 = 0.25;
+
+//Camera rotation in degrees per pixel of mouse movement
+const MOUSE_SENSITIVITY: f32 = 0.1;
+
+//Mouse smoothing factor is part of remaining mouse movement kept after this time, so smoothing doesn't depend on frame rate
+const MOUSE_SMOOTHING_TIME: f32 = 1.0 / 60.0;
+
+//Walking speed in fields per second
+const MOVEMENT_SPEED: f32 = 1.4;
+
+//Player can't step up or down more than this, so bridge and tunnel of weave crossing are separated
+const MAX_STEP_HEIGHT: f32 = 0.5;
+
+const DEFAULT_WEAVE_CROSSINGS: usize = 10;
+
+//Overview camera is placed above the highest possible walls, margin leaves some space around the maze
+const OVERVIEW_HEIGHT: f32 = MAX_WALL_HEIGHT + 10.0;
+const OVERVIEW_MARGIN: f32 = 1.1;
+
+//Shortest path isn't searched in bigger mazes, search would need too much memory and time
+const MAX_SHORTEST_PATH_MAZE_SIZE: usize = 2000;
+
+//Cells drawn on minimap in every direction from the player
+const MINIMAP_RADIUS: usize = 10;
+
+//Cells revealed on minimap in every direction from the player with -minimap-reveal=explored
+const MINIMAP_EXPLORE_RADIUS: usize = 1;
+
+//Flythrough frames are captured with fixed frame time so recording doesn't depend on speed of computer
+const FLYTHROUGH_CAPTURE_FRAME_TIME: f32 = 1.0 / 60.0;
+const FLYTHROUGH_FRAMES_DIR: &str = "flythrough";
+
+//Loading screen is redrawn with this interval while maze is generated
+const LOADING_SCREEN_FRAME_TIME: Duration = Duration::from_millis(16);
+
+//Distance of debug overlay from top left corner of the screen in pixels
+const DEBUG_OVERLAY_MARGIN: f32 = 10.0;
+
+//Collision debug lines, tested points are drawn as squares of given size in maze units
+const DEBUG_COLLISION_COLOR: [f32; 4] = [1.0, 1.0, 0.0, 1.0];
+const DEBUG_COLLISION_HIT_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const DEBUG_COLLISION_POINT_COLOR: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+const DEBUG_COLLISION_POINT_SIZE: f32 = 0.05;
+
+//Gamepad sticks, look speed is in degrees per second at full tilt
+const GAMEPAD_DEAD_ZONE: f32 = 0.2;
+const GAMEPAD_LOOK_SPEED: f32 = 120.0;
+
+//Mouse wheel zoom lasts given time in seconds after last scroll, zoomed field of view doesn't go below minimum
+const ZOOM_DURATION: f32 = 1.5;
+const MIN_ZOOM_FOV: f32 = 10.0;
+
+//Physics and input work only while playing
+#[derive(Copy, Clone, PartialEq)]
+enum GameState {
+    Playing,
+    Paused,
+    Escaped(f32) //Completion time in seconds
+}
+
+//Statistics of current run shown on win screen, step is move from one cell to neighbouring one
+struct GameStats {
+    steps: u32,
+    current_cell: (i32, i32)
+}
+
+impl GameStats {
+    fn new(position: glm::Vec3) -> Self {
+        Self {
+            steps: 0,
+            current_cell: get_player_cell(position)
+        }
+    }
+
+    //Count steps when player gets into another cell, moving diagonally through corner of cell counts as two steps
+    fn update(&mut self, position: glm::Vec3) {
+        let cell = get_player_cell(position);
+
+        self.steps += cell.0.abs_diff(self.current_cell.0) + cell.1.abs_diff(self.current_cell.1);
+        self.current_cell = cell;
+    }
+}
+
+//Cells player has been near to, minimap shows only them with -minimap-reveal=explored
+//Every cell is one bit like in maze grid, mask is counted in memory budget
+struct ExploredCells {
+    cells: Vec<u64>,
+    maze_size: usize
+}
+
+impl ExploredCells {
+    fn new(maze_generator: &MazeGenerator, position: glm::Vec3) -> Self {
+        let maze_size = maze_generator.get_maze_size();
+        let mut explored_cells = Self {
+            cells: vec![0; (maze_size * maze_size).div_ceil(64)],
+            maze_size
+        };
+
+        explored_cells.update(maze_generator, position);
+
+        explored_cells
+    }
+
+    fn update(&mut self, maze_generator: &MazeGenerator, position: glm::Vec3) {
+        let (columns, rows) = maze_generator.get_area(position.x, position.z, MINIMAP_EXPLORE_RADIUS);
+
+        for i in rows {
+            for j in columns.clone() {
+                let index = i * self.maze_size + j;
+
+                self.cells[index / 64] |= 1 << (index % 64);
+            }
+        }
+    }
+
+    fn is_explored(&self, x: usize, y: usize) -> bool {
+        let index = y * self.maze_size + x;
+
+        self.cells[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn estimate_memory(maze_size: usize) -> usize {
+        maze_size.saturating_mul(maze_size).div_ceil(64).saturating_mul(mem::size_of::<u64>())
+    }
+}
+
+//Audio manager with loaded sounds, game runs without audio when it can't be created
+struct GameAudio {
+    audio_manager: AudioManager,
+    step_sound_data: StaticSoundData,
+    ambience_sound_data: StaticSoundData
+}
+
+impl GameAudio {
+    fn new(assets_path: &Path) -> Result<Self, String> {
+        let audio_manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default())
+            .map_err(|error| format!("audio device couldn't be initialized: {}", error))?;
+
+        Ok(Self {
+            audio_manager,
+            step_sound_data: load_sound(&assets_path.join("steps.wav"))?,
+            ambience_sound_data: load_sound(&assets_path.join("ambience.ogg"))?
+        })
+    }
+}
+
+//Load looped sound
+fn load_sound(sound_path: &Path) -> Result<StaticSoundData, String> {
+    if !sound_path.is_file() {
+        return Err(format!("sound file {} is missing", sound_path.display()));
+    }
+
+    StaticSoundData::from_file(sound_path, StaticSoundSettings::new().loop_region(0.0..))
+        .map_err(|error| format!("sound file {} couldn't be loaded: {}", sound_path.display(), error))
+}
+
+fn generate_random_seed() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(30)
+        .map(char::from)
+        .collect()
+}
+
+//Cell player is in, cells are centered on integer coordinates
+fn get_player_cell(position: glm::Vec3) -> (i32, i32) {
+    ((position.x + 0.5).floor() as i32, (position.z + 0.5).floor() as i32)
+}
+
+//Seed made from current unix timestamp, the same timestamp gives the same maze
+fn generate_time_seed() -> String {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().to_string()
+}
+
+//Load maze saved as JSON
+fn load_maze(maze_path: &str) -> Result<MazeGenerator, Box<dyn Error>> {
+    let maze: Maze = serde_json::from_str(&fs::read_to_string(maze_path)?)?;
+
+    Ok(MazeGenerator::from_maze(maze)?)
+}
+
+//Load maze drawn as text
+fn load_ascii_maze(maze_path: &str) -> Result<MazeGenerator, Box<dyn Error>> {
+    Ok(MazeGenerator::from_ascii(&fs::read_to_string(maze_path)?)?)
+}
+
+//Generate maze and its geometry from seed and generation options in config
+//Maze file from config is used instead if it's set, generation progress is reported to callback
+fn create_maze(program_config: &ProgramConfig, progress: &mut dyn FnMut(f32)) -> (MazeGenerator, MazeGeometry) {
+    //JSON maze file is used when both JSON and text maze files are set
+    let maze_file = match (&program_config.load_maze, &program_config.load_ascii) {
+        (Some(maze_path), _) => Some((maze_path, false)),
+        (None, Some(maze_path)) => Some((maze_path, true)),
+        (None, None) => None
+    };
+
+    let maze_generator = match maze_file {
+        Some((maze_path, is_ascii)) => {
+            println!();
+            println!("Loading maze from {}", maze_path);
+            println!();
+
+            let loaded_maze = match is_ascii {
+                true => load_ascii_maze(maze_path),
+                false => load_maze(maze_path)
+            };
+
+            match loaded_maze {
+                Ok(mut maze_generator) => {
+                    //Textures are loaded only for wall variety from config, not for materials of loaded maze
+                    if maze_generator.limit_wall_materials(program_config.wall_variety) {
+                        println!("Warning: Maze uses more wall materials than wall variety {}, default wall texture is used for the rest.", program_config.wall_variety);
+                    }
+
+                    maze_generator
+                },
+                Err(error) => {
+                    println!("Error: Loading maze from {} failed: {}", maze_path, error);
+                    process::exit(1);
+                }
+            }
+        },
+        None => {
+            //Same seed with the same options generates the same maze
+            //Seed with spaces is quoted in the argument so it can be pasted to shell
+            let seed_argument = match program_config.seed.contains(char::is_whitespace) {
+                true => format!("-seed=\"{}\"", program_config.seed),
+                false => format!("-seed={}", program_config.seed)
+            };
+
+            println!();
+            println!("Seed: {}", program_config.seed);
+            println!("Use {} to play this maze again (press C to copy seed to clipboard).", seed_argument);
+            println!();
+
+            let (maze_size, memory_budget) = fit_maze_to_memory_budget(program_config);
+
+            let mut maze_generator = MazeGenerator::new(program_config.selected_generator, maze_size, program_config.seed.clone());
+            maze_generator.set_memory_budget(memory_budget);
+            maze_generator.set_threads(program_config.threads);
+            maze_generator.set_start_placement(program_config.start_placement);
+            let warnings = maze_generator.generate_maze_with_progress(progress);
+            println!();
+
+            for warning in warnings {
+                println!("Warning: {}", warning);
+            }
+
+            maze_generator.carve_rooms(program_config.rooms);
+            maze_generator.assign_wall_materials(program_config.wall_variety);
+            maze_generator.add_weave_crossings(program_config.weave_crossings);
+            maze_generator.add_decoy_exits(program_config.decoy_exits);
+
+            maze_generator
+        }
+    };
+
+    let maze_geometry = MazeGeometry::new(!program_config.no_ceiling, program_config.wall_height);
+
+    (maze_generator, maze_geometry)
+}
+
+//Explored cells mask is kept for every cell next to the maze so it's taken from memory budget before generation
+//Maze size is lowered until generator estimate and the mask fit, generator gets budget left after the mask
+fn fit_maze_to_memory_budget(program_config: &ProgramConfig) -> (usize, usize) {
+    let memory_budget = program_config.memory_budget * 1024 * 1024;
+
+    if program_config.minimap_reveal == MinimapReveal::Full {
+        return (program_config.maze_size, memory_budget);
+    }
+
+    let needed_memory = |maze_size: usize| program_config.selected_generator.estimate_memory(maze_size).saturating_add(ExploredCells::estimate_memory(maze_size));
+    let mut maze_size = usize::min(program_config.maze_size, program_config.selected_generator.get_maximum_size(memory_budget));
+
+    while maze_size > 0 && needed_memory(maze_size) > memory_budget {
+        maze_size -= 1;
+    }
+
+    if maze_size < program_config.maze_size {
+        println!("Warning: Maze size {} with explored cells mask needs up to {} MB which is over memory budget of {} MB, using {} instead.", 
+            program_config.maze_size, needed_memory(program_config.maze_size) / (1024 * 1024), program_config.memory_budget, maze_size);
+    }
+
+    (maze_size, memory_budget - ExploredCells::estimate_memory(maze_size))
+}
+
+//Generate maze on separate thread and draw loading screen with its progress until it's finished
+//Events are pumped only to keep window responsive, closing it exits program without waiting for generation
+fn create_maze_with_loading_screen(program_config: &ProgramConfig, event_loop: &mut EventLoop<()>, window: &Window, 
+    maze_renderer: &mut MazeRenderer) -> (MazeGenerator, MazeGeometry) {
+    let (progress_sender, progress_receiver) = mpsc::channel();
+    let mut progress = 0.0;
+
+    thread::scope(|scope| {
+        let generation = scope.spawn(move || create_maze(program_config, &mut |progress| {
+            print_generation_progress(progress);
+            progress_sender.send(progress).ok();
+        }));
+
+        while !generation.is_finished() {
+            let status = event_loop.pump_events(Some(Duration::ZERO), |event, window_target| match event {
+                Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => window_target.exit(),
+                Event::WindowEvent { event: WindowEvent::Resized(new_size), .. } => {
+                    maze_renderer.renderer.resize_viewport(new_size.width, new_size.height);
+                },
+                Event::WindowEvent { event: WindowEvent::RedrawRequested, .. } => {
+                    progress = progress_receiver.try_iter().last().unwrap_or(progress);
+
+                    maze_renderer.renderer.clear_color([0.0, 0.0, 0.0, 1.0]);
+                    maze_renderer.renderer.draw_loading_screen(progress);
+
+                    if let RenderResult::VkOutOfDate = maze_renderer.renderer.render() {
+                        maze_renderer.renderer.resize_viewport(window.inner_size().width, window.inner_size().height);
+                    }
+                },
+                _ => ()
+            });
+
+            if let PumpStatus::Exit(_) = status {
+                println!();
+                maze_renderer.renderer.cleanup();
+                process::exit(0);
+            }
+
+            window.request_redraw();
+            thread::sleep(LOADING_SCREEN_FRAME_TIME);
+        }
+
+        match generation.join() {
+            Ok(maze) => maze,
+            Err(_) => {
+                println!("Error: Maze generation failed.");
+                process::exit(1);
+            }
+        }
+    })
+}
+
+//Generate the biggest maze that fits memory budget with selected generator and report how long it took
+fn run_stress_test(program_config: &ProgramConfig) {
+    let memory_budget = program_config.memory_budget * 1024 * 1024;
+    let maze_size = program_config.selected_generator.get_maximum_size(memory_budget);
+
+    println!();
+    println!("Stress test: {}x{} maze, up to {} MB of {} MB memory budget", maze_size, maze_size, 
+        program_config.selected_generator.estimate_memory(maze_size) / (1024 * 1024), program_config.memory_budget);
+
+    let serial_time = generate_stress_maze(program_config, maze_size, 1);
+
+    //Parallel generation is compared with serial one on maze of the same size
+    if program_config.threads > 1 {
+        match program_config.selected_generator {
+            SelectedGenerator::RD => {
+                let parallel_time = generate_stress_maze(program_config, maze_size, program_config.threads);
+
+                println!("Parallel speedup: {:.2}x", serial_time / parallel_time.max(f64::EPSILON));
+            },
+            _ => println!("Warning: {} generator doesn't have parallel version, -threads is ignored.", program_config.selected_generator)
+        }
+    }
+}
+
+//Progress is printed over the same line, new line has to be printed after generation
+fn print_generation_progress(progress: f32) {
+    print!("\rGenerating maze: {:3.0}%", progress * 100.0);
+    io::stdout().flush().ok();
+}
+
+//Generate maze for stress test on given number of threads, prints and returns generation time in seconds
+fn generate_stress_maze(program_config: &ProgramConfig, maze_size: usize, threads: usize) -> f64 {
+    let mut maze_generator = MazeGenerator::new(program_config.selected_generator, maze_size, program_config.seed.clone());
+    maze_generator.set_memory_budget(program_config.memory_budget * 1024 * 1024);
+    maze_generator.set_threads(threads);
+
+    let time_start = Instant::now();
+    let warnings = maze_generator.generate_maze_with_progress(&mut print_generation_progress);
+    let generation_time = time_start.elapsed().as_secs_f64();
+    println!();
+
+    for warning in warnings {
+        println!("Warning: {}", warning);
+    }
+
+    //Generators can change size (odd size is needed by some of them)
+    let cell_count = maze_generator.get_maze_size() * maze_generator.get_maze_size();
+    let mode = match threads {
+        1 => String::from("serial"),
+        _ => format!("{} threads", threads)
+    };
+
+    println!("Generation time ({}): {:.3} s ({:.1} million cells per second)", mode, generation_time, 
+        cell_count as f64 / generation_time.max(f64::EPSILON) / 1_000_000.0);
+
+    generation_time
+}
+
+//Check collision between point and rectangle
+//Used for checking collision between player and maze walls
+//Margin is distance from wall center, everything over 0.5 keeps camera away from wall so it doesn't look through it
+fn check_collision_point_rectangle(point_x: f32, point_y: f32, wall_x: f32, wall_y: f32, margin: f32) -> bool {
+    if point_x >= wall_x - margin && point_x <= wall_x + margin &&
+        point_y >= wall_y - margin && point_y <= wall_y + margin {
+            return true;
+        }
+        
+    false
+}
+
+//Move player by offset, every axis is checked separately so player can slide along walls
+//Floor is height of floor under the player relative to ground floor
+//Points tested for both axes are returned as (x, z, collided) for collision debug lines
+fn move_with_collisions(position: glm::Vec3, offset: glm::Vec3, floor: f32, collisions_enabled: bool, collision_margin: f32, 
+    maze_generator: &MazeGenerator) -> (glm::Vec3, [(f32, f32, bool); 2]) {
+    let mut new_position = position;
+    let mut tested_points = [(0.0, 0.0, false); 2];
+
+    new_position.x += offset.x;
+
+    let collided_x = collisions_enabled && check_collision(new_position.x, new_position.z, floor, collision_margin, maze_generator);
+    tested_points[0] = (new_position.x, new_position.z, collided_x);
+
+    if collided_x {
+        new_position.x = position.x;
+    }
+
+    new_position.z += offset.z;
+
+    let collided_z = collisions_enabled && check_collision(new_position.x, new_position.z, floor, collision_margin, maze_generator);
+    tested_points[1] = (new_position.x, new_position.z, collided_z);
+
+    if collided_z {
+        new_position.z = position.z;
+    }
+
+    new_position.y += offset.y;
+
+    (new_position, tested_points)
+}
+
+//Height of floor at point inside field relative to ground floor, None for walls
+//Ramps of weave crossings go down by tunnel depth, crossing has floor on the bridge and in the tunnel so the one closer to current floor is used
+fn get_field_floor_height(x: usize, z: usize, point_x: f32, point_z: f32, current_floor: f32, maze_generator: &MazeGenerator) -> Option<f32> {
+    match maze_generator.get_weave_field(x, z) {
+        Some(WeaveField::Ramp(direction)) => {
+            //Distance travelled down the ramp (0 at the top, 1 at the bottom)
+            let ramp_progress = match direction {
+                Direction::Top => z as f32 + 0.5 - point_z,
+                Direction::Bottom => point_z - (z as f32 - 0.5),
+                Direction::Left => x as f32 + 0.5 - point_x,
+                Direction::Right => point_x - (x as f32 - 0.5)
+            };
+
+            Some(-maze_geometry::TUNNEL_DEPTH * f32::clamp(ramp_progress, 0.0, 1.0))
+        },
+        Some(WeaveField::Crossing(_)) => match current_floor < -maze_geometry::TUNNEL_DEPTH * 0.5 {
+            true => Some(-maze_geometry::TUNNEL_DEPTH),
+            false => Some(0.0)
+        },
+        None if maze_generator.is_wall(x, z) => None,
+        //Exit hole leads out of maze so it's blocked like wall
+        None if (x as u32, z as u32) == (maze_generator.get_exit_hole().0, maze_generator.get_exit_hole().1) => None,
+        None => Some(0.0)
+    }
+}
+
+//Height of floor under the player, None outside of maze or inside wall
+fn get_floor_height(player_x: f32, player_z: f32, current_floor: f32, maze_generator: &MazeGenerator) -> Option<f32> {
+    let x = (player_x + 0.5).floor();
+    let z = (player_z + 0.5).floor();
+    let maze_size = maze_generator.get_maze_size() as f32;
+
+    if x < 0.0 || z < 0.0 || x >= maze_size || z >= maze_size {
+        return None;
+    }
+
+    get_field_floor_height(x as usize, z as usize, player_x, player_z, current_floor, maze_generator)
+}
+
+//Change velocity towards target velocity, by acceleration while there is input and by friction when player stops
+//Velocity changes linearly so it reaches target exactly instead of getting closer forever
+fn update_velocity(velocity: glm::Vec3, target_velocity: glm::Vec3, acceleration: f32, friction: f32, time_step: f32) -> glm::Vec3 {
+    let velocity_change = target_velocity - velocity;
+    let change_length = glm::length(&velocity_change);
+
+    let max_change = match glm::length(&target_velocity) > 0.0 {
+        true => acceleration * time_step,
+        false => friction * time_step
+    };
+
+    if change_length <= max_change {
+        return target_velocity;
+    }
+
+    velocity + velocity_change * (max_change / change_length)
+}
+
+//Apply radial dead zone to analog stick and rescale it so movement starts smoothly at the edge of dead zone
+fn apply_dead_zone(x: f32, y: f32) -> (f32, f32) {
+    let magnitude = (x * x + y * y).sqrt();
+
+    if magnitude < GAMEPAD_DEAD_ZONE {
+        return (0.0, 0.0);
+    }
+
+    let scale = f32::min((magnitude - GAMEPAD_DEAD_ZONE) / (1.0 - GAMEPAD_DEAD_ZONE), 1.0) / magnitude;
+
+    (x * scale, y * scale)
+}
+
+//Field blocks player if it's wall or its floor is too high or too low compared to current floor
+fn is_field_blocking(x: usize, z: usize, player_x: f32, player_z: f32, current_floor: f32, maze_generator: &MazeGenerator) -> bool {
+    //Floor is checked at the point of field closest to the player
+    let point_x = f32::clamp(player_x, x as f32 - 0.5, x as f32 + 0.5);
+    let point_z = f32::clamp(player_z, z as f32 - 0.5, z as f32 + 0.5);
+
+    match get_field_floor_height(x, z, point_x, point_z, current_floor, maze_generator) {
+        Some(floor) => (floor - current_floor).abs() > MAX_STEP_HEIGHT,
+        None => true
+    }
+}
+
+//Check collision between player and map
+//Player collides with walls and with fields which floor is too high or too low compared to current floor
+fn check_collision(player_x: f32, player_z: f32, current_floor: f32, collision_margin: f32, maze_generator: &MazeGenerator) -> bool {
+    //Only neighbours of cell player is in can be close enough (margin is always less than 1)
+    let (columns, rows) = maze_generator.get_area(player_x, player_z, 1);
+
+    let mut collision_occured = false;
+    
+    for i in rows {
+        for j in columns.clone() {
+            if !check_collision_point_rectangle(player_x, player_z, j as f32, i as f32, collision_margin) {
+                continue;
+            }
+
+            collision_occured = is_field_blocking(j, i, player_x, player_z, current_floor, maze_generator);
+
+            if collision_occured {
+                break;
+            }
+        }
+
+        if collision_occured {
+            break;
+        }
+    }
+
+    collision_occured
+}
+
+//Generate looping tone played by exit beacon
+//Sine wave with slow pulsing envelope, one second long so it loops without clicking
+fn generate_beacon_sound() -> StaticSoundData {
+    let sample_rate = 44100;
+    let frequency = 440.0;
+    let pulse_frequency = 2.0;
+
+    let frames: Vec<Frame> = (0..sample_rate)
+        .map(|n| {
+            let time = n as f32 / sample_rate as f32;
+            let envelope = 0.5 - 0.5 * (time * pulse_frequency * std::f32::consts::TAU).cos();
+
+            Frame::from_mono((time * frequency * std::f32::consts::TAU).sin() * envelope)
+        })
+        .collect();
+
+    StaticSoundData {
+        sample_rate,
+        frames: frames.into(),
+        settings: StaticSoundSettings::new().loop_region(0.0..)
+    }
+}
+
+//Save frame as PNG file named with current timestamp in working directory
+fn save_screenshot(frame: image::RgbaImage) {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let file_name = format!("dsdmaze_{}.png", timestamp);
+
+    match frame.save(&file_name) {
+        Ok(_) => println!("Screenshot saved to {}", file_name),
+        Err(error) => println!("Error: Saving screenshot failed: {}", error)
+    }
+}
+
+//Save flythrough frame as numbered PNG file, numbering keeps frames in order for making video
+fn save_flythrough_frame(frame: image::RgbaImage, frame_index: u32) {
+    let file_path = Path::new(FLYTHROUGH_FRAMES_DIR).join(format!("frame_{:06}.png", frame_index));
+
+    if let Err(error) = frame.save(&file_path) {
+        println!("Error: Saving flythrough frame {} failed: {}", file_path.display(), error);
+    }
+}
+
+//Texture from texture pack directory if it's selected and contains it, from assets directory otherwise
+fn get_texture_path(texture_name: &str, textures_path: &Option<PathBuf>, assets_path: &Path) -> PathBuf {
+    match textures_path {
+        Some(textures_path) if textures_path.join(texture_name).exists() => textures_path.join(texture_name),
+        _ => assets_path.join(texture_name)
+    }
+}
+
+//Check that textures and shaders needed by selected renderer exist, print every missing file and exit if any is missing
+//Renderers panic when they can't load these files so it's done before renderer is created
+fn check_required_files(assets_path: &Path, textures_path: &Option<PathBuf>, shaders_path: &Path, rendering_api: &RenderingAPI, shadows: bool) {
+    let mut required_files: Vec<PathBuf> = ["wall.png", "floor.png", "ceiling.png", "exit.png", "escaped.png"].iter()
+        .map(|texture_name| get_texture_path(texture_name, textures_path, assets_path))
+        .collect();
+
+    let mut shader_names = vec!["vertexshader", "fragmentshader", "overlayvertexshader", "overlayfragmentshader"];
+
+    if shadows {
+        shader_names.extend(["shadowvertexshader", "shadowfragmentshader"]);
+    }
+
+    for shader_name in shader_names {
+        required_files.push(match rendering_api {
+            RenderingAPI::VULKAN => shaders_path.join("vk").join(format!("{}.spv", shader_name)),
+            RenderingAPI::OPENGL => {
+                let extension = if shader_name.contains("vertex") { "vert" } else { "frag" };
+                shaders_path.join("gl").join(format!("{}.{}", shader_name, extension))
+            }
+        });
+    }
+
+    let missing_files: Vec<&PathBuf> = required_files.iter().filter(|file_path| !file_path.is_file()).collect();
+
+    if missing_files.is_empty() {
+        return;
+    }
+
+    for missing_file in &missing_files {
+        println!("Error: Required file {} is missing.", missing_file.display());
+    }
+
+    if let Some(textures_path) = textures_path {
+        println!("Textures were searched in {} and {}.", textures_path.display(), assets_path.display());
+    }
+
+    println!("Assets and shaders directories have to be placed in the same directory as dsdmaze binary.");
+    process::exit(1);
+}
+
+//Config file is stored in system config directory which is created if it doesn't exist
+//None if directory can't be found or created, program continues with default options then
+fn get_config_file_path() -> Option<PathBuf> {
+    let config_dir = match dirs::config_dir() {
+        Some(config_dir) => config_dir.join("DragonSWDev").join("dsdmaze"),
+        None => {
+            println!("Warning: Couldn't find config directory, using default options.");
+            return None;
+        }
+    };
+
+    if let Err(error) = fs::create_dir_all(&config_dir) {
+        println!("Warning: Couldn't create config directory {} ({}), using default options.", config_dir.display(), error);
+        return None;
+    }
+
+    Some(config_dir.join("dsdmaze.toml"))
+}
+
+//Write current window size and position to config file so next launch uses them
+//Size is saved in logical pixels same as it's used when window is created
+//Position isn't available on every platform (for example on Wayland)
+fn save_window_geometry(config_path: &Path, window: &Window) {
+    let window_size: LogicalSize<u32> = window.inner_size().to_logical(window.scale_factor());
+    let window_position = window.outer_position().ok().map(|position| (position.x, position.y));
+
+    if let Err(error) = program_config::save_window_geometry(config_path, (window_size.width, window_size.height), window_position) {
+        println!("Warning: Couldn't save window size to config file: {}", error);
+    }
+}
+
+//Hide cursor and grab it so mouse look isn't stopped by window border, unsupported grab modes fall back to the next one
+//Returns true when cursor has to be moved back manually after every move (confined cursor can still reach window border)
+//Without any grab cursor is only hidden, camera still rotates by relative mouse motion
+fn grab_cursor(window: &Window) -> bool {
+    window.set_cursor_visible(false);
+
+    if window.set_cursor_grab(CursorGrabMode::Locked).is_ok() {
+        return false;
+    }
+
+    match window.set_cursor_grab(CursorGrabMode::Confined) {
+        Ok(()) => true,
+        Err(error) => {
+            println!("Warning: Cursor can't be grabbed, it's only hidden: {}", error);
+            false
+        }
+    }
+}
+
+//Fullscreen mode used at startup and by F11, exclusive fullscreen uses video mode closest to selected window size
+//Monitor and its video modes can be unknown (for example on Wayland) so borderless fullscreen is used then
+fn select_fullscreen_mode(monitor: Option<MonitorHandle>, program_config: &ProgramConfig) -> Fullscreen {
+    if program_config.exclusive_fullscreen {
+        //Modes with the same size differ in refresh rate, the highest one is preferred
+        let video_mode = monitor.and_then(|monitor| monitor.video_modes().min_by_key(|video_mode| {
+            (video_mode.size().width.abs_diff(program_config.window_width) + video_mode.size().height.abs_diff(program_config.window_height),
+                cmp::Reverse(video_mode.refresh_rate_millihertz()))
+        }));
+
+        match video_mode {
+            Some(video_mode) => return Fullscreen::Exclusive(video_mode),
+            None => println!("Warning: No video mode for exclusive fullscreen found, using borderless fullscreen.")
+        }
+    }
+
+    Fullscreen::Borderless(None)
+}
+
+//Renderer can't draw maze without textures, shaders or mesh so program ends when their setup fails
+fn check_renderer_setup(setup_result: Result<(), RendererError>) {
+    if let Err(error) = setup_result {
+        println!("Error: {}", error);
+        process::exit(1);
+    }
+}
+
+//Create OpenGL renderer, there is nothing to fall back to so program exits if it fails
+fn create_opengl_renderer(window_builder: WindowBuilder, window_target: &EventLoopWindowTarget<()>, vsync_enabled: bool, msaa_samples: u32, 
+    verbose: bool) -> (MazeRenderer, Window) {
+    match GLRenderer::new(window_builder, window_target, vsync_enabled, msaa_samples, verbose) {
+        Ok((opengl_renderer, window)) => (MazeRenderer::new(Box::new(opengl_renderer)), window),
+        Err(error) => {
+            println!("Error: OpenGL initialization failed: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+//Overview camera looks straight down at the center of the maze
+//Orthographic projection is scaled so whole maze fits in the window
+//Returns view and projection matrices and size of one maze cell in pixels
+fn calculate_overview_camera(maze_size: usize, window_width: u32, window_height: u32, rendering_api: &RenderingAPI) -> (glm::Mat4, glm::Mat4, f32) {
+    let maze_center = glm::vec3((maze_size as f32 - 1.0) / 2.0, 0.0, (maze_size as f32 - 1.0) / 2.0);
+
+    //Top of the screen is north (negative Z) like on minimap
+    let view = glm::look_at(&(maze_center + glm::vec3(0.0, OVERVIEW_HEIGHT, 0.0)), &maze_center, &glm::vec3(0.0, 0.0, -1.0));
+
+    let cell_size = window_width.min(window_height) as f32 / (maze_size as f32 * OVERVIEW_MARGIN);
+    let half_width = window_width as f32 / 2.0 / cell_size;
+    let half_height = window_height as f32 / 2.0 / cell_size;
+
+    //Far plane reaches below floor of weave tunnels (floor is 0.5 below ground level)
+    let projection = orthographic_projection(rendering_api, half_width, half_height, 0.1, OVERVIEW_HEIGHT + 0.5 + maze_geometry::TUNNEL_DEPTH + 1.0);
+
+    (view, projection, cell_size)
+}
+
+//OpenGL clip space has depth in -1 to 1 range and Y pointing up, Vulkan has depth in 0 to 1 range and Y pointing down
+//Projection is built for depth range of selected API and Y is inverted on Vulkan so both renderers show the same image with the same winding
+fn perspective_projection(rendering_api: &RenderingAPI, aspect: f32, fov: f32, near_plane: f32, far_plane: f32) -> glm::Mat4 {
+    match rendering_api {
+        RenderingAPI::OPENGL => glm::perspective_rh_no(aspect, fov, near_plane, far_plane),
+        RenderingAPI::VULKAN => invert_projection_y(glm::perspective_rh_zo(aspect, fov, near_plane, far_plane))
+    }
+}
+
+//Symmetric orthographic projection, same conventions as perspective_projection
+fn orthographic_projection(rendering_api: &RenderingAPI, half_width: f32, half_height: f32, near_plane: f32, far_plane: f32) -> glm::Mat4 {
+    match rendering_api {
+        RenderingAPI::OPENGL => glm::ortho_rh_no(-half_width, half_width, -half_height, half_height, near_plane, far_plane),
+        RenderingAPI::VULKAN => invert_projection_y(glm::ortho_rh_zo(-half_width, half_width, -half_height, half_height, near_plane, far_plane))
+    }
+}
+
+fn invert_projection_y(mut projection: glm::Mat4) -> glm::Mat4 {
+    projection[5] *= -1.0; //Invert [1][1] component
+
+    projection
+}
+
+//Projection for lines drawn in world space over the scene, overlay y axis points down like in Vulkan
+fn overlay_projection(rendering_api: &RenderingAPI, projection: glm::Mat4) -> glm::Mat4 {
+    match rendering_api {
+        RenderingAPI::OPENGL => invert_projection_y(projection),
+        RenderingAPI::VULKAN => projection
+    }
+}
+
+//Draw square on the floor around the point
+fn draw_floor_rectangle(renderer: &mut dyn Renderer, transform: glm::Mat4, x: f32, z: f32, half_size: f32, floor: f32, color: [f32; 4]) {
+    let corners = [
+        glm::vec3(x - half_size, floor, z - half_size),
+        glm::vec3(x + half_size, floor, z - half_size),
+        glm::vec3(x + half_size, floor, z + half_size),
+        glm::vec3(x - half_size, floor, z + half_size)
+    ];
+
+    for (start, end) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+        renderer.draw_world_line(transform, *start, *end, color);
+    }
+}
+
+//Calculate exit beacon volume and panning
+//Volume drops with distance between player and exit, panning depends on which side of the player exit is
+fn calculate_beacon_parameters(camera_position: glm::Vec3, camera_front: glm::Vec3, exit_x: f32, exit_z: f32) -> (f64, f64) {
+    let to_exit = glm::vec2(exit_x - camera_position.x, exit_z - camera_position.z);
+    let distance = glm::length(&to_exit);
+    let volume = 0.5 / (1.0 + 0.25 * distance as f64);
+
+    //Camera right vector on XZ plane (cross product of camera front and up vector)
+    let right = glm::vec2(-camera_front.z, camera_front.x);
+
+    if distance < 0.001 || glm::length(&right) < 0.001 {
+        return (volume, 0.5);
+    }
+
+    let side = glm::dot(&glm::normalize(&to_exit), &glm::normalize(&right));
+
+    (volume, 0.5 + 0.5 * side as f64)
+}
+
+//Torch light intensity multiplier for given time
+//Few sine waves with unrelated frequencies give irregular but smooth wobble, same time always gives same value
+fn calculate_torch_flicker(time: f32, amplitude: f32) -> f32 {
+    let wobble = 0.5 * (7.3 * time).sin() + 0.3 * (13.1 * time + 1.7).sin() + 0.2 * (23.9 * time + 0.5).sin();
+
+    1.0 - amplitude * (0.5 + 0.5 * wobble)
+}
+
+//Command line arguments with their descriptions, used for --help and for reporting arguments with missing or unexpected value
+const COMMANDLINE_ARGUMENTS: [(&str, &str); 76] = [
+    ("-width=value", "Window width (default 800)"),
+    ("-height=value", "Window height (default 600)"),
+    ("-size=value", "Maze size (min 10, max limited by -memory-budget, default 20)"),
+    ("-difficulty=value", "Preset of maze options: easy, normal, hard or nightmare, other arguments override it"),
+    ("-memory-budget=value", "Memory in MB maze generation can use (16 - 65536, default 1024)"),
+    ("-threads=value", "Threads for parallel maze generation, used by RD generator (1 - 64, default 1)"),
+    ("-stress", "Generate the biggest maze fitting memory budget, report generation time and exit"),
+    ("-fullscreen", "Run in fullscreen with desktop resolution"),
+    ("-exclusive-fullscreen", "Run in exclusive fullscreen with video mode closest to -width and -height"),
+    ("-disable-collisions", "Disable collisions with walls"),
+    ("-collision-margin=value", "Distance from wall center player is stopped at (0.55 - 0.95, default 0.7)"),
+    ("-generator=value", "Maze generator: RD, DFS, Eller or Wilson (default RD)"),
+    ("-seed=value", "Generator seed, random when not set, time uses current unix timestamp"),
+    ("-start=value", "Start position: random or center (default random)"),
+    ("-rooms=value", "Number of rooms carved into maze (0 - 100, default 0)"),
+    ("-weave[=value]", "Add crossings with bridges and tunnels, optionally with their number (1 - 100, default 10)"),
+    ("-decoys=value", "Number of fake exits that lead nowhere (0 - 20, default 0)"),
+    ("-save-maze=file", "Save generated maze to JSON file"),
+    ("-flythrough", "Camera follows the shortest path to exit and program exits at its end"),
+    ("-flythrough-capture", "Flythrough saving every frame to flythrough directory"),
+    ("-load-maze=file", "Load maze from JSON file instead of generating it"),
+    ("-load-ascii=file", "Load maze drawn as text ('#' wall, space, 'S' start, 'E' exit) instead of generating it"),
+    ("-resume", "Continue session saved with Ctrl+S"),
+    ("-portable", "Don't load or create config file"),
+    ("-config=file", "Load options from selected config file"),
+    ("-title=value", "Window title (default dsdmaze)"),
+    ("-disable-mouse", "Disable mouse control"),
+    ("-free-cursor", "Keep cursor visible and don't grab it with mouse control"),
+    ("-fly-movement", "Looking up or down with mouse slows down walking like in older versions"),
+    ("-mouse-smoothing=value", "Mouse smoothing factor, 0 uses raw input (0 - 0.9, default 0)"),
+    ("-disable-audio", "Disable audio"),
+    ("-gamepad", "Enable gamepad control"),
+    ("-acceleration=value", "Movement acceleration in fields per second squared (1 - 100, default 8)"),
+    ("-friction=value", "Movement deceleration after releasing keys (1 - 100, default 10)"),
+    ("-rendering-api=value", "Rendering API: Vulkan or OpenGL (default Vulkan)"),
+    ("-gpu=index", "GPU used by Vulkan renderer"),
+    ("--list-gpus", "List Vulkan devices with their indices and exit"),
+    ("--version", "Print version and selected rendering API and exit"),
+    ("-debug", "Enable Vulkan validation layers"),
+    ("-verbose", "Print renderer configuration details, on Vulkan also after every swapchain recreation"),
+    ("-disable-vsync", "Disable V-Sync"),
+    ("-triple-buffering", "Use mailbox present mode with V-Sync for lower latency (Vulkan only)"),
+    ("-max-fps=value", "Frame rate cap without V-Sync (10 - 1000, default uncapped)"),
+    ("-msaa=value", "MSAA samples: 1, 2, 4 or 8 (default 4)"),
+    ("-filter=value", "Texture filtering: linear or nearest (default linear)"),
+    ("-timestep=value", "Physics time step in seconds (0.001 - 0.05, default 0.01)"),
+    ("-exit-beacon", "Play a tone from the exit"),
+    ("-exit-marker", "Draw high contrast outline on exit and dashed outline on decoy exits"),
+    ("-noclip", "Start in noclip mode"),
+    ("-wireframe", "Draw maze as wireframe"),
+    ("-debug-collision", "Draw collision rectangles of nearby walls and tested points"),
+    ("-minimap", "Show minimap"),
+    ("-minimap-reveal=value", "Cells shown on minimap (full, explored)"),
+    ("-show-fps", "Show FPS in window title"),
+    ("-textures=dir", "Directory with texture pack, missing textures are loaded from assets"),
+    ("-wall-variety=value", "Number of wall textures (1 - 8, default 1)"),
+    ("-wall-height=value", "Wall height (0.25 - 10.0, default 1.0)"),
+    ("-no-ceiling", "Don't draw ceiling"),
+    ("-sky-color=r,g,b", "Sky color used without ceiling (default 0.4,0.6,0.9)"),
+    ("-skybox=dir", "Directory with skybox faces (right, left, top, bottom, front and back .png) drawn instead of sky color"),
+    ("-wall-tint=RRGGBB", "Color multiplied with wall textures (default FFFFFF)"),
+    ("-floor-tint=RRGGBB", "Color multiplied with floor texture (default FFFFFF)"),
+    ("-ceiling-tint=RRGGBB", "Color multiplied with ceiling texture (default FFFFFF)"),
+    ("-gamma=value", "Gamma correction (0.5 - 3.0, default 1.0)"),
+    ("-fov=value", "Vertical field of view in degrees (30 - 120, default 45)"),
+    ("-zoom=value", "Mouse wheel zoom factor, 1 disables zoom (1 - 8, default 2)"),
+    ("-near-plane=value", "Near clip plane (0.01 - 1.0, default 0.1)"),
+    ("-far-plane=value", "Far clip plane, calculated from render distance by default"),
+    ("-render-distance=value", "Cells drawn around the player (3 - 100, default 10)"),
+    ("-light-radius=value", "Radius of player light (1 - 100, default 8)"),
+    ("-exit-light-color=r,g,b", "Color of exit light (default 0.2,1.0,0.3)"),
+    ("-torch-flicker[=value]", "Make player light flicker, optionally with strength (0.0 - 1.0, default 0.15)"),
+    ("-normal-maps", "Use normal maps for lighting"),
+    ("-shadows", "Player light casts shadows"),
+    ("-post=value", "Post process effect applied to the scene: vignette or none (default none)"),
+    ("--help", "Show this help and exit")
+];
+
+fn print_help() {
+    println!("Usage: dsdmaze [options]\n");
+    println!("Options:");
+
+    for (argument, description) in COMMANDLINE_ARGUMENTS {
+        println!("  {:<26}{}", argument, description);
+    }
+
+    println!("\nCommand line arguments override values from config file.");
+    println!("Unknown arguments and values that aren't numbers where number is expected are reported as error.");
+    println!("Numbers outside of allowed range are replaced with default value.");
+}
+
+//Parse value of numeric argument, value that isn't a number is reported as error instead of being replaced with default
+fn parse_number<T: FromStr>(argument: &str, value: &str) -> Result<T, String> {
+    value.parse::<T>().map_err(|_| format!("Invalid argument {} (value has to be a number)", argument))
+}
+
+//Parse command line arguments and setup program values
+//Arguments have to match exactly, unknown arguments and arguments with missing, unexpected or invalid value are reported as error
+//Numbers outside of allowed range are replaced with defaults later by restrictions
+fn parse_commandline_arguments(arguments: &[String], config: &mut ProgramConfig) -> Result<(), String> {
+    //First argument is program path
+    for argument in arguments.iter().skip(1) {
+        let (name, value) = match argument.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (argument.as_str(), None)
+        };
+
+        match (name, value) {
+            //Window width
+            ("-width", Some(value)) if !value.is_empty() => config.window_width = parse_number::<u32>(argument, value)?,
+
+            //Window height
+            ("-height", Some(value)) if !value.is_empty() => config.window_height = parse_number::<u32>(argument, value)?,
+
+            //Maze size
+            ("-size", Some(value)) if !value.is_empty() => config.maze_size = parse_number::<usize>(argument, value)?,
+            ("-memory-budget", Some(value)) if !value.is_empty() => config.memory_budget = parse_number::<usize>(argument, value)?,
+            ("-threads", Some(value)) if !value.is_empty() => config.threads = parse_number::<usize>(argument, value)?,
+            ("-stress", None) => config.stress = true,
+
+            //Load maze from JSON file instead of generating it
+            ("-load-maze", Some(value)) if !value.is_empty() => config.load_maze = Some(String::from(value)),
+
+            //Load maze drawn as text instead of generating it
+            ("-load-ascii", Some(value)) if !value.is_empty() => config.load_ascii = Some(String::from(value)),
+
+            //Save generated maze to JSON file
+            ("-save-maze", Some(value)) if !value.is_empty() => config.save_maze = Some(String::from(value)),
+
+            //Continue saved session
+            ("-resume", None) => config.resume = true,
+
+            //Scripted camera following the shortest path, optionally saving every frame
+            ("-flythrough", None) => config.flythrough = true,
+            ("-flythrough-capture", None) => {
+                config.flythrough = true;
+                config.flythrough_capture = true;
+            },
+
+            //Generator seed
+            //Seed is used as text so numeric seeds work too, only surrounding whitespace is removed
+            //Seed "time" is replaced with current unix timestamp before maze is generated
+            ("-seed", Some(value)) if !value.is_empty() => config.seed = String::from(value.trim()),
+
+            //Disable collisions (enabled by default)
+            ("-disable-collisions", None) => config.enable_collisions = false,
+
+            //Collision margin
+            ("-collision-margin", Some(value)) if !value.is_empty() => config.collision_margin = parse_number::<f32>(argument, value)?,
+
+            //Enable fullscreen (disabled by default)
+            ("-fullscreen", None) => config.set_fullscreen = true,
+
+            //Enable exclusive fullscreen, selected size is used as requested resolution
+            ("-exclusive-fullscreen", None) => {
+                config.set_fullscreen = true;
+                config.exclusive_fullscreen = true;
+            },
+
+            //Set maze generator
+            ("-generator", Some(value)) => {
+                config.selected_generator = match value {
+                    "DFS" => SelectedGenerator::DFS,
+                    "RD" => SelectedGenerator::RD,
+                    "Eller" => SelectedGenerator::Eller,
+                    "Wilson" => SelectedGenerator::Wilson,
+                    _ => return Err(format!("Unknown generator {} (expected RD, DFS, Eller or Wilson)", value))
+                }
+            },
+
+            //Start position in generated maze
+            ("-start", Some(value)) => {
+                config.start_placement = match value.to_lowercase().as_str() {
+                    "random" => StartPlacement::Random,
+                    "center" => StartPlacement::Center,
+                    _ => return Err(format!("Unknown start position {} (expected random or center)", value))
+                }
+            },
+
+            //Config file is handled before other arguments because values from it are overridden by command line
+            ("-portable", None) => (),
+            ("-config", Some(value)) if !value.is_empty() => (),
 
-use winit::dpi::{LogicalSize, PhysicalPosition};
-use winit::event::{DeviceEvent, Event, KeyEvent, WindowEvent};
-use winit::event_loop::EventLoop;
-use winit::keyboard::{Key, KeyCode, NamedKey, PhysicalKey};
-use winit::window::{Fullscreen, Icon, WindowBuilder};
+            //Difficulty preset is applied before other arguments so they can override its options
+            ("-difficulty", Some(value)) if !value.is_empty() => (),
 
-use kira::{
-	manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings},
-	sound::static_sound::{StaticSoundData, StaticSoundSettings, StaticSoundHandle},
-	tween::Tween,
-};
+            //Disable mouse control (enabled by default)
+            ("-disable-mouse", None) => config.mouse_enabled = false,
+            ("-free-cursor", None) => config.free_cursor = true,
+            ("-fly-movement", None) => config.fly_movement = true,
+            ("-mouse-smoothing", Some(value)) if !value.is_empty() => config.mouse_smoothing = parse_number::<f32>(argument, value)?,
 
-use maze_generator::{MazeGenerator, SelectedGenerator, Direction};
+            //Disable audio (enabled by default)
+            ("-disable-audio", None) => config.audio_enabled = false,
 
-use crate::maze_renderer::gl_renderer::GLRenderer;
-use crate::maze_renderer::vulkan_renderer::VulkanRenderer;
-use crate::maze_renderer::{MazeRenderer, RenderResult, UniformData};
+            //Enable gamepad control (disabled by default)
+            ("-gamepad", None) => config.gamepad_enabled = true,
 
-                                    //Vertex position   //Texture UV    //Normal vector
-static VERTEX_DATA: [f32; 32] =   [ 0.5,  0.5, 0.0,     1.0, 1.0,       0.0, 0.0, -1.0,
-                                    0.5, -0.5, 0.0,     1.0, 0.0,       0.0, 0.0, -1.0,
-                                    -0.5, -0.5, 0.0,    0.0, 0.0,       0.0, 0.0, -1.0,
-                                    -0.5,  0.5, 0.0,    0.0, 1.0,       0.0, 0.0, -1.0];
+            //Movement acceleration and friction
+            ("-acceleration", Some(value)) if !value.is_empty() => config.acceleration = parse_number::<f32>(argument, value)?,
+            ("-friction", Some(value)) if !value.is_empty() => config.friction = parse_number::<f32>(argument, value)?,
 
-static VERTEX_INDICES: [u32; 6] = [0, 1, 3, //First triangle
-                                   1, 2, 3]; //Second triangle
+            //Set rendering API
+            ("-rendering-api", Some(value)) => {
+                config.rendering_api = match value {
+                    "OpenGL" => RenderingAPI::OPENGL,
+                    "Vulkan" => RenderingAPI::VULKAN,
+                    _ => return Err(format!("Unknown rendering API {} (expected Vulkan or OpenGL)", value))
+                }
+            },
 
-struct ProgramConfig {
-    window_width: u32,
-    window_height: u32,
-    maze_size: usize,
-    enable_collisions: bool,
-    set_fullscreen: bool,
-    set_portable: bool,
-    mouse_enabled: bool,
-    audio_enabled: bool,
-    seed: String,
-    selected_generator: SelectedGenerator,
-    rendering_api: RenderingAPI,
-    vsync_enabled: bool
-}
+            //Select GPU used by Vulkan renderer
+            ("-gpu", Some(value)) if !value.is_empty() => config.gpu_index = Some(parse_number::<usize>(argument, value)?),
+            ("--list-gpus", None) => config.list_gpus = true,
+            ("--version", None) => config.print_version = true,
 
-//Check collision between point and rectangle
-//Used for checking collision between player and maze walls
-//In wall position there is margin to avoid camera looking through walls
-fn check_collision_point_rectangle(point_x: f32, point_y: f32, wall_x: f32, wall_y: f32) -> bool {
-    if point_x >= wall_x - 0.7 && point_x <= wall_x + 0.7 &&
-        point_y >= wall_y - 0.7 && point_y <= wall_y + 0.7 {
-            return true;
-        }
-        
-    false
-}
+            //Enable Vulkan validation layers (disabled by default)
+            ("-debug", None) => config.debug_enabled = true,
+            ("-verbose", None) => config.verbose = true,
 
-//Check collision between player and map
-fn check_collision(player_x: f32, player_z: f32, maze_size: usize, maze_array: &Vec<bool>) -> bool {
-    let mut start_row = player_z as i32;
-    let mut start_column = player_x as i32;
+            //Disable vertical sync
+            ("-disable-vsync", None) => config.vsync_enabled = false,
+            ("-triple-buffering", None) => config.triple_buffering = true,
 
-    //Only small area around player needs to be checked
-    start_row -= 2;
-    start_column -= 2;
+            //Set frame rate cap
+            ("-max-fps", Some(value)) if !value.is_empty() => config.max_fps = parse_number::<u32>(argument, value)?,
 
-    //Trim start values to 0 if they are negative
-    start_row = cmp::max(start_row, 0);
-    start_column = cmp::max(start_column, 0);
+            //Enable exit beacon (disabled by default)
+            ("-exit-beacon", None) => config.exit_beacon = true,
+            ("-exit-marker", None) => config.exit_marker = true,
 
-    //Get end values and trim it to maze size if they are bigger
-    let end_row = cmp::min(start_row + 4, maze_size as i32);
-    let end_column = cmp::min(start_column + 4, maze_size as i32);
+            //Start in noclip mode (disabled by default)
+            ("-noclip", None) => config.noclip = true,
 
-    let mut collision_occured = false;
-    
-    for i in start_row..end_row {
-        for j in start_column..end_column {
-            if maze_array[(i as usize) * maze_size + (j as usize)] 
-                && check_collision_point_rectangle(player_x, player_z, j as f32, i as f32) {
-                    collision_occured = true;
+            //Draw maze as wireframe (disabled by default)
+            ("-wireframe", None) => config.wireframe = true,
+
+            //Draw collision debug lines (disabled by default)
+            ("-debug-collision", None) => config.debug_collision = true,
+
+            //Enable minimap (disabled by default)
+            ("-minimap", None) => config.minimap_enabled = true,
+
+            //Cells shown on minimap (full by default)
+            ("-minimap-reveal", Some(value)) => {
+                config.minimap_reveal = match value.to_lowercase().as_str() {
+                    "full" => MinimapReveal::Full,
+                    "explored" => MinimapReveal::Explored,
+                    _ => return Err(format!("Unknown minimap reveal mode {} (expected full or explored)", value))
                 }
-        }
+            },
 
-        if collision_occured {
-            break;
-        }
-    }
+            //Show FPS in window title (disabled by default)
+            ("-show-fps", None) => config.show_fps = true,
 
-    collision_occured
-}
+            //Don't draw ceiling (disabled by default)
+            ("-no-ceiling", None) => config.no_ceiling = true,
 
-//Parse command line arguments and setup program values
-//Get default values if arguments were not provided or they were wrong
-fn parse_commandline_arguments(arguments: Vec<String>, config: &mut ProgramConfig) {
-    for argument in arguments {
-        //Window width
-        if argument.contains("-width=") && argument.len() > 7 {
-            let slice = &argument[7..];
-
-            config.window_width =  match slice.parse::<u32>() {
-                Ok(value) => value, 
-                Err(_) => 800,
-            }
-        }
+            //Use normal maps for lighting (disabled by default)
+            ("-normal-maps", None) => config.normal_maps = true,
 
-        //Window height
-        if argument.contains("-height=") && argument.len() > 8 {
-            let slice = &argument[8..];
+            //Shadows cast by light carried by the player (disabled by default)
+            ("-shadows", None) => config.shadows = true,
 
-            config.window_height =  match slice.parse::<u32>() {
-                Ok(value) => value, 
-                Err(_) => 600,
-            }
-        }
+            //Sky color used when ceiling is disabled
+            ("-sky-color", Some(value)) => {
+                config.sky_color = parse_color(value).ok_or(format!("Invalid sky color {} (expected r,g,b in 0.0 - 1.0 range)", value))?;
+            },
 
-        //Maze size
-        if argument.contains("-size=") && argument.len() > 6 {
-            let slice = &argument[6..];
+            //Color of light at the exit
+            ("-exit-light-color", Some(value)) => {
+                config.exit_light_color = parse_color(value).ok_or(format!("Invalid exit light color {} (expected r,g,b in 0.0 - 1.0 range)", value))?;
+            },
 
-            config.maze_size =  match slice.parse::<usize>() {
-                Ok(value) => value, 
-                Err(_) => 600,
-            }
-        }
+            //Surface tints
+            ("-wall-tint", Some(value)) => {
+                config.wall_tint = parse_hex_color(value).ok_or(format!("Invalid wall tint {} (expected hex color RRGGBB)", value))?;
+            },
 
-        //Generator seed
-        if argument.contains("-seed=") && argument.len() > 6 {
-            let slice = &argument[6..];
-            
-            config.seed = String::from(slice);
-        }
+            ("-floor-tint", Some(value)) => {
+                config.floor_tint = parse_hex_color(value).ok_or(format!("Invalid floor tint {} (expected hex color RRGGBB)", value))?;
+            },
 
-        //Disable collisions (enabled by default)
-        if argument.contains("-disable-collisions") {
-            config.enable_collisions = false;
-        }
+            ("-ceiling-tint", Some(value)) => {
+                config.ceiling_tint = parse_hex_color(value).ok_or(format!("Invalid ceiling tint {} (expected hex color RRGGBB)", value))?;
+            },
 
-        //Enable fullscreen (disabled by default)
-        if argument.contains("-fullscreen") {
-            config.set_fullscreen = true;
-        }
+            //Field of view
+            ("-fov", Some(value)) if !value.is_empty() => config.fov = parse_number::<f32>(argument, value)?,
 
-        //Set maze generator
-        if argument.contains("-generator=") && argument.len() > 11 {
-            let slice = &argument[11..];
+            //Field of view is divided by zoom factor while zooming with mouse wheel
+            ("-zoom", Some(value)) if !value.is_empty() => config.zoom_factor = parse_number::<f32>(argument, value)?,
 
-            match slice {
-                "DFS" => config.selected_generator = SelectedGenerator::DFS,
-                _ => config.selected_generator = SelectedGenerator::RD
-            }
-        }
+            //Near clip plane
+            ("-near-plane", Some(value)) if !value.is_empty() => config.near_plane = parse_number::<f32>(argument, value)?,
 
-        //Disable mouse control (enabled by default)
-        if argument.contains("-disable-mouse") {
-            config.mouse_enabled = false;
-        }
+            //Far clip plane
+            ("-far-plane", Some(value)) if !value.is_empty() => config.far_plane = parse_number::<f32>(argument, value)?,
 
-        //Disable mouse control (enabled by default)
-        if argument.contains("-disable-audio") {
-            config.audio_enabled = false;
-        }
+            //Gamma correction
+            ("-gamma", Some(value)) if !value.is_empty() => config.gamma = parse_number::<f32>(argument, value)?,
 
-        //Set rendering API
-        if argument.contains("-rendering-api=") && argument.len() > 15 {
-            let slice = &argument[15..];
+            //Light radius
+            ("-light-radius", Some(value)) if !value.is_empty() => config.light_radius = parse_number::<f32>(argument, value)?,
 
-            match slice {
-                "OpenGL" => config.rendering_api = RenderingAPI::OPENGL,
-                _ => config.rendering_api = RenderingAPI::VULKAN
-            }
-        }
+            //Enable torch flicker (disabled by default), amplitude can be provided as value
+            ("-torch-flicker", None) => config.torch_flicker = true,
+            ("-torch-flicker", Some(value)) if !value.is_empty() => {
+                config.torch_flicker = true;
+                config.flicker_amplitude = parse_number::<f32>(argument, value)?;
+            },
+
+            //Directory with texture pack
+            ("-textures", Some(value)) if !value.is_empty() => config.textures_dir = Some(String::from(value)),
+            ("-skybox", Some(value)) if !value.is_empty() => config.skybox_dir = Some(String::from(value)),
+
+            //Number of different wall textures
+            ("-wall-variety", Some(value)) if !value.is_empty() => config.wall_variety = parse_number::<usize>(argument, value)?,
+
+            //Number of rooms carved into maze
+            ("-rooms", Some(value)) if !value.is_empty() => config.rooms = parse_number::<usize>(argument, value)?,
+
+            //Add weave crossings (disabled by default), number of crossings can be provided as value
+            ("-weave", None) => config.weave_crossings = DEFAULT_WEAVE_CROSSINGS,
+            ("-weave", Some(value)) if !value.is_empty() => config.weave_crossings = parse_number::<usize>(argument, value)?,
+
+            //Number of decoy exits
+            ("-decoys", Some(value)) if !value.is_empty() => config.decoy_exits = parse_number::<usize>(argument, value)?,
+
+            //MSAA sample count
+            ("-msaa", Some(value)) if !value.is_empty() => config.msaa_samples = parse_number::<u32>(argument, value)?,
+
+            //Texture filtering
+            ("-filter", Some(value)) => {
+                config.texture_filter = match value.to_lowercase().as_str() {
+                    "linear" => TextureFilter::LINEAR,
+                    "nearest" => TextureFilter::NEAREST,
+                    _ => return Err(format!("Unknown texture filter {} (expected linear or nearest)", value))
+                }
+            },
+
+            //Post process effect
+            ("-post", Some(value)) => {
+                config.post_effect = match value.to_lowercase().as_str() {
+                    "vignette" => Some(PostEffect::Vignette),
+                    "none" => None,
+                    _ => return Err(format!("Unknown post effect {} (expected vignette or none)", value))
+                }
+            },
+
+            //Physics time step
+            ("-timestep", Some(value)) if !value.is_empty() => config.time_step = parse_number::<f32>(argument, value)?,
+
+            //Wall height
+            ("-wall-height", Some(value)) if !value.is_empty() => config.wall_height = parse_number::<f32>(argument, value)?,
+
+            //Window title (also used as application name by Vulkan)
+            ("-title", Some(value)) => config.title = value.trim().to_string(),
 
-        //Disable vertical sync
-        if argument.contains("-disable-vsync") {
-            config.vsync_enabled = false;
+            //Render distance
+            ("-render-distance", Some(value)) if !value.is_empty() => config.render_distance = parse_number::<i32>(argument, value)?,
+
+            //Known argument used without required value or with value it doesn't take
+            _ => {
+                let known_argument = COMMANDLINE_ARGUMENTS.iter()
+                    .find(|(usage, _)| usage.split(['=', '[']).next() == Some(name));
+
+                return match known_argument {
+                    Some((usage, _)) => Err(format!("Invalid argument {} (expected {})", argument, usage)),
+                    None => Err(format!("Unknown argument {}", argument))
+                };
+            }
         }
     }
+
+    Ok(())
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    let mut program_config = ProgramConfig {
-        window_width: 800,
-        window_height: 600,
-        maze_size: 20,
-        enable_collisions: true,
-        set_fullscreen: false,
-        set_portable: false,
-        mouse_enabled: true,
-        audio_enabled: true,
-        seed: String::new(),
-        selected_generator: SelectedGenerator::RD,
-        rendering_api: RenderingAPI::VULKAN,
-        vsync_enabled: true
+    if args.iter().skip(1).any(|e| e == "--help" || e == "-help" || e == "-h") {
+        print_help();
+        return;
+    }
+
+    let mut program_config = ProgramConfig::default();
+    let set_portable = args.iter().any(|e| e == "-portable");
+
+    //Config file selected with -config= overrides default location and portable mode
+    let selected_config_path = args.iter()
+        .find_map(|e| e.strip_prefix("-config="))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+
+    //Path of config file, window size and position are written back to it on exit
+    //Without config file defaults are used and nothing is saved
+    let config_file_path = match (selected_config_path, set_portable) {
+        (Some(config_path), _) => Some(config_path),
+        (None, true) => None,
+        (None, false) => get_config_file_path()
     };
 
-    if args.iter().any(|e| e.contains("-portable")) {
-        program_config.set_portable = true;
+    if let Some(config_path) = &config_file_path {
+        program_config = program_config::load_config_file(config_path);
     }
 
-    if !program_config.set_portable {
-        let mut config_path = dirs::config_dir().expect("Failed to get config dir.");
-        config_path = config_path.join("DragonSWDev");
+    //INI presets are only read, window geometry is never written back to them
+    let config_file_path = config_file_path.filter(|config_path| !program_config::is_ini_file(config_path));
+
+    program_config.set_portable = set_portable;
 
-        if !config_path.exists() {
-            fs::create_dir(config_path.clone()).expect("Failed to create config dir.");
+    //Difficulty preset overrides config file but not other command line arguments
+    if let Some(value) = args.iter().find_map(|e| e.strip_prefix("-difficulty=")).filter(|value| !value.is_empty()) {
+        match program_config::parse_difficulty(value) {
+            Some(difficulty) => program_config::apply_difficulty(&mut program_config, difficulty),
+            None => {
+                println!("Error: Unknown difficulty {} (expected easy, normal, hard or nightmare)", value);
+                println!("Use --help to list available arguments.");
+                process::exit(1);
+            }
         }
+    }
+
+    if let Err(error) = parse_commandline_arguments(&args, &mut program_config) {
+        println!("Error: {}", error);
+        println!("Use --help to list available arguments.");
+        process::exit(1);
+    }
+
+    //Saved session replaces maze options so the same maze is generated again, player state is restored after camera setup
+    let session_file_path = game_session::get_session_file_path(&config_file_path);
+
+    let resumed_session = match program_config.resume {
+        true => match game_session::load_session(&session_file_path) {
+            Ok(session) => {
+                session.apply_to_config(&mut program_config);
+                Some(session)
+            },
+            Err(error) => {
+                println!("Error: Resuming session from {} failed: {}", session_file_path.display(), error);
+                process::exit(1);
+            }
+        },
+        false => None
+    };
+
+    //Resolutions restrictions (only for window, full screen uses desktop resolution)
+    if program_config.window_width < 100 || program_config.window_width > 7680 || program_config.window_height < 100 
+        || program_config.window_height > 4320 || program_config.window_width < program_config.window_height {
+            program_config.window_width = 800;
+            program_config.window_height = 600;
+    }
+
+    //Title restrictions
+    if program_config.title.is_empty() {
+        program_config.title = String::from("dsdmaze");
+    }
 
-        config_path = config_path.join("dsdmaze");
+    //Informational modes exit before window is created so they work without display
+    if program_config.print_version {
+        println!("dsdmaze {}", env!("CARGO_PKG_VERSION"));
+        println!("Rendering API: {}", program_config.rendering_api);
+        return;
+    }
 
-        if !config_path.exists() {
-            fs::create_dir(config_path.clone()).expect("Failed to create config dir.");
+    if program_config.list_gpus {
+        match VulkanRenderer::list_devices(&program_config.title) {
+            Ok(devices) if devices.is_empty() => println!("No Vulkan devices found."),
+            Ok(devices) => devices.iter().for_each(|device| println!("{}", device)),
+            Err(error) => {
+                println!("Error: Listing Vulkan devices failed: {}", error);
+                process::exit(1);
+            }
         }
 
-        config_path = config_path.join("dsdmaze.ini");
+        return;
+    }
 
-        //Config file doesn't exist so create it with default values
-        if !config_path.exists() {
-            let mut conf = Ini::new();
+    //Maze size restrictions (generators also enforce their own minimum size and shrink mazes over memory budget)
+    if program_config.maze_size < 10 {
+        program_config.maze_size = 20;
+    }
 
-            conf.with_section(None::<String>).set("encoding", "utf-8");
+    //Memory budget restrictions
+    if program_config.memory_budget < 16 || program_config.memory_budget > 65536 {
+        program_config.memory_budget = 1024;
+    }
 
-            conf.with_section(Some("Config"))
-                .set("Fullscreen", "0")
-                .set("Width", "800")
-                .set("Height", "600")
-                .set("Size", "20")
-                .set("Generator", "RD")
-                .set("Collisions", "1")
-                .set("Mouse", "1")
-                .set("Audio", "1")
-                .set("RenderingAPI", "Vulkan")
-                .set("VSync", "1");
+    //Generation threads restrictions
+    if program_config.threads < 1 || program_config.threads > 64 {
+        program_config.threads = 1;
+    }
 
-            conf.write_to_file(config_path).unwrap();
-        } else { //Config file exists, try loading 
-            let conf = Ini::load_from_file(config_path).unwrap();
-            let section = conf.section(Some("Config")).unwrap();
+    //Render distance restrictions
+    if program_config.render_distance < 3 || program_config.render_distance > 100 {
+        program_config.render_distance = 10;
+    }
 
-            if section.get("Fullscreen").unwrap() == "1" {
-                program_config.set_fullscreen = true;
-            }
+    //MSAA restrictions (renderers can still use lower value if requested one isn't supported)
+    if ![1, 2, 4, 8].contains(&program_config.msaa_samples) {
+        program_config.msaa_samples = 4;
+    }
 
-            program_config.window_width = section.get("Width").unwrap().parse::<u32>().unwrap();
-            program_config.window_height = section.get("Height").unwrap().parse::<u32>().unwrap();
-            program_config.maze_size = section.get("Size").unwrap().parse::<usize>().unwrap();
+    //Field of view restrictions
+    program_config.fov = f32::clamp(program_config.fov, 30.0, 120.0);
+    program_config.zoom_factor = f32::clamp(program_config.zoom_factor, 1.0, 8.0);
 
-            match section.get("Generator").unwrap() {
-                "DFS" => program_config.selected_generator = SelectedGenerator::DFS,
-                _ => program_config.selected_generator = SelectedGenerator::RD
-            }
+    //Mouse smoothing restrictions
+    program_config.mouse_smoothing = f32::clamp(program_config.mouse_smoothing, 0.0, 0.9);
 
-            if section.get("Collisions").unwrap() == "0" {
-                program_config.enable_collisions = false;
-            }
+    //Clip planes restrictions
+    //Far plane needs to cover diagonal of drawn area (with some margin) so geometry isn't clipped
+    //Depth buffer is 32 bit float on Vulkan but only 24 bit on OpenGL, with default near plane precision is fine for these ranges on both
+    //Near plane close to minimum can cause z-fighting on distant walls with OpenGL
+    if program_config.near_plane < 0.01 || program_config.near_plane > 1.0 {
+        program_config.near_plane = 0.1;
+    }
 
-            if section.get("Mouse").unwrap() == "0" {
-                program_config.mouse_enabled = false;
-            }
+    if program_config.far_plane <= program_config.near_plane {
+        program_config.far_plane = program_config.render_distance as f32 * std::f32::consts::SQRT_2 + 2.0;
+    }
 
-            if section.get("Audio").unwrap() == "0" {
-                program_config.audio_enabled = false;
-            }
+    //Collision margin restrictions
+    //Player has to fit between walls of corridor so margin can't get to 1
+    if !(0.55..=0.95).contains(&program_config.collision_margin) {
+        program_config.collision_margin = DEFAULT_COLLISION_MARGIN;
+    }
 
-            match section.get("RenderingAPI").unwrap() {
-                "Vulkan" => program_config.rendering_api = RenderingAPI::VULKAN,
-                _ => program_config.rendering_api = RenderingAPI::OPENGL
-            }
+    //Camera gets margin - 0.5 close to walls, near plane corners are even further from camera than near plane itself
+    if program_config.collision_margin - 0.5 <= program_config.near_plane {
+        println!("Warning: Collision margin {} lets camera closer to walls than near plane {}, walls can be clipped.", 
+            program_config.collision_margin, program_config.near_plane);
+    }
 
-            if section.get("VSync").unwrap() == "0" {
-                program_config.vsync_enabled = false;
-            }
+    //Wall height restrictions
+    if !(MIN_WALL_HEIGHT..=MAX_WALL_HEIGHT).contains(&program_config.wall_height) {
+        program_config.wall_height = 1.0;
+    }
+
+    //Gamma restrictions
+    if !(MIN_GAMMA..=MAX_GAMMA).contains(&program_config.gamma) {
+        program_config.gamma = 1.0;
+    }
+
+    //Frame rate cap restrictions, 0 disables cap
+    if program_config.max_fps != 0 && !(10..=1000).contains(&program_config.max_fps) {
+        program_config.max_fps = 0;
+    }
+
+    //Time step restrictions
+    if !(0.001..=0.05).contains(&program_config.time_step) {
+        program_config.time_step = 0.01;
+    }
+
+    //Movement restrictions
+    if !(1.0..=100.0).contains(&program_config.acceleration) {
+        program_config.acceleration = 8.0;
+    }
+
+    if !(1.0..=100.0).contains(&program_config.friction) {
+        program_config.friction = 10.0;
+    }
+
+    //Light radius restrictions
+    if program_config.light_radius < 1.0 || program_config.light_radius > 100.0 {
+        program_config.light_radius = 8.0;
+    }
+
+    //Flicker amplitude restrictions (intensity can't go below zero)
+    if program_config.flicker_amplitude < 0.0 || program_config.flicker_amplitude > 1.0 {
+        program_config.flicker_amplitude = 0.15;
+    }
+
+    //Texture pack restrictions
+    if let Some(textures_dir) = &program_config.textures_dir {
+        if !Path::new(textures_dir).is_dir() {
+            println!("Warning: Texture pack directory {} not found, using default textures.", textures_dir);
+            program_config.textures_dir = None;
         }
-    } 
+    }
 
-    parse_commandline_arguments(args, &mut program_config);
+    //Skybox restrictions, all faces have to be in selected directory
+    if let Some(skybox_dir) = &program_config.skybox_dir {
+        let missing_face = SKYBOX_FACES.iter().find(|face_name| !Path::new(skybox_dir).join(face_name).is_file());
 
-    //Resolutions restrictions (only for window, full screen uses desktop resolution)
-    if program_config.window_width < 100 || program_config.window_width > 7680 || program_config.window_height < 100 
-        || program_config.window_height > 4320 || program_config.window_width < program_config.window_height {
-            program_config.window_width = 800;
-            program_config.window_height = 600;
+        if let Some(missing_face) = missing_face {
+            println!("Warning: Skybox face {} not found in {}, skybox disabled.", missing_face, skybox_dir);
+            program_config.skybox_dir = None;
+        }
     }
 
-    //Maze size restrictions
-    if program_config.maze_size < 10 || program_config.maze_size > 100000 {
-        program_config.maze_size = 20;
+    //Wall variety restrictions
+    if program_config.wall_variety < 1 || program_config.wall_variety > MAX_WALL_MATERIALS {
+        program_config.wall_variety = 1;
+    }
+
+    //Rooms restrictions
+    if program_config.rooms > 100 {
+        program_config.rooms = 0;
+    }
+
+    //Weave crossings restrictions (0 disables them)
+    if program_config.weave_crossings > 100 {
+        program_config.weave_crossings = DEFAULT_WEAVE_CROSSINGS;
+    }
+
+    //Decoy exits restrictions
+    if program_config.decoy_exits > 20 {
+        program_config.decoy_exits = 0;
     }
 
-    let event_loop = EventLoop::new().unwrap();
+    let mut install_path = env::current_exe().expect("Failed to get current path.");
+    install_path.pop();
+    let assets_path = install_path.join("assets");
+    let shaders_path = install_path.join("shaders");
+    let textures_path = program_config.textures_dir.as_ref().map(PathBuf::from);
+
+    check_required_files(&assets_path, &textures_path, &shaders_path, &program_config.rendering_api, program_config.shadows);
+
+    let mut event_loop = EventLoop::new().unwrap();
 
     let window_builder;
 
+    //Selected once so F11 returns to the same mode even after window was resized
+    let fullscreen_mode = select_fullscreen_mode(event_loop.primary_monitor().or_else(|| event_loop.available_monitors().next()), &program_config);
+
     if program_config.set_fullscreen {
-        window_builder = WindowBuilder::new().with_title("dsdmaze")
-                                                .with_fullscreen(Some(Fullscreen::Borderless(None)));   
+        window_builder = WindowBuilder::new().with_title(&program_config.title)
+                                                .with_fullscreen(Some(fullscreen_mode.clone()));   
     }
     else {
-        window_builder = WindowBuilder::new().with_title("dsdmaze")
+        let mut windowed_builder = WindowBuilder::new().with_title(&program_config.title)
                                                 .with_inner_size(LogicalSize::new(program_config.window_width, program_config.window_height));   
+
+        if let Some((position_x, position_y)) = program_config.window_position {
+            windowed_builder = windowed_builder.with_position(PhysicalPosition::new(position_x, position_y));
+        }
+
+        window_builder = windowed_builder;
     }                         
 
     let window;
 
     let mut maze_renderer = match program_config.rendering_api {
         RenderingAPI::VULKAN => {
-            window = window_builder.build(&event_loop).unwrap();
-            let vulkan_renderer = VulkanRenderer::new(&window, program_config.vsync_enabled);
+            let vulkan_window = window_builder.clone().build(&event_loop).unwrap();
+
+            match VulkanRenderer::new(&vulkan_window, &program_config.title, program_config.vsync_enabled, program_config.triple_buffering, 
+                program_config.gpu_index, program_config.debug_enabled,
+                program_config.msaa_samples, program_config.verbose) {
+                Ok(vulkan_renderer) => {
+                    window = vulkan_window;
 
-            MazeRenderer::new(Box::new(vulkan_renderer))
+                    MazeRenderer::new(Box::new(vulkan_renderer))
+                },
+                Err(error) => {
+                    println!("Warning: Vulkan initialization failed ({}), falling back to OpenGL.", error);
+
+                    //OpenGL needs its own window created with matching config
+                    drop(vulkan_window);
+                    program_config.rendering_api = RenderingAPI::OPENGL;
+                    check_required_files(&assets_path, &textures_path, &shaders_path, &program_config.rendering_api, program_config.shadows);
+
+                    let opengl_renderer = create_opengl_renderer(window_builder, &event_loop, program_config.vsync_enabled, program_config.msaa_samples, 
+                        program_config.verbose);
+                    window = opengl_renderer.1;
+
+                    opengl_renderer.0
+                }
+            }
         },
         _ => {
-            let opengl_renderer = GLRenderer::new(window_builder, &event_loop, program_config.vsync_enabled);
+            let opengl_renderer = create_opengl_renderer(window_builder, &event_loop, program_config.vsync_enabled, program_config.msaa_samples, 
+                program_config.verbose);
             window = opengl_renderer.1;
 
-            MazeRenderer::new(Box::new(opengl_renderer.0))
+            opengl_renderer.0
         }
     };
 
@@ -340,35 +1588,109 @@ fn main() {
     print!("Resolution: {}x{} ", program_config.window_width, program_config.window_height);
 
     if program_config.set_fullscreen {
-        println!("fullscreen");
+        match fullscreen_mode {
+            Fullscreen::Exclusive(_) => println!("exclusive fullscreen"),
+            Fullscreen::Borderless(_) => println!("fullscreen")
+        }
     }
     else {
         println!("windowed");
     }
 
+    if let Some(difficulty) = program_config.difficulty {
+        println!("Difficulty: {}", difficulty);
+    }
     println!("Maze size: {}", program_config.maze_size);
-    println!("Collisions: {}", program_config.enable_collisions);
-    println!("Mouse control: {}", program_config.mouse_enabled);
+    println!("Render distance: {}", program_config.render_distance);
+    println!("Wall variety: {}", program_config.wall_variety);
+
+    if let Some(textures_dir) = &program_config.textures_dir {
+        println!("Texture pack: {}", textures_dir);
+    }
+    println!("Rooms: {}", program_config.rooms);
+    println!("Weave crossings: {}", program_config.weave_crossings);
+    println!("Decoy exits: {}", program_config.decoy_exits);
+    println!("Gamma: {}", program_config.gamma);
+    println!("Field of view: {}", program_config.fov);
+    println!("Zoom factor: {}", program_config.zoom_factor);
+    println!("Clip planes: {} - {}", program_config.near_plane, program_config.far_plane);
+    println!("No ceiling: {}", program_config.no_ceiling);
+
+    if let Some(skybox_dir) = &program_config.skybox_dir {
+        println!("Skybox: {}", skybox_dir);
+    }
+    println!("Wall height: {}", program_config.wall_height);
+    println!("Normal maps: {}", program_config.normal_maps);
+    println!("Shadows: {}", program_config.shadows);
+
+    if let Some(post_effect) = program_config.post_effect {
+        println!("Post effect: {}", post_effect);
+    }
+    println!("Light radius: {}", program_config.light_radius);
+    println!("Exit light color: {:?}", program_config.exit_light_color);
+    print!("Torch flicker: {}", program_config.torch_flicker);
+
+    if program_config.torch_flicker {
+        print!(" (amplitude {})", program_config.flicker_amplitude);
+    }
+
+    println!();
+    println!("Collisions: {}, margin: {}", program_config.enable_collisions, program_config.collision_margin);
+    println!("Mouse control: {}{}", program_config.mouse_enabled, if program_config.mouse_enabled && program_config.free_cursor { " (free cursor)" } else { "" });
+
+    if program_config.fly_movement {
+        println!("Fly movement: {}", if program_config.mouse_enabled { "true" } else { "true (ignored without mouse control)" });
+    }
+    if program_config.mouse_smoothing > 0.0 {
+        println!("Mouse smoothing: {}", program_config.mouse_smoothing);
+    }
+    println!("Gamepad control: {}", program_config.gamepad_enabled);
+    println!("Acceleration: {}, friction: {}", program_config.acceleration, program_config.friction);
+    println!("Physics time step: {}", program_config.time_step);
     println!("Selected generator: {}", program_config.selected_generator);
+
+    if program_config.threads > 1 {
+        println!("Generation threads: {}{}", program_config.threads, 
+            if matches!(program_config.selected_generator, SelectedGenerator::RD) { "" } else { " (ignored, only RD generator is parallel)" });
+    }
+    println!("Start position: {}", program_config.start_placement);
     println!("Rendering API: {}", program_config.rendering_api);
     println!("V-Sync: {}", program_config.vsync_enabled);
 
+    if program_config.triple_buffering {
+        println!("Triple buffering: {}", if program_config.vsync_enabled && matches!(program_config.rendering_api, RenderingAPI::VULKAN) { "true" } 
+            else { "true (ignored, requires Vulkan with V-Sync)" });
+    }
+
+    if program_config.max_fps != 0 {
+        println!("Frame rate cap: {}{}", program_config.max_fps, if program_config.vsync_enabled { " (ignored with V-Sync)" } else { "" });
+    }
+
+    println!("MSAA: {}x", program_config.msaa_samples);
+    println!("Texture filter: {}", program_config.texture_filter);
+    println!("Exit beacon: {}", program_config.exit_beacon);
+    println!("Exit marker: {}", program_config.exit_marker);
+    println!("Noclip: {}", program_config.noclip);
+    println!("Wireframe: {}", program_config.wireframe);
+    println!("Debug collision: {}", program_config.debug_collision);
+    println!("Minimap: {}", program_config.minimap_enabled);
+    println!("Minimap reveal: {}", program_config.minimap_reveal);
+    println!("Show FPS: {}", program_config.show_fps);
+
     //Generate random seed if it wasn't provided
     if program_config.seed.is_empty() {
-        program_config.seed = thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(30)
-        .map(char::from)
-        .collect();
+        program_config.seed = generate_random_seed();
+    }
+    else if program_config.seed == "time" {
+        program_config.seed = generate_time_seed();
     }
 
-    //Setup and generate maze
-    let mut maze_generator = MazeGenerator::new(program_config.selected_generator, program_config.maze_size, program_config.seed);
-    maze_generator.generate_maze();
-
-    let mut install_path = env::current_exe().expect("Failed to get current path.");
-    install_path.pop();
-    let assets_path = install_path.join("assets");
+    //Stress test only generates maze, window isn't created
+    if program_config.stress {
+        println!("Seed: {}", program_config.seed);
+        run_stress_test(&program_config);
+        return;
+    }
 
     //Setup window icon
     //Lack of window icon is not critical error so it should continue even after icon can't be loaded
@@ -384,38 +1706,206 @@ fn main() {
         window.set_window_icon(Some(icon));
     }
 
-    let shaders_path = install_path.join("shaders");
-
     let mut maze_textures_paths = Vec::new();
-    maze_textures_paths.push(assets_path.join("wall.png").to_str().unwrap().to_string());
-    maze_textures_paths.push(assets_path.join("floor.png").to_str().unwrap().to_string());
-    maze_textures_paths.push(assets_path.join("ceiling.png").to_str().unwrap().to_string());
-    maze_textures_paths.push(assets_path.join("exit.png").to_str().unwrap().to_string());
+    maze_textures_paths.push(get_texture_path("wall.png", &textures_path, &assets_path).to_str().unwrap().to_string());
+    maze_textures_paths.push(get_texture_path("floor.png", &textures_path, &assets_path).to_str().unwrap().to_string());
+    maze_textures_paths.push(get_texture_path("ceiling.png", &textures_path, &assets_path).to_str().unwrap().to_string());
+    maze_textures_paths.push(get_texture_path("exit.png", &textures_path, &assets_path).to_str().unwrap().to_string());
+
+    //Fake exit texture is optional, decoys look like real exit without it
+    //Maze isn't generated yet so missing texture is reported later only when maze has decoys
+    let fake_exit_texture_path = get_texture_path("fake_exit.png", &textures_path, &assets_path);
+    let fake_exit_texture_missing = !fake_exit_texture_path.exists();
+
+    match fake_exit_texture_missing {
+        false => maze_textures_paths.push(fake_exit_texture_path.to_str().unwrap().to_string()),
+        true => maze_textures_paths.push(maze_textures_paths[3].clone())
+    }
+
+    //Additional wall textures are optional, use default wall texture when file is missing
+    for n in 2..=program_config.wall_variety {
+        let wall_texture_path = get_texture_path(&format!("wall{}.png", n), &textures_path, &assets_path);
+
+        if wall_texture_path.exists() {
+            maze_textures_paths.push(wall_texture_path.to_str().unwrap().to_string());
+        }
+        else {
+            println!("Warning: {} not found, using default wall texture.", wall_texture_path.display());
+            maze_textures_paths.push(maze_textures_paths[0].clone());
+        }
+    }
+
+    //Win screen message is drawn as overlay so it's loaded with maze textures
+    let win_texture_path = get_texture_path("escaped.png", &textures_path, &assets_path);
+    let win_texture_index = maze_textures_paths.len() as i32;
+    let win_texture_aspect = match image::image_dimensions(&win_texture_path) {
+        Ok((width, height)) => width as f32 / height as f32,
+        Err(_) => 1.0
+    };
+
+    maze_textures_paths.push(win_texture_path.to_str().unwrap().to_string());
+
+    //Normal maps are optional too, texture without normal map (named like texture with "_normal" suffix) stays flat
+    let normal_maps_paths: Vec<Option<String>> = maze_textures_paths.iter()
+        .map(|texture_path| texture_path.replace(".png", "_normal.png"))
+        .map(|normal_map_path| if Path::new(&normal_map_path).exists() { Some(normal_map_path) } else { None })
+        .collect();
+
+    maze_renderer.renderer.set_texture_filter(program_config.texture_filter);
+    //All maze textures store colors
+    let maze_textures: Vec<TextureDescriptor> = maze_textures_paths.iter()
+        .map(|texture_path| TextureDescriptor::new(texture_path, ColorSpace::SRGB))
+        .collect();
+
+    check_renderer_setup(maze_renderer.renderer.load_textures(maze_textures.clone()));
+
+    let texture_tints = MazeGeometry::get_texture_tints(program_config.wall_variety, program_config.wall_tint, 
+        program_config.floor_tint, program_config.ceiling_tint);
+    let texture_markers = MazeGeometry::get_texture_markers(program_config.exit_marker);
+
+    if program_config.normal_maps {
+        if normal_maps_paths.iter().all(|normal_map_path| normal_map_path.is_none()) {
+            println!("Warning: No normal maps found in {}.", assets_path.display());
+        }
+
+        maze_renderer.renderer.load_normal_maps(normal_maps_paths);
+    }
+
+    //Shadow map has to be ready before maze shaders are loaded
+    if program_config.shadows {
+        match program_config.rendering_api {
+            RenderingAPI::VULKAN => maze_renderer.renderer.enable_shadows(shaders_path.join("vk").join("shadowvertexshader.spv").to_str().unwrap(), 
+                shaders_path.join("vk").join("shadowfragmentshader.spv").to_str().unwrap()),
+            RenderingAPI::OPENGL => maze_renderer.renderer.enable_shadows(shaders_path.join("gl").join("shadowvertexshader.vert").to_str().unwrap(), 
+                shaders_path.join("gl").join("shadowfragmentshader.frag").to_str().unwrap())
+        }
+    }
+
+    //Post process is optional too and it has to be enabled before shaders are loaded, scene is drawn directly to the screen if it fails
+    if let Some(post_effect) = program_config.post_effect {
+        let (vertex_shader_path, fragment_shader_path) = match program_config.rendering_api {
+            RenderingAPI::VULKAN => (shaders_path.join("vk").join("postvertexshader.spv"), shaders_path.join("vk").join(format!("{}.spv", post_effect.shader_name()))),
+            RenderingAPI::OPENGL => (shaders_path.join("gl").join("postvertexshader.vert"), shaders_path.join("gl").join(format!("{}.frag", post_effect.shader_name())))
+        };
+
+        if let Err(error) = maze_renderer.renderer.enable_post_process(vertex_shader_path.to_str().unwrap(), fragment_shader_path.to_str().unwrap()) {
+            println!("Warning: Post process disabled: {}", error);
+        }
+    }
+
+    match program_config.rendering_api {
+        RenderingAPI::VULKAN => {
+            check_renderer_setup(maze_renderer.renderer.load_shaders(shaders_path.join("vk").join("vertexshader.spv").to_str().unwrap(), 
+                shaders_path.join("vk").join("fragmentshader.spv").to_str().unwrap()));
+            check_renderer_setup(maze_renderer.renderer.load_overlay_shaders(shaders_path.join("vk").join("overlayvertexshader.spv").to_str().unwrap(), 
+                shaders_path.join("vk").join("overlayfragmentshader.spv").to_str().unwrap()));
+        },
+        RenderingAPI::OPENGL => {
+            check_renderer_setup(maze_renderer.renderer.load_shaders(shaders_path.join("gl").join("vertexshader.vert").to_str().unwrap(), 
+                shaders_path.join("gl").join("fragmentshader.frag").to_str().unwrap()));
+            check_renderer_setup(maze_renderer.renderer.load_overlay_shaders(shaders_path.join("gl").join("overlayvertexshader.vert").to_str().unwrap(), 
+                shaders_path.join("gl").join("overlayfragmentshader.frag").to_str().unwrap()));
+        }
+    }
+
+    check_renderer_setup(maze_renderer.renderer.init_mesh(VERTEX_DATA.to_vec(), VERTEX_INDICES.to_vec()));
+
+    //Skybox is optional, it's disabled with warning when its faces or shaders can't be loaded
+    let mut skybox_enabled = false;
+
+    if let Some(skybox_dir) = &program_config.skybox_dir {
+        let faces_paths = SKYBOX_FACES.iter()
+            .map(|face_name| Path::new(skybox_dir).join(face_name).to_str().unwrap().to_string())
+            .collect();
+
+        let (vertex_shader_path, fragment_shader_path) = match program_config.rendering_api {
+            RenderingAPI::VULKAN => (shaders_path.join("vk").join("skyboxvertexshader.spv"), shaders_path.join("vk").join("skyboxfragmentshader.spv")),
+            RenderingAPI::OPENGL => (shaders_path.join("gl").join("skyboxvertexshader.vert"), shaders_path.join("gl").join("skyboxfragmentshader.frag"))
+        };
+
+        let (skybox_vertices, skybox_indices) = maze_renderer::skybox_mesh();
+
+        let skybox_result = maze_renderer.renderer.register_mesh(SKYBOX_MESH_NAME, skybox_vertices, skybox_indices)
+            .and_then(|_| maze_renderer.renderer.enable_skybox(faces_paths, vertex_shader_path.to_str().unwrap(), fragment_shader_path.to_str().unwrap()));
+
+        match skybox_result {
+            Ok(()) => skybox_enabled = true,
+            Err(error) => println!("Warning: Skybox disabled: {}", error)
+        }
+    }
+
+    if program_config.wireframe {
+        maze_renderer.renderer.set_wireframe(true);
+    }
+
+    //Maze is generated on separate thread while loading screen is shown
+    let (mut maze_generator, mut maze_geometry) = create_maze_with_loading_screen(&program_config, &mut event_loop, &window, &mut maze_renderer);
+
+    program_config.window_width = window.inner_size().width;
+    program_config.window_height = window.inner_size().height;
+
+    if fake_exit_texture_missing && !maze_generator.get_decoy_exits().is_empty() {
+        println!("Warning: {} not found, decoy exits use exit texture.", fake_exit_texture_path.display());
+    }
+
+    if let Some(maze_path) = &program_config.save_maze {
+        let save_result = serde_json::to_string(&maze_generator.to_maze())
+            .map_err(|error| error.to_string())
+            .and_then(|maze_json| fs::write(maze_path, maze_json).map_err(|error| error.to_string()));
+
+        match save_result {
+            Ok(()) => println!("Maze saved to {}", maze_path),
+            Err(error) => println!("Warning: Saving maze to {} failed: {}", maze_path, error)
+        }
+    }
+
+    //Flythrough follows the shortest path, it isn't searched in too big mazes
+    let mut flythrough = match program_config.flythrough {
+        true if maze_generator.get_maze_size() > MAX_SHORTEST_PATH_MAZE_SIZE => {
+            println!("Warning: Flythrough isn't available in mazes bigger than {}.", MAX_SHORTEST_PATH_MAZE_SIZE);
+            None
+        },
+        true => match maze_generator.get_shortest_path() {
+            Some(path) => Some(Flythrough::new(&path)),
+            None => {
+                println!("Warning: Exit can't be reached, flythrough is disabled.");
+                None
+            }
+        },
+        false => None
+    };
 
-    maze_renderer.renderer.load_textures(maze_textures_paths);
+    let mut flythrough_capture = program_config.flythrough_capture && flythrough.is_some();
+    let mut flythrough_frame: u32 = 0;
 
-    match program_config.rendering_api {
-        RenderingAPI::VULKAN => {
-            maze_renderer.renderer.load_shaders(shaders_path.join("vk").join("vertexshader.spv").to_str().unwrap(), 
-                shaders_path.join("vk").join("fragmentshader.spv").to_str().unwrap());
-        },
-        RenderingAPI::OPENGL => {
-            maze_renderer.renderer.load_shaders(shaders_path.join("gl").join("vertexshader.vert").to_str().unwrap(), 
-                shaders_path.join("gl").join("fragmentshader.frag").to_str().unwrap());
+    if flythrough_capture {
+        match fs::create_dir_all(FLYTHROUGH_FRAMES_DIR) {
+            Ok(()) => println!("Flythrough frames are saved to {} directory.", FLYTHROUGH_FRAMES_DIR),
+            Err(error) => {
+                println!("Warning: Creating {} directory failed, flythrough frames won't be saved: {}", FLYTHROUGH_FRAMES_DIR, error);
+                flythrough_capture = false;
+            }
         }
     }
 
-    maze_renderer.renderer.init_mesh(VERTEX_DATA.to_vec(), VERTEX_INDICES.to_vec());
-
     //Setup audio
-    let mut audio_manager =
-		AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
-
-    let step_sound_data = StaticSoundData::from_file(assets_path.join("steps.wav"), StaticSoundSettings::new().loop_region(0.0..)).unwrap();
-    let ambience_sound_data = StaticSoundData::from_file(assets_path.join("ambience.ogg"), StaticSoundSettings::new().loop_region(0.0..)).unwrap();
+    //Missing sound files or audio device only disable audio
+    let mut game_audio = match program_config.audio_enabled {
+        true => match GameAudio::new(&assets_path) {
+            Ok(game_audio) => Some(game_audio),
+            Err(error) => {
+                println!("Warning: Audio disabled, {}", error);
+                None
+            }
+        },
+        false => None
+    };
 
     //Camera setup
-    let mut camera_position = glm::vec3(maze_generator.get_start_position().0 as f32, 0.0, maze_generator.get_start_position().1 as f32);
+    //Eyes are in the middle of default wall, lower walls also lower the camera so player can't look over them
+    let camera_height = f32::min(program_config.wall_height, 1.0) * 0.5 - 0.5;
+
+    let mut camera_position = glm::vec3(maze_generator.get_start_position().0 as f32, camera_height, maze_generator.get_start_position().1 as f32);
     let mut camera_front = glm::vec3(0.0, 0.0, -1.0);
     let camera_up = glm::vec3(0.0, 1.0, 0.0);
 
@@ -425,30 +1915,90 @@ fn main() {
     let mut cursor_manual_lock = false;
     let mut last_cursor_position: PhysicalPosition<f64> = PhysicalPosition::new(0.0, 0.0);
 
-    if program_config.mouse_enabled {
-        window.set_cursor_visible(false);
-        
-        if window.set_cursor_grab(winit::window::CursorGrabMode::Locked).is_err() {
-            window.set_cursor_grab(winit::window::CursorGrabMode::Confined).unwrap();
-            cursor_manual_lock = true;
-        }
+    if program_config.mouse_enabled && !program_config.free_cursor {
+        cursor_manual_lock = grab_cursor(&window);
     }
 
     //Setup game values
     let time_start = Instant::now();
     let mut last_frame = time_start.elapsed().as_secs_f32();
-    let time_step: f32 = 0.01;
+    let time_step = program_config.time_step;
+    let max_physics_steps = f32::ceil(MAX_SIMULATED_TIME / time_step) as u32;
     let mut accumulator: f32 = 0.0;
+    let mut previous_frame_time: f32 = 0.0;
+
+    //V-Sync already limits frame rate so cap is used only without it
+    let min_frame_time = match (program_config.vsync_enabled, program_config.max_fps) {
+        (false, max_fps) if max_fps != 0 => Some(1.0 / max_fps as f32),
+        _ => None
+    };
+
+    //FPS counter values (averaged over one second)
+    let mut fps_timer: f32 = 0.0;
+    let mut fps_frame_count: u32 = 0;
 
     let mut camera_speed = 90.0;
+    let mut mouse_delta = (0.0, 0.0); //Mouse movement not applied to camera yet when smoothing is enabled
+    let mut zoom_time: f32 = 0.0; //Time left until zoom snaps back, 0 means not zoomed
+    let mut player_velocity = glm::vec3(0.0, 0.0, 0.0);
+
+    //Currently pressed keys
+    let mut pressed_keys: HashSet<KeyCode> = HashSet::new();
+
+    let mut noclip_enabled = program_config.noclip;
+    let mut overview_enabled = false;
+    let mut debug_overlay_enabled = false;
+    let mut game_state = GameState::Playing;
+    let mut play_time: f32 = 0.0; //Time spent playing without pauses
+    let mut game_stats = GameStats::new(camera_position);
+    let mut win_stats_text = String::new();
+    let mut wireframe_enabled = program_config.wireframe;
+    let mut debug_collision_enabled = program_config.debug_collision;
+    let mut screenshot_requested = false;
+
+    if let Some(session) = &resumed_session {
+        camera_position = glm::make_vec3(&session.camera_position);
+        camera_yaw = session.camera_yaw;
+        camera_pitch = session.camera_pitch;
+        play_time = session.play_time;
+        game_stats = GameStats::new(camera_position);
+        game_stats.steps = session.steps;
+
+        println!("Session resumed from {}", session_file_path.display());
+    }
 
-    let mut key_table = vec![false; 255].into_boxed_slice();
+    //Explored cells aren't saved in session, minimap starts empty after resume
+    let mut explored_cells = match program_config.minimap_reveal {
+        MinimapReveal::Explored => Some(ExploredCells::new(&maze_generator, camera_position)),
+        MinimapReveal::Full => None
+    };
+
+    //Points tested by last collision check, shown by collision debug lines
+    let mut collision_tested_points: [(f32, f32, bool); 2] = [(camera_position.x, camera_position.z, false); 2];
+
+    //Clipboard is created on first use and kept alive, on some platforms copied text disappears when it's dropped
+    let mut clipboard: Option<Clipboard> = None;
+    let mut gamma = program_config.gamma;
 
     let mut step_sound_playing = false;
     let mut step_sound: Option<StaticSoundHandle> = Default::default();
 
-    if program_config.audio_enabled {
-        audio_manager.play(ambience_sound_data).unwrap();
+    //Gamepad is optional, game works without it if it can't be initialized
+    let mut gilrs = match program_config.gamepad_enabled {
+        true => Gilrs::new()
+            .map_err(|error| println!("Warning: Gamepad support couldn't be initialized: {}", error))
+            .ok(),
+        false => None
+    };
+
+    let mut exit_beacon: Option<StaticSoundHandle> = None;
+
+    if let Some(game_audio) = &mut game_audio {
+        game_audio.audio_manager.play(game_audio.ambience_sound_data.clone()).ok();
+
+        if program_config.exit_beacon {
+            exit_beacon = game_audio.audio_manager.play(generate_beacon_sound()).ok();
+        }
     }
 
     //Main loop
@@ -460,8 +2010,192 @@ fn main() {
                     ..
                 } => window_target.exit(),
                 WindowEvent::KeyboardInput { event, .. } => {
+                    //Flythrough ignores input, only Escape and closing window work
+                    if flythrough.is_some() {
+                        return;
+                    }
+
                     if let PhysicalKey::Code(code) = event.physical_key {
-                        key_table[code as usize] = event.state.is_pressed();
+                        let control_pressed = pressed_keys.contains(&KeyCode::ControlLeft) || pressed_keys.contains(&KeyCode::ControlRight);
+
+                        //S pressed with Ctrl saves session, it isn't movement key then
+                        if !event.state.is_pressed() {
+                            pressed_keys.remove(&code);
+                        } else if !(code == KeyCode::KeyS && control_pressed) {
+                            pressed_keys.insert(code);
+                        }
+
+                        //Win screen waits for any key, R still generates new maze
+                        if matches!(game_state, GameState::Escaped(_)) && code != KeyCode::KeyR {
+                            if event.state.is_pressed() && !event.repeat {
+                                window_target.exit();
+                            }
+
+                            return;
+                        }
+
+                        //Toggle keys, react only on first press
+                        if event.state.is_pressed() && !event.repeat {
+                            match code {
+                                KeyCode::KeyF => {
+                                    noclip_enabled = !noclip_enabled;
+
+                                    //Put camera back on the ground when leaving noclip, flying doesn't count as steps
+                                    if !noclip_enabled {
+                                        camera_position.y = camera_height;
+                                        game_stats.current_cell = get_player_cell(camera_position);
+                                    }
+
+                                    println!("Noclip: {}", noclip_enabled);
+                                },
+                                KeyCode::KeyP => {
+                                    game_state = match game_state {
+                                        GameState::Playing => GameState::Paused,
+                                        _ => GameState::Playing
+                                    };
+
+                                    let paused = game_state == GameState::Paused;
+
+                                    //Sounds are stopped so they don't keep playing while nothing moves
+                                    if paused {
+                                        if let Some(step_sound) = &mut step_sound {
+                                            step_sound.stop(Tween::default()).ok();
+                                        }
+
+                                        step_sound_playing = false;
+                                    }
+
+                                    if let Some(exit_beacon) = &mut exit_beacon {
+                                        match paused {
+                                            true => exit_beacon.pause(Tween::default()).ok(),
+                                            false => exit_beacon.resume(Tween::default()).ok()
+                                        };
+                                    }
+
+                                    println!("Paused: {}", paused);
+                                },
+                                KeyCode::KeyM => {
+                                    overview_enabled = !overview_enabled;
+
+                                    //Player doesn't move while looking at overview so steps are stopped
+                                    if overview_enabled {
+                                        if let Some(step_sound) = &mut step_sound {
+                                            step_sound.stop(Tween::default()).ok();
+                                        }
+
+                                        step_sound_playing = false;
+                                    }
+
+                                    println!("Overview: {}", overview_enabled);
+                                },
+                                KeyCode::Backquote => {
+                                    debug_overlay_enabled = !debug_overlay_enabled;
+                                },
+                                KeyCode::F3 => {
+                                    wireframe_enabled = !wireframe_enabled;
+                                    maze_renderer.renderer.set_wireframe(wireframe_enabled);
+
+                                    println!("Wireframe: {}", wireframe_enabled);
+                                },
+                                KeyCode::F4 => {
+                                    debug_collision_enabled = !debug_collision_enabled;
+
+                                    println!("Debug collision: {}", debug_collision_enabled);
+                                },
+                                KeyCode::F5 => {
+                                    println!("Reloading shaders...");
+                                    maze_renderer.renderer.reload_shaders();
+                                },
+                                KeyCode::F6 => {
+                                    println!("Reloading textures...");
+
+                                    if let Err(error) = maze_renderer.renderer.reload_textures(maze_textures.clone()) {
+                                        println!("Error: Texture reload failed: {}", error);
+                                    }
+                                },
+                                KeyCode::F11 => {
+                                    //Viewport and projection are updated by resize event sent after changing window mode
+                                    let fullscreen_enabled = window.fullscreen().is_none();
+
+                                    match fullscreen_enabled {
+                                        true => window.set_fullscreen(Some(fullscreen_mode.clone())),
+                                        false => window.set_fullscreen(None)
+                                    }
+
+                                    println!("Fullscreen: {}", fullscreen_enabled);
+                                },
+                                KeyCode::F12 => {
+                                    screenshot_requested = true;
+                                },
+                                KeyCode::KeyR => {
+                                    //Shift+R restarts current maze, R generates new one
+                                    //New maze is always generated, even if current one was loaded from file
+                                    if !pressed_keys.contains(&KeyCode::ShiftLeft) && !pressed_keys.contains(&KeyCode::ShiftRight) {
+                                        program_config.seed = generate_random_seed();
+                                        program_config.load_maze = None;
+                                        program_config.load_ascii = None;
+                                    }
+
+                                    (maze_generator, maze_geometry) = create_maze(&program_config, &mut print_generation_progress);
+
+                                    camera_position = glm::vec3(maze_generator.get_start_position().0 as f32, camera_height, maze_generator.get_start_position().1 as f32);
+                                    camera_yaw = -90.0;
+                                    camera_pitch = 0.0;
+
+                                    game_state = GameState::Playing;
+                                    play_time = 0.0;
+                                    game_stats = GameStats::new(camera_position);
+                                    accumulator = 0.0;
+                                    player_velocity = glm::vec3(0.0, 0.0, 0.0);
+
+                                    if program_config.minimap_reveal == MinimapReveal::Explored {
+                                        explored_cells = Some(ExploredCells::new(&maze_generator, camera_position));
+                                    }
+
+                                    //Beacon could be paused if maze was regenerated from pause
+                                    if let Some(exit_beacon) = &mut exit_beacon {
+                                        exit_beacon.resume(Tween::default()).ok();
+                                    }
+
+                                    window.set_title(&program_config.title);
+                                },
+                                //Ctrl+S saves session that can be resumed with -resume
+                                KeyCode::KeyS if control_pressed => {
+                                    if matches!(game_state, GameState::Escaped(_)) {
+                                        println!("Warning: Maze is already escaped, session isn't saved.");
+                                    }
+                                    else {
+                                        let session = GameSession::new(&program_config, camera_position, camera_yaw, camera_pitch, play_time, game_stats.steps);
+
+                                        match game_session::save_session(&session_file_path, &session) {
+                                            Ok(()) => println!("Session saved to {}", session_file_path.display()),
+                                            Err(error) => println!("Error: Saving session to {} failed: {}", session_file_path.display(), error)
+                                        }
+                                    }
+                                },
+                                KeyCode::KeyC => {
+                                    if clipboard.is_none() {
+                                        clipboard = Clipboard::new()
+                                            .map_err(|error| println!("Error: Clipboard isn't available: {}", error))
+                                            .ok();
+                                    }
+
+                                    if let Some(clipboard) = &mut clipboard {
+                                        match clipboard.set_text(program_config.seed.as_str()) {
+                                            Ok(()) => println!("Seed copied to clipboard."),
+                                            Err(error) => println!("Error: Copying seed to clipboard failed: {}", error)
+                                        }
+                                    }
+                                },
+                                KeyCode::Equal | KeyCode::Minus => {
+                                    let gamma_step = if code == KeyCode::Equal { 0.1 } else { -0.1 };
+                                    gamma = f32::clamp(gamma + gamma_step, MIN_GAMMA, MAX_GAMMA);
+
+                                    println!("Gamma: {:.1}", gamma);
+                                },
+                                _ => ()
+                            }
+                        }
                     }
                 },
                 WindowEvent::Resized(new_size) => {
@@ -472,13 +2206,30 @@ fn main() {
                         maze_renderer.renderer.resize_viewport(new_size.width, new_size.height);
                     }
                 },
+                //Scrolling up zooms in until timer runs out, scrolling down snaps back immediately
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let scroll = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => y,
+                        MouseScrollDelta::PixelDelta(position) => position.y as f32
+                    };
+
+                    if scroll > 0.0 && program_config.zoom_factor > 1.0 && game_state == GameState::Playing && !overview_enabled {
+                        zoom_time = ZOOM_DURATION;
+                    }
+                    else if scroll < 0.0 {
+                        zoom_time = 0.0;
+                    }
+                },
                 WindowEvent::CursorMoved { position, .. } => {
                     if cursor_manual_lock {
                         if last_cursor_position.x == 0.0 && last_cursor_position.y == 0.0 {
                             last_cursor_position = position;
                         }
 
-                        window.set_cursor_position(last_cursor_position).unwrap();
+                        //Some platforms can't move cursor, it's only confined then
+                        if window.set_cursor_position(last_cursor_position).is_err() {
+                            cursor_manual_lock = false;
+                        }
                     }
                 }
                 _ => (),
@@ -486,17 +2237,24 @@ fn main() {
             Event::DeviceEvent { event, .. } => {
                 match event {
                     DeviceEvent::MouseMotion { delta } => {
-                        if program_config.mouse_enabled {
-                            let offset_x = delta.0 as f32 * camera_speed;
-                            let offset_y = delta.1 as f32 * camera_speed;
-
-                            camera_yaw += offset_x;
-                            camera_pitch -= offset_y;
-
-                            if camera_pitch > 89.0 {
-                                camera_pitch = 89.0;
-                            } else if camera_pitch < -89.0 {
-                                camera_pitch = -89.0
+                        if program_config.mouse_enabled && game_state == GameState::Playing && !overview_enabled && flythrough.is_none() {
+                            //Smoothed movement is accumulated and applied to camera once per frame
+                            if program_config.mouse_smoothing > 0.0 {
+                                mouse_delta.0 += delta.0 as f32;
+                                mouse_delta.1 += delta.1 as f32;
+                            }
+                            else {
+                                let offset_x = delta.0 as f32 * camera_speed;
+                                let offset_y = delta.1 as f32 * camera_speed;
+
+                                camera_yaw += offset_x;
+                                camera_pitch -= offset_y;
+
+                                if camera_pitch > 89.0 {
+                                    camera_pitch = 89.0;
+                                } else if camera_pitch < -89.0 {
+                                    camera_pitch = -89.0
+                                }
                             }
                         }
                     },
@@ -505,92 +2263,233 @@ fn main() {
             },
             Event::AboutToWait => {
                 let camera_center = camera_position + camera_front;
-                let view = glm::look_at(&camera_position, &camera_center, &camera_up);
+                let mut view = glm::look_at(&camera_position, &camera_center, &camera_up);
 
                 //Setup projection matrix
-                let projection = match program_config.rendering_api {
-                    RenderingAPI::OPENGL => glm::perspective((program_config.window_width as f32)/(program_config.window_height as f32), f32::to_radians(45.0), 0.1, 100.0),
-                    RenderingAPI::VULKAN => {
-                        let mut projection = glm::perspective_rh_zo((program_config.window_width as f32)/(program_config.window_height as f32), 
-                            f32::to_radians(45.0), 0.1, 100.0);
-                        projection[5] *= -1.0; //Invert [1][1] component to invert Y on Vulkan
-
-                        projection
-                    }
-                };
+                //Aspect comes from viewport since Vulkan swapchain extent doesn't have to match window size exactly
+                let (viewport_width, viewport_height) = maze_renderer.renderer.get_viewport_size();
+                let aspect = viewport_width.max(1) as f32 / viewport_height.max(1) as f32;
 
                 let current_frame = time_start.elapsed().as_secs_f32();
-                let frame_time = f32::max(0.0, current_frame - last_frame);
+                let mut frame_time = f32::max(0.0, current_frame - last_frame);
                 last_frame = current_frame;
 
-                accumulator += frame_time;
-                accumulator = f32::clamp(accumulator, 0.0, 1.0);
+                //Zoom narrows field of view, it snaps back when pausing or switching to overview
+                if game_state != GameState::Playing || overview_enabled {
+                    zoom_time = 0.0;
+                }
+
+                let fov = match zoom_time > 0.0 {
+                    true => f32::max(program_config.fov / program_config.zoom_factor, MIN_ZOOM_FOV),
+                    false => program_config.fov
+                };
+
+                zoom_time = f32::max(0.0, zoom_time - frame_time);
+
+                //Flying camera sees drawn area from above or below, its distance from the ground is added to far plane
+                let far_plane = match noclip_enabled {
+                    true => program_config.far_plane + (camera_position.y - camera_height).abs(),
+                    false => program_config.far_plane
+                };
+
+                let mut projection = perspective_projection(&program_config.rendering_api, aspect, f32::to_radians(fov), 
+                    program_config.near_plane, far_plane);
+
+                //Game continues after stall as if it didn't happen
+                let measured_frame_time = frame_time;
+
+                if frame_time > MAX_FRAME_TIME && previous_frame_time <= MAX_FRAME_TIME {
+                    frame_time = 0.0;
+                }
+
+                previous_frame_time = measured_frame_time;
+
+                //Recorded frames follow each other with the same time step as video frames
+                if flythrough_capture {
+                    frame_time = FLYTHROUGH_CAPTURE_FRAME_TIME;
+                }
+
+                //Physics runs only while playing, frame time is still measured every frame so there is no jump after unpausing
+                //Time still counts while looking at overview but player can't move
+                if game_state == GameState::Playing {
+                    if !overview_enabled {
+                        accumulator += frame_time;
+                    }
+
+                    play_time += frame_time;
+                }
+
+                //Update FPS counter in window title once per second (win screen keeps completion time in title)
+                if program_config.show_fps && !matches!(game_state, GameState::Escaped(_)) {
+                    fps_timer += frame_time;
+                    fps_frame_count += 1;
+
+                    if fps_timer >= 1.0 {
+                        let average_frame_time = fps_timer / fps_frame_count as f32;
+
+                        window.set_title(&format!("{} - {:.0} FPS ({:.2} ms)", program_config.title, 1.0 / average_frame_time, average_frame_time * 1000.0));
+
+                        fps_timer = 0.0;
+                        fps_frame_count = 0;
+                    }
+                }
+
+                //Camera rotates by part of accumulated mouse movement, the rest is kept for next frames
+                //Movement left when player can't look around is dropped so camera doesn't turn after unpausing
+                if game_state != GameState::Playing || overview_enabled || flythrough.is_some() {
+                    mouse_delta = (0.0, 0.0);
+                }
+                else if mouse_delta != (0.0, 0.0) {
+                    let applied = 1.0 - program_config.mouse_smoothing.powf(frame_time / MOUSE_SMOOTHING_TIME);
+
+                    camera_yaw += mouse_delta.0 * applied * MOUSE_SENSITIVITY;
+                    camera_pitch = f32::clamp(camera_pitch - mouse_delta.1 * applied * MOUSE_SENSITIVITY, -89.0, 89.0);
+
+                    mouse_delta.0 *= 1.0 - applied;
+                    mouse_delta.1 *= 1.0 - applied;
+                }
+
+                //Read gamepad sticks once per frame, events have to be processed to update gamepad state
+                let mut gamepad_movement = (0.0, 0.0);
+                let mut gamepad_look = (0.0, 0.0);
+
+                if let Some(gilrs) = &mut gilrs {
+                    while let Some(gilrs::Event { event, .. }) = gilrs.next_event() {
+                        match event {
+                            EventType::Connected => println!("Gamepad connected."),
+                            EventType::Disconnected => println!("Gamepad disconnected."),
+                            _ => ()
+                        }
+                    }
+
+                    //First connected gamepad is used
+                    if let Some((_, gamepad)) = gilrs.gamepads().next() {
+                        gamepad_movement = apply_dead_zone(gamepad.value(Axis::LeftStickX), gamepad.value(Axis::LeftStickY));
+                        gamepad_look = apply_dead_zone(gamepad.value(Axis::RightStickX), gamepad.value(Axis::RightStickY));
+                    }
+                }
 
                 //Physics loop
-                while accumulator >= time_step {
+                let mut physics_steps = 0;
+
+                while accumulator >= time_step && physics_steps < max_physics_steps {
+                    physics_steps += 1;
+
+                    //Flythrough moves camera along the path instead of input, camera still follows the floor
+                    if let Some(flythrough) = &mut flythrough {
+                        flythrough.update(time_step);
+
+                        let current_floor = camera_position.y - camera_height;
+                        let position = flythrough.get_position();
+
+                        camera_position = glm::vec3(position.x, 
+                            camera_height + get_floor_height(position.x, position.y, current_floor, &maze_generator).unwrap_or(current_floor), position.y);
+                        camera_yaw = flythrough.get_yaw();
+                        camera_pitch = 0.0;
+
+                        accumulator -= time_step;
+                        continue;
+                    }
+
                     if program_config.mouse_enabled {
-                        camera_speed = 10.0 * time_step;
+                        camera_speed = MOUSE_SENSITIVITY;
                     }
                     else {
                         camera_speed = 80.0 * time_step;
                     }
 
-                    let movement_speed = 1.4 * time_step;
+                    let movement_speed = MOVEMENT_SPEED * time_step;
 
-                    //Process input
-                    if key_table[KeyCode::KeyW as usize] {
-                        let last_position = camera_position;
-                        
-    
-                        camera_position.x += movement_speed * camera_front.x;
-    
-                        if program_config.enable_collisions && check_collision(camera_position.x, camera_position.z, 
-                                                                maze_generator.get_maze_size(), maze_generator.get_maze_array()) {
-                            camera_position = last_position;
+                    //Noclip ignores collisions and allows moving in every direction
+                    let collisions_enabled = program_config.enable_collisions && !noclip_enabled;
+
+                    //Movement from keyboard and gamepad, gamepad also allows strafing
+                    let camera_right = glm::normalize(&glm::vec3(-camera_front.z, 0.0, camera_front.x));
+                    let mut forward_input = gamepad_movement.1;
+
+                    if pressed_keys.contains(&KeyCode::KeyW) {
+                        forward_input += 1.0;
+                    }
+
+                    if pressed_keys.contains(&KeyCode::KeyS) {
+                        forward_input -= 1.0;
+                    }
+
+                    forward_input = f32::clamp(forward_input, -1.0, 1.0);
+
+                    //Camera moves in the direction it's looking only in noclip mode
+                    //Walking follows only yaw so looking up or down doesn't change where player goes or how fast
+                    //Fly movement keeps horizontal part of camera front which gets shorter with bigger pitch
+                    let forward_direction = match (noclip_enabled, program_config.fly_movement) {
+                        (true, _) => camera_front,
+                        (false, true) => glm::vec3(camera_front.x, 0.0, camera_front.z),
+                        (false, false) => glm::vec3(camera_yaw.to_radians().cos(), 0.0, camera_yaw.to_radians().sin())
+                    };
+
+                    //Input sets target velocity, player accelerates towards it and slows down with friction
+                    let movement = forward_direction * forward_input + camera_right * gamepad_movement.0;
+
+                    player_velocity = update_velocity(player_velocity, movement * MOVEMENT_SPEED, program_config.acceleration, 
+                        program_config.friction, time_step);
+
+                    //Vertical velocity left after noclip would keep pushing camera when walking
+                    if !noclip_enabled {
+                        player_velocity.y = 0.0;
+                    }
+
+                    let is_moving = player_velocity.x != 0.0 || player_velocity.z != 0.0 || (noclip_enabled && player_velocity.y != 0.0);
+
+                    if is_moving {
+                        let current_floor = camera_position.y - camera_height;
+                        let (mut new_position, tested_points) = move_with_collisions(camera_position, player_velocity * time_step, current_floor, 
+                            collisions_enabled, program_config.collision_margin, &maze_generator);
+
+                        collision_tested_points = tested_points;
+
+                        //Without collisions player walks through walls but still can't leave maze through its border
+                        //Distance from border fields is the same as collisions keep from walls
+                        if !collisions_enabled && !noclip_enabled {
+                            let maze_end = maze_generator.get_maze_size() as f32 - 1.0 - program_config.collision_margin;
+
+                            new_position.x = f32::clamp(new_position.x, program_config.collision_margin, maze_end);
+                            new_position.z = f32::clamp(new_position.z, program_config.collision_margin, maze_end);
                         }
-    
-                        let last_position = camera_position;
-    
-                        camera_position.z += movement_speed * camera_front.z;
-    
-                        if program_config.enable_collisions && check_collision(camera_position.x, camera_position.z, 
-                                                                maze_generator.get_maze_size(), maze_generator.get_maze_array()) {
-                            camera_position = last_position;
+
+                        //Velocity is lost on axis blocked by wall so player doesn't keep sliding into it
+                        if new_position.x == camera_position.x {
+                            player_velocity.x = 0.0;
                         }
 
-                        if program_config.audio_enabled && !step_sound_playing {
-                            step_sound = Some(audio_manager.play(step_sound_data.clone()).unwrap());
-                            step_sound_playing = true;
+                        if new_position.z == camera_position.z {
+                            player_velocity.z = 0.0;
                         }
-                    }
-    
-                    if key_table[KeyCode::KeyS as usize] {
-                        let last_position = camera_position;
-    
-                        camera_position.x -= movement_speed * camera_front.x;
-    
-                        if program_config.enable_collisions && check_collision(camera_position.x, camera_position.z, 
-                                                                maze_generator.get_maze_size(), maze_generator.get_maze_array()) {
-                            camera_position = last_position;
+
+                        camera_position = new_position;
+
+                        //Noclip is used for inspecting maze so it doesn't count steps
+                        if !noclip_enabled {
+                            game_stats.update(camera_position);
                         }
-    
-                        let last_position = camera_position;
-    
-                        camera_position.z -= movement_speed * camera_front.z;
-    
-                        if program_config.enable_collisions && check_collision(camera_position.x, camera_position.z, 
-                                                                maze_generator.get_maze_size(), maze_generator.get_maze_array()) {
-                            camera_position = last_position;
+
+                        if let Some(explored_cells) = &mut explored_cells {
+                            explored_cells.update(&maze_generator, camera_position);
+                        }
+
+                        //Camera follows the floor, it's lower on ramps and in tunnels of weave crossings
+                        if !noclip_enabled {
+                            camera_position.y = camera_height + get_floor_height(camera_position.x, camera_position.z, current_floor, &maze_generator).unwrap_or(current_floor);
                         }
+                    }
 
-                        if program_config.audio_enabled && !step_sound_playing {
-                            step_sound = Some(audio_manager.play(step_sound_data.clone()).unwrap());
+                    //Step sound plays only while player is moving
+                    if is_moving && !step_sound_playing {
+                        if let Some(game_audio) = &mut game_audio {
+                            step_sound = game_audio.audio_manager.play(game_audio.step_sound_data.clone()).ok();
                             step_sound_playing = true;
                         }
                     }
 
-                    //Player is not moving so stop step sound if it's playing
-                    if !key_table[KeyCode::KeyW as usize] && !key_table[KeyCode::KeyS as usize] && step_sound_playing {
+                    if !is_moving && step_sound_playing {
                         if let Some(step_sound) = &mut step_sound {
                             step_sound.stop(Tween::default()).unwrap();
                         }
@@ -598,20 +2497,43 @@ fn main() {
                         step_sound_playing = false;
                     }
     
-                    if key_table[KeyCode::KeyA as usize] {
+                    if pressed_keys.contains(&KeyCode::KeyA) {
                         if !program_config.mouse_enabled {
                             camera_yaw -= camera_speed;
                         }
                     }
     
-                    if key_table[KeyCode::KeyD as usize] {
+                    if pressed_keys.contains(&KeyCode::KeyD) {
                         if !program_config.mouse_enabled {
                             camera_yaw += camera_speed;
                         }
                     }
 
+                    //Right stick works like mouse, pitch is used only with mouse control
+                    camera_yaw += gamepad_look.0 * GAMEPAD_LOOK_SPEED * time_step;
+
+                    if program_config.mouse_enabled {
+                        camera_pitch = f32::clamp(camera_pitch + gamepad_look.1 * GAMEPAD_LOOK_SPEED * time_step, -89.0, 89.0);
+                    }
+
+                    //Vertical movement in noclip mode
+                    if noclip_enabled {
+                        if pressed_keys.contains(&KeyCode::Space) {
+                            camera_position.y += movement_speed;
+                        }
+
+                        if pressed_keys.contains(&KeyCode::ControlLeft) {
+                            camera_position.y -= movement_speed;
+                        }
+                    }
+
                     accumulator -= time_step;
                 }
+
+                //Frame was too long for physics to catch up, remaining time is dropped
+                if physics_steps == max_physics_steps && accumulator >= time_step {
+                    accumulator = 0.0;
+                }
         
 
                 //Setup camera front
@@ -628,120 +2550,262 @@ fn main() {
                         camera_yaw.to_radians().sin());
                 }
 
-                //End game if player is near to exit
-                if check_collision_point_rectangle(camera_position.x, camera_position.z, 
-                            maze_generator.get_exit().0 as f32, maze_generator.get_exit().1 as f32) {
-                    window_target.exit();
-                } 
+                //Update exit beacon so it can be heard from the direction of exit
+                if let Some(exit_beacon) = &mut exit_beacon {
+                    let (volume, panning) = calculate_beacon_parameters(camera_position, camera_front, 
+                        maze_generator.get_exit().0 as f32, maze_generator.get_exit().1 as f32);
+
+                    //Command queue can be full with very high frame rate, skipping one update is harmless
+                    exit_beacon.set_volume(volume, Tween::default()).ok();
+                    exit_beacon.set_panning(panning, Tween::default()).ok();
+                }
+
+                //End game if player is near to exit (noclip is used for inspecting maze so don't end game then)
+                if game_state == GameState::Playing && !noclip_enabled && flythrough.is_none() && maze_generator.is_exit_reached(camera_position.x, camera_position.z, program_config.collision_margin) {
+                    game_state = GameState::Escaped(play_time);
+
+                    if let Some(step_sound) = &mut step_sound {
+                        step_sound.stop(Tween::default()).ok();
+                    }
+
+                    step_sound_playing = false;
+
+                    let completion_time = format!("{}:{:05.2}", (play_time / 60.0) as u32, play_time % 60.0);
+
+                    //Game ends a bit before player gets into exit cell, last step is counted anyway
+                    let exit_cell = (maze_generator.get_exit().0 as i32, maze_generator.get_exit().1 as i32);
+
+                    if game_stats.current_cell != exit_cell {
+                        game_stats.steps += 1;
+                    }
+
+                    win_stats_text = format!("Time: {}\nSteps: {}", completion_time, game_stats.steps);
+
+                    //Efficiency compares steps with shortest path, walking it exactly is 100%
+                    let shortest_path = match maze_generator.get_maze_size() <= MAX_SHORTEST_PATH_MAZE_SIZE {
+                        true => maze_generator.get_shortest_path_length(),
+                        false => None
+                    };
+
+                    if let Some(shortest_path) = shortest_path {
+                        let efficiency = u32::min(100, shortest_path as u32 * 100 / game_stats.steps.max(1));
+
+                        win_stats_text += &format!("\nShortest path: {}\nEfficiency: {}%", shortest_path, efficiency);
+                    }
+
+                    println!("{}", win_stats_text.replace('\n', ", "));
+                    println!("You escaped in {}! Press any key to exit.", completion_time);
+                    window.set_title(&format!("{} - You escaped in {}!", program_config.title, completion_time));
+                }
 
                 //Setup uniforms
+                let light_intensity = match program_config.torch_flicker {
+                    true => calculate_torch_flicker(current_frame, program_config.flicker_amplitude),
+                    false => 1.0
+                };
+
+                //Torch carried by player is always first light
+                let mut lights = [Light::default(); MAX_LIGHTS];
+                let mut light_count = 1;
+
+                //Light placed exactly in the camera would hide all shadows behind objects casting them
+                //With shadows enabled torch is held a bit to the right and below the eyes
+                let torch_position = match program_config.shadows {
+                    true => camera_position + glm::normalize(&glm::cross(&camera_front, &camera_up)) * 0.2 - camera_up * 0.15,
+                    false => camera_position
+                };
+
+                lights[0] = Light {
+                    position: torch_position,
+                    radius: program_config.light_radius,
+                    color: glm::vec3(1.0, 1.0, 1.0) * light_intensity,
+                    _padding: Default::default()
+                };
+
+                //Overview replaces player camera and torch is moved above the maze so everything is lit
+                let mut overview_cell_size = 0.0;
+
+                if overview_enabled {
+                    let maze_size = maze_generator.get_maze_size();
+                    (view, projection, overview_cell_size) = calculate_overview_camera(maze_size, program_config.window_width, program_config.window_height, 
+                        &program_config.rendering_api);
+
+                    let maze_center = (maze_size as f32 - 1.0) / 2.0;
+
+                    lights[0] = Light {
+                        position: glm::vec3(maze_center, OVERVIEW_HEIGHT, maze_center),
+                        radius: (OVERVIEW_HEIGHT + maze_size as f32) * 2.0,
+                        color: glm::vec3(1.0, 1.0, 1.0),
+                        _padding: Default::default()
+                    };
+                }
+
+                //Exit light is used only if it can reach anything that is drawn
+                let exit_position = glm::vec3(maze_generator.get_exit().0 as f32, 0.0, maze_generator.get_exit().1 as f32);
+
+                if overview_enabled || glm::distance(&camera_position, &exit_position) < program_config.render_distance as f32 + EXIT_LIGHT_RADIUS {
+                    lights[light_count] = Light {
+                        position: exit_position,
+                        radius: EXIT_LIGHT_RADIUS,
+                        color: glm::make_vec3(&program_config.exit_light_color),
+                        _padding: Default::default()
+                    };
+
+                    light_count += 1;
+                }
+
+                //Shadow map is rendered from torch looking the same direction as player
+                //Its field of view is wider than camera's so shadows at the screen edges aren't cut off
+                let light_view = glm::look_at(&torch_position, &(torch_position + camera_front), &camera_up);
+                let light_fov = f32::to_radians(f32::min(program_config.fov * 1.5, 150.0));
+
+                let light_projection = perspective_projection(&program_config.rendering_api, 1.0, light_fov, 0.05, program_config.light_radius);
+
                 maze_renderer.renderer.update_uniform_data(UniformData {
                     view_matrix: view,
                     projection_matrix: projection,
-                    light_position: camera_position,
-                    light_color: glm::vec3(1.0, 1.0, 1.0),
-                    _padding: Default::default(),
+                    lights,
+                    light_count: light_count as i32,
+                    gamma,
+                    normal_mapping: program_config.normal_maps as i32,
+                    shadows: (program_config.shadows && !overview_enabled) as i32,
+                    light_space_matrix: light_projection * light_view,
+                    texture_tints,
+                    texture_markers,
+                    marker_time: current_frame
                 });
 
                 //Begin rendering
-                maze_renderer.renderer.clear_color([0.0, 0.0, 0.0, 1.0]);
+                //Without ceiling background is visible so fill it with sky color
+                let clear_color = match program_config.no_ceiling {
+                    true => [program_config.sky_color[0], program_config.sky_color[1], program_config.sky_color[2], 1.0],
+                    false => [0.0, 0.0, 0.0, 1.0]
+                };
+
+                maze_renderer.renderer.clear_color(clear_color);
 
                 //Maze rendering
-                //Only small area around the player needs to be drawn
-                //Calculate start and end row and column based on player position
-                let start_row = cmp::max(1, camera_position.z as i32 - 10);
-                let start_column = cmp::max(1, camera_position.x as i32 - 10);
-                let end_row = cmp::min(maze_generator.get_maze_size() as i32 - 1, camera_position.z as i32 + 10);
-                let end_column = cmp::min(maze_generator.get_maze_size() as i32 - 1, camera_position.x as i32 + 10);
-
-                for i in start_row..end_row {
-                    for j in start_column..end_column {
-                        //Don't draw walls around non empty field (they won't be visible)
-                        if maze_generator.get_maze_array()[i as usize * maze_generator.get_maze_size() + j as usize] {
-                            continue;
-                        }
+                //Only small area around the player needs to be drawn, overview shows whole maze
+                let (columns, rows) = match overview_enabled {
+                    true => (0..maze_generator.get_maze_size(), 0..maze_generator.get_maze_size()),
+                    false => maze_generator.get_area(camera_position.x, camera_position.z, program_config.render_distance as usize)
+                };
+
+                //All visible quads are collected and drawn with one instanced call
+                let mut maze_instances: Vec<(glm::Mat4, i32)> = Vec::new();
 
-                        //Draw walls
-                        //Left wall
-                        if maze_generator.get_maze_array()[i as usize * maze_generator.get_maze_size() + (j - 1) as usize] {                            
-                            let mut model = glm::Mat4::identity();
-                            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
-                            model = glm::translate(&model, &glm::vec3(-0.5, 0.0, 0.0)); //Move left a bit
-                            model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(0.0, 1.0, 0.0)); //Rotate by 90 degrees around Y
+                for i in rows {
+                    for j in columns.clone() {
+                        let cell = maze_geometry.get_cell(&maze_generator, j, i);
 
-                            maze_renderer.renderer.draw(model, 0);
+                        //Ceiling would cover everything when looking from above
+                        match overview_enabled {
+                            true => maze_instances.extend(cell.iter().filter(|quad| quad.1 != maze_geometry::CEILING_TEXTURE)),
+                            false => maze_instances.extend_from_slice(cell)
                         }
+                    }
+                }
 
-                        //Right wall
-                        if maze_generator.get_maze_array()[i as usize * maze_generator.get_maze_size() + (j + 1) as usize] {
-                            let mut model = glm::Mat4::identity();
-                            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
-                            model = glm::translate(&model, &glm::vec3(0.5, 0.0, 0.0)); //Move right a bit
-                            model = glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(0.0, 1.0, 0.0)); //Rotate by 90 degrees around Y
+                maze_renderer.renderer.draw_instanced(&maze_instances);
 
-                            maze_renderer.renderer.draw(model, 0);
-                        }
+                //Skybox follows only camera rotation, overview uses orthographic projection so it doesn't have one
+                if skybox_enabled && !overview_enabled {
+                    let rotation_view = glm::mat3_to_mat4(&glm::mat4_to_mat3(&view));
 
-                        //Front wall
-                        if maze_generator.get_maze_array()[(i - 1) as usize * maze_generator.get_maze_size() + j as usize] {
-                            let mut model = glm::Mat4::identity();
-                            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
-                            model = glm::translate(&model, &glm::vec3(0.0, 0.0, -0.5)); //Move front a bit
-                            model = glm::rotate(&model, f32::to_radians(180.0), &glm::vec3(0.0, 1.0, 0.0));
-                
-                            maze_renderer.renderer.draw(model, 0);
-                        }
+                    maze_renderer.renderer.draw_skybox(projection * rotation_view);
+                }
+
+                //Player position on overview
+                if overview_enabled {
+                    let maze_center = (maze_generator.get_maze_size() as f32 - 1.0) / 2.0;
+                    let screen_center = glm::vec2(program_config.window_width as f32, program_config.window_height as f32) / 2.0;
+                    let player_offset = glm::vec2(camera_position.x - maze_center, camera_position.z - maze_center);
+
+                    maze_renderer.renderer.draw_player_marker(screen_center + player_offset * overview_cell_size, overview_cell_size.max(4.0), 
+                        camera_yaw.to_radians());
+                }
+
+                //Minimap shows cells around the player (including walls on border)
+                if program_config.minimap_enabled && !overview_enabled {
+                    let (columns, rows) = maze_generator.get_area(camera_position.x, camera_position.z, MINIMAP_RADIUS);
+
+                    let mut minimap_cells = Vec::new();
+
+                    for i in rows {
+                        for j in columns.clone() {
+                            if explored_cells.as_ref().is_some_and(|explored_cells| !explored_cells.is_explored(j, i)) {
+                                continue;
+                            }
 
-                        //Back wall
-                        if maze_generator.get_maze_array()[(i + 1) as usize * maze_generator.get_maze_size() + j as usize] {
-                            let mut model = glm::Mat4::identity();
-                            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
-                            model = glm::translate(&model, &glm::vec3(0.0, 0.0, 0.5)); //Move back a bit
-                
-                            maze_renderer.renderer.draw(model, 0);
+                            let is_wall = maze_generator.is_wall(j, i);
+
+                            minimap_cells.push((j as f32, i as f32, is_wall));
                         }
+                    }
 
-                        //Floor
-                        let mut model = glm::Mat4::identity();
-                        model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0));
-                        model = glm::translate(&model, &glm::vec3(0.0, -0.5, 0.0));
-                        model = glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(1.0, 0.0, 0.0));
-            
-                        maze_renderer.renderer.draw(model, 1);
-
-                        //Ceiling
-                        let mut model = glm::Mat4::identity();
-                        model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0));
-                        model = glm::translate(&model, &glm::vec3(0.0, 0.5, 0.0));
-                        model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(1.0, 0.0, 0.0));
-            
-                        maze_renderer.renderer.draw(model, 2);
-
-                        //Draw exit if it's visible
-                        if j == maze_generator.get_exit().0 as i32 && i == maze_generator.get_exit().1 as i32 {
-                            let mut model = glm::Mat4::identity();
-                    
-                            match maze_generator.get_end_border() {
-                                Direction::Top => {
-                                    model = model * glm::translate(&model, &glm::vec3(j as f32, 0.0, (i as f32) - 0.5));
-                                    model = model * glm::rotate(&model, f32::to_radians(180.0), &glm::vec3(0.0, 1.0, 0.0));
-                                },
-                                Direction::Bottom => {
-                                    model = model * glm::translate(&model, &glm::vec3(j as f32, 0.0, (i as f32) + 0.5));
-                                },
-                                Direction::Left => {
-                                    model = model * glm::translate(&model, &glm::vec3((j as f32) - 0.5, 0.0, i as f32));
-                                    model = model * glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(0.0, 1.0, 0.0));
-                                },
-                                Direction::Right => {
-                                    model = model * glm::translate(&model, &glm::vec3((j as f32) + 0.5, 0.0, i as f32));
-                                    model = model * glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(0.0, 1.0, 0.0));
-                                },
+                    maze_renderer.renderer.draw_minimap(&minimap_cells, (camera_position.x, camera_position.z, camera_yaw.to_radians()));
+                }
+
+                //Collision rectangles of blocking fields around the player and points tested by last collision check
+                //Lines are on the floor under the player, rectangle is red when player is inside it (possible with noclip)
+                if debug_collision_enabled && !overview_enabled {
+                    let transform = overlay_projection(&program_config.rendering_api, projection) * view;
+                    let current_floor = camera_position.y - camera_height;
+                    let floor = current_floor - 0.5;
+                    let (columns, rows) = maze_generator.get_area(camera_position.x, camera_position.z, 1);
+
+                    for i in rows {
+                        for j in columns.clone() {
+                            if !is_field_blocking(j, i, camera_position.x, camera_position.z, current_floor, &maze_generator) {
+                                continue;
                             }
 
-                            maze_renderer.renderer.draw(model, 3);
+                            let color = match check_collision_point_rectangle(camera_position.x, camera_position.z, j as f32, i as f32, 
+                                program_config.collision_margin) {
+                                true => DEBUG_COLLISION_HIT_COLOR,
+                                false => DEBUG_COLLISION_COLOR
+                            };
+
+                            draw_floor_rectangle(maze_renderer.renderer.as_mut(), transform, j as f32, i as f32, program_config.collision_margin, floor, color);
                         }
                     }
+
+                    for (x, z, collided) in collision_tested_points {
+                        let color = if collided { DEBUG_COLLISION_HIT_COLOR } else { DEBUG_COLLISION_POINT_COLOR };
+
+                        draw_floor_rectangle(maze_renderer.renderer.as_mut(), transform, x, z, DEBUG_COLLISION_POINT_SIZE, floor, color);
+                    }
+                }
+
+                //Debug overlay with player position, cell player is in, camera rotation and exit cell
+                if debug_overlay_enabled {
+                    let debug_text = format!("Position: {:.2}, {:.2}, {:.2}\nCell: {}, {}\nYaw: {:.1} Pitch: {:.1}\nExit: {}, {}", 
+                        camera_position.x, camera_position.y, camera_position.z,
+                        get_player_cell(camera_position).0, get_player_cell(camera_position).1,
+                        f32::rem_euclid(camera_yaw, 360.0), camera_pitch,
+                        maze_generator.get_exit().0, maze_generator.get_exit().1);
+
+                    maze_renderer.renderer.draw_text(&debug_text, DEBUG_OVERLAY_MARGIN, DEBUG_OVERLAY_MARGIN);
+                }
+
+                match game_state {
+                    GameState::Paused => maze_renderer.renderer.draw_pause_overlay(),
+                    GameState::Escaped(_) => {
+                        maze_renderer.renderer.draw_win_screen(win_texture_index, win_texture_aspect);
+
+                        //Statistics are centered at the bottom of the screen
+                        let (viewport_width, viewport_height) = maze_renderer.renderer.get_viewport_size();
+                        let stats_size = maze_renderer::text_size(&win_stats_text);
+
+                        maze_renderer.renderer.draw_text(&win_stats_text, (viewport_width as f32 - stats_size.x) / 2.0, 
+                            viewport_height as f32 - stats_size.y - DEBUG_OVERLAY_MARGIN);
+                    },
+                    GameState::Playing => ()
+                }
+
+                //Frame is read back by renderer before it's presented
+                if screenshot_requested || flythrough_capture {
+                    maze_renderer.renderer.request_capture();
                 }
 
                 //Finish rendering
@@ -757,9 +2821,49 @@ fn main() {
                     _ => ()
                 }
 
+                //Save frame captured by render
+                if screenshot_requested {
+                    screenshot_requested = false;
+
+                    match maze_renderer.renderer.capture_frame() {
+                        Some(frame) => save_screenshot(frame),
+                        None => println!("Error: Failed to capture frame.")
+                    }
+                }
+
+                if flythrough_capture {
+                    match maze_renderer.renderer.capture_frame() {
+                        Some(frame) => save_flythrough_frame(frame, flythrough_frame),
+                        None => println!("Error: Failed to capture flythrough frame {}.", flythrough_frame)
+                    }
+
+                    flythrough_frame += 1;
+                }
+
+                //Program ends after the last frame of flythrough
+                if flythrough.as_ref().is_some_and(|flythrough| flythrough.is_finished()) {
+                    println!("Flythrough finished.");
+                    window_target.exit();
+                    return;
+                }
+
+                //Sleep for the rest of frame time when frame rate is capped
+                if let Some(min_frame_time) = min_frame_time {
+                    let remaining_time = min_frame_time - (time_start.elapsed().as_secs_f32() - current_frame);
+
+                    if remaining_time > 0.0 {
+                        thread::sleep(Duration::from_secs_f32(remaining_time));
+                    }
+                }
+
                 window.request_redraw();
             },
             Event::LoopExiting => {
+                //Fullscreen uses desktop resolution so there is nothing to save
+                if let (Some(config_path), None) = (&config_file_path, window.fullscreen()) {
+                    save_window_geometry(config_path, &window);
+                }
+
                 maze_renderer.renderer.cleanup();
             }
             _ => (),
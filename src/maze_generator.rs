@@ -1,35 +1,102 @@
 //Common interface, data and methods for maze generators
 pub mod generator_dfs;
+pub mod generator_eller;
 pub mod generator_rd;
+pub mod generator_wilson;
+pub mod maze_grid;
+mod progress;
 
 use core::fmt;
+use std::{collections::VecDeque, mem, ops::Range};
 
 use rand::{
     distributions::{Distribution, Standard},
+    seq::SliceRandom,
     Rng
 };
 
 use rand_seeder::Seeder;
 use rand_pcg::Pcg64;
 
-use self::{generator_rd::GeneratorRD, generator_dfs::GeneratorDFS};
+use serde::{Deserialize, Serialize};
 
+use self::{generator_rd::GeneratorRD, generator_dfs::GeneratorDFS, generator_eller::GeneratorEller, generator_wilson::GeneratorWilson, maze_grid::MazeGrid, progress::Progress};
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum SelectedGenerator {
     DFS,
-    RD
+    RD,
+    Eller,
+    Wilson
 }
 
 impl fmt::Display for SelectedGenerator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             SelectedGenerator::DFS => write!(f, "DFS (Depth first search)"),
-            SelectedGenerator::RD => write!(f, "RD (Recursive division)")
+            SelectedGenerator::RD => write!(f, "RD (Recursive division)"),
+            SelectedGenerator::Eller => write!(f, "Eller (Eller's algorithm)"),
+            SelectedGenerator::Wilson => write!(f, "Wilson (Wilson's algorithm)")
+        }
+    }
+}
+
+impl SelectedGenerator {
+    //Smallest maze size generator can work with
+    //DFS starts at random point at least 3 fields away from the edge, other generators need at least 2 maze fields
+    pub fn get_minimum_size(&self) -> usize {
+        match *self {
+            SelectedGenerator::DFS => 7,
+            SelectedGenerator::RD | SelectedGenerator::Eller | SelectedGenerator::Wilson => 5
+        }
+    }
+
+    //Bytes needed to generate maze of given size, maze grid (one bit per cell) with the worst case of generator state
+    pub fn estimate_memory(&self, maze_size: usize) -> usize {
+        let cells = maze_size.saturating_mul(maze_size);
+        let grid_bytes = cells.div_ceil(8);
+
+        match *self {
+            //Stack can keep every empty field (about half of cells) with its position and shuffled directions
+            SelectedGenerator::DFS => grid_bytes.saturating_add((cells / 2).saturating_mul(mem::size_of::<(usize, usize, [Direction; 4], usize)>())),
+            //Visited flag and walk direction of every maze field (quarter of cells)
+            SelectedGenerator::Wilson => grid_bytes.saturating_add(cells / 4 * 2),
+            //RD and Eller keep only maze grid and state of few rows
+            SelectedGenerator::RD | SelectedGenerator::Eller => grid_bytes
+        }
+    }
+
+    //Biggest maze size that can be generated within memory budget
+    pub fn get_maximum_size(&self, memory_budget: usize) -> usize {
+        //Grid alone fits 8 cells into one byte
+        let mut maximum_size = memory_budget.saturating_mul(8).isqrt();
+
+        while maximum_size > 0 && self.estimate_memory(maximum_size) > memory_budget {
+            maximum_size -= 1;
+        }
+
+        maximum_size
+    }
+}
+
+//Where player starts in generated maze
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum StartPlacement {
+    Random,
+    Center //Empty field closest to the center, the same for every seed
+}
+
+impl fmt::Display for StartPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StartPlacement::Random => write!(f, "random"),
+            StartPlacement::Center => write!(f, "center")
         }
     }
 }
 
 //Cover directions in maze (maze is 2d so only 4 directions)
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Top,
     Bottom,
@@ -49,19 +116,78 @@ impl Distribution<Direction> for Standard {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct PointU32(pub u32, pub u32);
 
+//Crossing in weave maze, passage on the ground goes over the field like bridge and second passage goes through tunnel under it
+//Walls on both sides of crossing in tunnel direction are replaced with ramps leading down to the tunnel
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct Crossing {
+    pub position: PointU32,
+    pub vertical_tunnel: bool //Tunnel goes from top to bottom, otherwise from left to right
+}
+
+//Passage height of field that is part of weave crossing
+//Maze grid keeps only ground level so crossing is empty field and ramps are walls in it
+#[derive(Copy, Clone)]
+pub enum WeaveField {
+    Crossing(bool), //Bridge on the ground with tunnel under it, true if tunnel is vertical
+    Ramp(Direction) //Ramp going down to the tunnel in given direction
+}
+
+//Wall materials are assigned to square regions of maze instead of single cells
+const WALL_MATERIAL_REGION_SIZE: usize = 8;
+
+//Wall materials are numbered from 0 (default material), program has textures for this many of them
+pub const MAX_WALL_MATERIALS: usize = 8;
+
+//Size range of rooms carved into maze (width and height are random)
+const MIN_ROOM_SIZE: usize = 3;
+const MAX_ROOM_SIZE: usize = 7;
+
+//Crossings can't be closer to each other so their ramps and entrances never touch
+const MIN_CROSSING_DISTANCE: usize = 4;
+
+//Memory maze array can use by default (1 GiB), bigger mazes are shrunk to fit it
+pub const DEFAULT_MEMORY_BUDGET: usize = 1 << 30;
+
+//Exit is reached this close to the point where player is stopped by blocked border hole
+pub const EXIT_REACH_TOLERANCE: f32 = 0.05;
+
+//Generated maze without generator state
+//It can be saved and loaded back to get exactly the same layout without running generator again
+//Maze array is unpacked here so saved files keep one bool per field
+#[derive(Serialize, Deserialize)]
+pub struct Maze {
+    pub generator: SelectedGenerator,
+    pub maze_size: usize,
+    pub start_position: PointU32,
+    pub end_position: PointU32,
+    pub end_border: Direction,
+    pub maze_array: Vec<bool>,
+    pub wall_materials: Vec<u8>,
+    #[serde(default)]
+    pub crossings: Vec<Crossing>,
+    #[serde(default)]
+    pub decoy_exits: Vec<(PointU32, Direction)>
+}
+
 //For every generator that implements Generator trait
 //All data related to maze is stored here (including array with maze)
-//Generators are supposed to return array for this struct
+//Generators are supposed to return grid for this struct
 pub struct MazeGenerator {
     generator: SelectedGenerator,
     maze_size: usize,
     start_position: PointU32,
     end_position: PointU32,
     end_border: Direction,
-    maze_array: Vec<bool>,
+    maze_grid: MazeGrid,
+    wall_materials: Vec<u8>,
+    crossings: Vec<Crossing>,
+    decoy_exits: Vec<(PointU32, Direction)>, //Border walls next to these fields look like exit but can't be passed
+    memory_budget: usize,
+    start_placement: StartPlacement,
+    threads: usize, //Generators with parallel version use it when there is more than one thread
     random_engine: Pcg64
 }
 
@@ -73,13 +199,291 @@ impl MazeGenerator {
             start_position: PointU32(0, 0), 
             end_position: PointU32(0, 0), 
             end_border: Direction::Top, 
-            maze_array: Vec::new(),
+            maze_grid: MazeGrid::new(0, true),
+            wall_materials: Vec::new(),
+            crossings: Vec::new(),
+            decoy_exits: Vec::new(),
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            start_placement: StartPlacement::Random,
+            threads: 1,
             random_engine: Seeder::from(seed).make_rng()
         }
     }
 
+    //Create generator with already generated maze, maze is checked so broken data can't cause out of bounds access later
+    pub fn from_maze(maze: Maze) -> Result<Self, String> {
+        let maze_size = maze.maze_size;
+        let regions_per_row = maze_size.div_ceil(WALL_MATERIAL_REGION_SIZE);
+        let is_empty_cell = |point: PointU32| {
+            (point.0 as usize) < maze_size && (point.1 as usize) < maze_size && !maze.maze_array[point.1 as usize * maze_size + point.0 as usize]
+        };
+
+        if maze_size < 3 || maze_size.checked_mul(maze_size) != Some(maze.maze_array.len()) {
+            return Err(format!("Maze array doesn't match maze size {}.", maze_size));
+        }
+
+        if !is_empty_cell(maze.start_position) || !is_empty_cell(maze.end_position) {
+            return Err("Start or exit position is outside of maze or inside wall.".to_string());
+        }
+
+        if !maze.wall_materials.is_empty() && maze.wall_materials.len() != regions_per_row * regions_per_row {
+            return Err("Wall materials don't match maze size.".to_string());
+        }
+
+        if let Some(&material) = maze.wall_materials.iter().find(|&&material| material as usize >= MAX_WALL_MATERIALS) {
+            return Err(format!("Wall material {} is out of range (max is {}).", material, MAX_WALL_MATERIALS - 1));
+        }
+
+        //Crossing with its ramps and entrances has to be inside maze border
+        let is_inner_crossing = |crossing: &Crossing| {
+            (3..maze_size.saturating_sub(3)).contains(&(crossing.position.0 as usize)) && 
+                (3..maze_size.saturating_sub(3)).contains(&(crossing.position.1 as usize))
+        };
+
+        if !maze.crossings.iter().all(is_inner_crossing) {
+            return Err("Crossing is too close to maze border.".to_string());
+        }
+
+        //Decoy exit is border wall next to empty field, on the side of the border it's next to
+        let is_border_field = |&(point, border): &(PointU32, Direction)| {
+            is_empty_cell(point) && match border {
+                Direction::Top => point.1 == 1,
+                Direction::Bottom => point.1 as usize == maze_size - 2,
+                Direction::Left => point.0 == 1,
+                Direction::Right => point.0 as usize == maze_size - 2
+            }
+        };
+
+        if !maze.decoy_exits.iter().all(is_border_field) {
+            return Err("Decoy exit isn't next to maze border.".to_string());
+        }
+
+        //Exit hole is made in border next to exit so exit has to be next to its border too
+        if !is_border_field(&(maze.end_position, maze.end_border)) {
+            return Err("Exit isn't next to its maze border.".to_string());
+        }
+
+        //Only exit hole can be open in border, player and path search would get outside of maze through any other opening
+        let PointU32(end_x, end_y) = maze.end_position;
+        let exit_hole = match maze.end_border {
+            Direction::Top => (end_x, end_y - 1),
+            Direction::Bottom => (end_x, end_y + 1),
+            Direction::Left => (end_x - 1, end_y),
+            Direction::Right => (end_x + 1, end_y)
+        };
+
+        let border_fields = (0..maze_size as u32).flat_map(|n| [(n, 0), (n, maze_size as u32 - 1), (0, n), (maze_size as u32 - 1, n)]);
+
+        for (x, y) in border_fields {
+            if (x, y) != exit_hole && is_empty_cell(PointU32(x, y)) {
+                return Err(format!("Maze border isn't closed at row {}, column {}.", y + 1, x + 1));
+            }
+        }
+
+        Ok(MazeGenerator {
+            generator: maze.generator,
+            maze_size,
+            start_position: maze.start_position,
+            end_position: maze.end_position,
+            end_border: maze.end_border,
+            maze_grid: MazeGrid::from_fields(maze_size, &maze.maze_array),
+            wall_materials: maze.wall_materials,
+            crossings: maze.crossings,
+            decoy_exits: maze.decoy_exits,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            start_placement: StartPlacement::Random,
+            threads: 1,
+            random_engine: Seeder::from("").make_rng()
+        })
+    }
+
+    //Create generator with maze drawn as text, '#' is wall, space is empty field, 'S' is start and 'E' is exit
+    //Exit has to be next to maze border, exit hole is made in the border it's next to if it isn't open already
+    pub fn from_ascii(text: &str) -> Result<Self, String> {
+        let mut rows: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+
+        //Empty lines after maze are ignored
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        let maze_size = rows.len();
+        let columns = rows.first().map_or(0, |row| row.len());
+
+        if let Some(index) = rows.iter().position(|row| row.len() != columns) {
+            return Err(format!("Maze isn't rectangular, row {} has {} fields but first row has {}.", index + 1, rows[index].len(), columns));
+        }
+
+        if columns != maze_size {
+            return Err(format!("Maze has {} rows and {} columns but it has to be square.", maze_size, columns));
+        }
+
+        if maze_size < 3 {
+            return Err(format!("Maze size {} is too small (min is 3).", maze_size));
+        }
+
+        let mut maze_array = Vec::with_capacity(maze_size * maze_size);
+        let mut starts = Vec::new();
+        let mut exits = Vec::new();
+        let mut open_border_fields = Vec::new();
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &field) in row.iter().enumerate() {
+                let is_border = x == 0 || y == 0 || x == maze_size - 1 || y == maze_size - 1;
+
+                match field {
+                    '#' => maze_array.push(true),
+                    'S' | 'E' if is_border => return Err(format!("Start or exit at row {}, column {} is on maze border.", y + 1, x + 1)),
+                    ' ' => {
+                        if is_border {
+                            open_border_fields.push(PointU32(x as u32, y as u32));
+                        }
+
+                        maze_array.push(false);
+                    },
+                    'S' => {
+                        starts.push(PointU32(x as u32, y as u32));
+                        maze_array.push(false);
+                    },
+                    'E' => {
+                        exits.push(PointU32(x as u32, y as u32));
+                        maze_array.push(false);
+                    },
+                    _ => return Err(format!("Unknown field '{}' at row {}, column {} (expected '#', space, 'S' or 'E').", field, y + 1, x + 1))
+                }
+            }
+        }
+
+        let (start_position, end_position) = match (starts.as_slice(), exits.as_slice()) {
+            ([start_position], [end_position]) => (*start_position, *end_position),
+            _ => return Err(format!("Maze has {} starts and {} exits but it needs exactly one of each.", starts.len(), exits.len()))
+        };
+
+        //Border field next to exit is its hole, every other border field has to be wall
+        let PointU32(end_x, end_y) = end_position;
+        let hole_border = |PointU32(x, y): PointU32| match (x, y) {
+            _ if (x, y + 1) == (end_x, end_y) => Some(Direction::Top),
+            _ if (x, y) == (end_x, end_y + 1) => Some(Direction::Bottom),
+            _ if (x + 1, y) == (end_x, end_y) => Some(Direction::Left),
+            _ if (x, y) == (end_x + 1, end_y) => Some(Direction::Right),
+            _ => None
+        };
+
+        let mut end_border = None;
+
+        for &field in &open_border_fields {
+            match (hole_border(field), end_border) {
+                (Some(border), None) => end_border = Some(border),
+                _ => return Err(format!("Maze border isn't closed at row {}, column {}.", field.1 + 1, field.0 + 1))
+            }
+        }
+
+        //Without open hole exit gets it in the first border it's next to
+        let end_border = match (end_border, end_position) {
+            (Some(border), _) => border,
+            (None, PointU32(_, 1)) => Direction::Top,
+            (None, PointU32(_, y)) if y as usize == maze_size - 2 => Direction::Bottom,
+            (None, PointU32(1, _)) => Direction::Left,
+            (None, PointU32(x, _)) if x as usize == maze_size - 2 => Direction::Right,
+            _ => return Err("Exit isn't next to maze border.".to_string())
+        };
+
+        //Generator isn't known for hand made maze, it's only stored when maze is saved
+        let mut maze_generator = Self::from_maze(Maze {
+            generator: SelectedGenerator::RD,
+            maze_size,
+            start_position,
+            end_position,
+            end_border,
+            maze_array,
+            wall_materials: Vec::new(),
+            crossings: Vec::new(),
+            decoy_exits: Vec::new()
+        })?;
+
+        let PointU32(hole_x, hole_y) = maze_generator.get_exit_hole();
+        maze_generator.maze_grid.set_wall(hole_x as usize, hole_y as usize, false);
+
+        Ok(maze_generator)
+    }
+
+    //Maze drawn as text that can be read by from_ascii, exit hole is open in the border
+    //Text keeps only layout with start and exit, wall materials, weave crossings and decoy exits aren't stored
+    pub fn to_ascii(&self) -> String {
+        let mut text = String::with_capacity((self.maze_size + 1) * self.maze_size);
+
+        for (x, y, is_wall) in self.cells() {
+            text.push(match is_wall {
+                true => '#',
+                false if self.is_start_position(x, y) => 'S',
+                false if (x, y) == (self.end_position.0 as usize, self.end_position.1 as usize) => 'E',
+                false => ' '
+            });
+
+            if x == self.maze_size - 1 {
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    //Copy of generated maze that can be saved
+    pub fn to_maze(&self) -> Maze {
+        Maze {
+            generator: self.generator,
+            maze_size: self.maze_size,
+            start_position: self.start_position,
+            end_position: self.end_position,
+            end_border: self.end_border,
+            maze_array: self.maze_grid.to_fields(),
+            wall_materials: self.wall_materials.clone(),
+            crossings: self.crossings.clone(),
+            decoy_exits: self.decoy_exits.clone()
+        }
+    }
+
+    //Limit memory used by maze array, checked when maze is generated
+    pub fn set_memory_budget(&mut self, memory_budget: usize) {
+        self.memory_budget = memory_budget;
+    }
+
+    //Select how start position is chosen, needs to be called before maze is generated
+    pub fn set_start_placement(&mut self, start_placement: StartPlacement) {
+        self.start_placement = start_placement;
+    }
+
+    //Number of threads used by generators that can run in parallel (RD), needs to be called before maze is generated
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
     //Generate maze using selected generator and setup start position and exit 
-    pub fn generate_maze(&mut self) {
+    //Returns warnings about changed options (maze size, thread count) for caller to show
+    pub fn generate_maze(&mut self) -> Vec<String> {
+        self.generate_maze_with_progress(&mut |_| ())
+    }
+
+    //Generate maze and report progress of generator as completed fraction (0 - 1) to callback
+    //Callback is called from this thread even with parallel generation, last call is always 1 after maze is finished
+    pub fn generate_maze_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let maximum_size = self.generator.get_maximum_size(self.memory_budget);
+
+        if self.maze_size > maximum_size {
+            warnings.push(format!("Maze size {} needs up to {} MB which is over memory budget of {} MB, using {} instead.", self.maze_size, 
+                self.generator.estimate_memory(self.maze_size) / (1024 * 1024), self.memory_budget / (1024 * 1024), maximum_size));
+            self.maze_size = maximum_size;
+        }
+
+        //Too small sizes would make generators ask for random value from empty range
+        let minimum_size = self.generator.get_minimum_size();
+
+        if self.maze_size < minimum_size {
+            warnings.push(format!("Maze size {} is too small for {} generator, using {} instead.", self.maze_size, self.generator, minimum_size));
+            self.maze_size = minimum_size;
+        }
+
         match self.generator {
             SelectedGenerator::RD => {
                 //RD generator needs odd size
@@ -88,81 +492,177 @@ impl MazeGenerator {
                 }
 
                 let mut generator_rd = GeneratorRD::new(self.maze_size, &mut self.random_engine);
-                self.maze_array = generator_rd.generate();
+                self.maze_grid = match self.threads {
+                    1 => generator_rd.generate_with_progress(progress),
+                    threads => {
+                        let (maze_grid, warning) = generator_rd.generate_parallel_with_progress(threads, progress);
+                        warnings.extend(warning);
+                        maze_grid
+                    }
+                };
+            }
+
+            SelectedGenerator::Eller => {
+                //Eller generator uses the same layout as RD so it needs odd size too
+                if self.maze_size % 2 == 0 {
+                    self.maze_size += 1;
+                }
+
+                let mut generator_eller = GeneratorEller::new(self.maze_size, &mut self.random_engine);
+                self.maze_grid = generator_eller.generate_with_progress(progress);
+            }
+
+            SelectedGenerator::Wilson => {
+                //Wilson generator uses the same layout as RD so it needs odd size too
+                if self.maze_size % 2 == 0 {
+                    self.maze_size += 1;
+                }
+
+                let mut generator_wilson = GeneratorWilson::new(self.maze_size, &mut self.random_engine);
+                self.maze_grid = generator_wilson.generate_with_progress(progress);
             }
 
             _ => {
                 let mut generator_dfs = GeneratorDFS::new(self.maze_size, &mut self.random_engine);
-                self.maze_array = generator_dfs.generate();
+                self.maze_grid = generator_dfs.generate_with_progress(progress);
             }
         }
 
         self.set_start_position();
         self.set_exit();
+
+        progress(1.0);
+
+        warnings
+    }
+
+    //Set start position from selected placement
+    fn set_start_position(&mut self) {
+        let center_position = match self.start_placement {
+            StartPlacement::Center => self.find_center_position(),
+            StartPlacement::Random => None
+        };
+
+        let (x, y) = match center_position {
+            Some(position) => position,
+            None => self.find_random_position()
+        };
+
+        self.start_position = PointU32(x as u32, y as u32);
     }
 
-    //Set start position
     //Get two random values and check if their coordinates matches empty (false) field in maze
+    //Field also needs empty neighbour so player can move somewhere from start
     //If not generate again in loop
-    fn set_start_position(&mut self)  {
+    fn find_random_position(&mut self) -> (usize, usize) {
         let mut x = self.random_engine.gen_range(1..=(self.maze_size - 1));
         let mut y = self.random_engine.gen_range(1..=(self.maze_size - 1));
 
-        while self.maze_array[y * self.maze_size + x] {
+        while self.maze_grid.is_wall(x, y) || !self.has_empty_neighbour(x, y) {
             x = self.random_engine.gen_range(1..=(self.maze_size - 1));
             y = self.random_engine.gen_range(1..=(self.maze_size - 1));
         }
 
-        self.start_position = PointU32(x as u32, y as u32);
+        (x, y)
+    }
+
+    //Search square rings around center field for empty field with empty neighbour closest to it
+    //Fields in ring are at least radius away so search stops when radius gets over the closest distance found
+    //Fields with equal distance are picked by row and then column so result depends only on maze layout
+    fn find_center_position(&self) -> Option<(usize, usize)> {
+        let center = self.maze_size / 2;
+        let mut closest: Option<(usize, usize, usize)> = None; //Squared distance and position
+
+        for radius in 0..=center {
+            if closest.is_some_and(|(distance, _, _)| radius * radius > distance) {
+                break;
+            }
+
+            let first = center.saturating_sub(radius);
+            let last = (center + radius).min(self.maze_size - 1);
+
+            for y in first..=last {
+                //Rows between top and bottom of the ring only have fields at its sides
+                let columns = match y.abs_diff(center) == radius {
+                    true => (first..=last).collect(),
+                    false => vec![center - radius, center + radius]
+                };
+
+                for x in columns.into_iter().filter(|&x| x >= first && x <= last) {
+                    if self.maze_grid.is_wall(x, y) || !self.has_empty_neighbour(x, y) {
+                        continue;
+                    }
+
+                    let distance = x.abs_diff(center).pow(2) + y.abs_diff(center).pow(2);
+
+                    if closest.is_none_or(|(closest_distance, _, _)| distance < closest_distance) {
+                        closest = Some((distance, x, y));
+                    }
+                }
+            }
+        }
+
+        closest.map(|(_, x, y)| (x, y))
+    }
+
+    //Check if any of four neighbours of field inside maze is empty
+    fn has_empty_neighbour(&self, x: usize, y: usize) -> bool {
+        !self.maze_grid.is_wall(x - 1, y) || !self.maze_grid.is_wall(x + 1, y) ||
+            !self.maze_grid.is_wall(x, y - 1) || !self.maze_grid.is_wall(x, y + 1)
+    }
+
+    fn is_start_position(&self, x: usize, y: usize) -> bool {
+        self.start_position.0 as usize == x && self.start_position.1 as usize == y
     }
 
     //Setup exit for maze
     //Every maze is supposed to have border around actual maze
     //For exit make a hole in that border but only if it's accesible inside maze (not covered by wall)
+    //Exit can't be placed next to start position so player doesn't spawn at the exit
     fn set_exit(&mut self)  {
         let mut found_exit = false;
 
         while !found_exit {
             //Get random index and border then check if it can be used as exit hole
             let exit_index = self.random_engine.gen_range(1..=(self.maze_size - 1));
-            let exit_wall: Direction = rand::random();
+            let exit_wall: Direction = self.random_engine.gen();
 
             match exit_wall {
                 Direction::Top => {
-                    if self.maze_array[1 * self.maze_size + exit_index] == false {
+                    if !self.maze_grid.is_wall(exit_index, 1) && !self.is_start_position(exit_index, 1) {
                         self.end_position = PointU32(exit_index as u32, 1);
                         self.end_border = exit_wall;
-                        self.maze_array[0 * self.maze_size + exit_index] = false;
+                        self.maze_grid.set_wall(exit_index, 0, false);
 
                         found_exit = true;
                     }
                 }
 
                 Direction::Bottom => {
-                    if self.maze_array[(self.maze_size - 2) * self.maze_size + exit_index] == false {
+                    if !self.maze_grid.is_wall(exit_index, self.maze_size - 2) && !self.is_start_position(exit_index, self.maze_size - 2) {
                         self.end_position = PointU32(exit_index as u32, (self.maze_size - 2) as u32);
                         self.end_border = exit_wall;
-                        self.maze_array[(self.maze_size - 1) * self.maze_size + exit_index] = false;
+                        self.maze_grid.set_wall(exit_index, self.maze_size - 1, false);
 
                         found_exit = true;
                     }
                 }
 
                 Direction::Left => {
-                    if self.maze_array[exit_index * self.maze_size + 1] == false {
+                    if !self.maze_grid.is_wall(1, exit_index) && !self.is_start_position(1, exit_index) {
                         self.end_position = PointU32(1, exit_index as u32);
                         self.end_border = exit_wall;
-                        self.maze_array[exit_index * self.maze_size + 0] = false;
+                        self.maze_grid.set_wall(0, exit_index, false);
 
                         found_exit = true;
                     }
                 }
 
                 Direction::Right => {
-                    if self.maze_array[exit_index * self.maze_size + (self.maze_size - 2)] == false {
+                    if !self.maze_grid.is_wall(self.maze_size - 2, exit_index) && !self.is_start_position(self.maze_size - 2, exit_index) {
                         self.end_position = PointU32((self.maze_size - 2) as u32, exit_index as u32);
                         self.end_border = exit_wall;
-                        self.maze_array[exit_index * self.maze_size + (self.maze_size - 1)] = false;
+                        self.maze_grid.set_wall(self.maze_size - 1, exit_index, false);
 
                         found_exit = true;
                     }
@@ -171,6 +671,274 @@ impl MazeGenerator {
         }
     }
 
+    //Add border walls that look like exit but lead nowhere
+    //Decoy can be anywhere real exit could be except at real exit and start position, border isn't carved so player can't pass through it
+    //If there aren't enough such places fewer decoys are added
+    pub fn add_decoy_exits(&mut self, decoy_count: usize) {
+        let last = self.maze_size - 2;
+        let mut candidates = Vec::new();
+
+        for index in 1..=last {
+            candidates.extend([
+                (PointU32(index as u32, 1), Direction::Top),
+                (PointU32(index as u32, last as u32), Direction::Bottom),
+                (PointU32(1, index as u32), Direction::Left),
+                (PointU32(last as u32, index as u32), Direction::Right)
+            ]);
+        }
+
+        candidates.retain(|&(point, border)| {
+            let (x, y) = (point.0 as usize, point.1 as usize);
+            let is_exit = (x, y) == (self.end_position.0 as usize, self.end_position.1 as usize) && border == self.end_border;
+
+            !self.maze_grid.is_wall(x, y) && !self.is_start_position(x, y) && !is_exit && !self.is_decoy_exit(x, y, border)
+        });
+
+        let decoy_exits: Vec<(PointU32, Direction)> = candidates.choose_multiple(&mut self.random_engine, decoy_count).copied().collect();
+
+        self.decoy_exits.extend(decoy_exits);
+    }
+
+    pub fn is_decoy_exit(&self, x: usize, y: usize, border: Direction) -> bool {
+        self.decoy_exits.iter().any(|&(point, decoy_border)| point.0 as usize == x && point.1 as usize == y && decoy_border == border)
+    }
+
+    pub fn get_decoy_exits(&self) -> &Vec<(PointU32, Direction)> {
+        &self.decoy_exits
+    }
+
+    //Carve rectangular rooms into generated maze
+    //Rooms only remove walls so every path that existed before still exists and border is never touched
+    //Room is carved only where it overlaps existing passage so it can't create separated area
+    pub fn carve_rooms(&mut self, room_count: usize) {
+        //Inner part of maze without border has to fit at least the smallest room
+        if self.maze_size < MIN_ROOM_SIZE + 2 {
+            return;
+        }
+
+        let max_room_size = MAX_ROOM_SIZE.min(self.maze_size - 2);
+        let mut carved_rooms = 0;
+        let mut attempts = 0;
+
+        //Limit attempts, in dense mazes every room overlaps passage so it's only safety for tiny mazes
+        while carved_rooms < room_count && attempts < room_count * 10 {
+            attempts += 1;
+
+            let width = self.random_engine.gen_range(MIN_ROOM_SIZE..=max_room_size);
+            let height = self.random_engine.gen_range(MIN_ROOM_SIZE..=max_room_size);
+            let x = self.random_engine.gen_range(1..=(self.maze_size - 1 - width));
+            let y = self.random_engine.gen_range(1..=(self.maze_size - 1 - height));
+
+            let overlaps_passage = (y..y + height)
+                .any(|row| (x..x + width).any(|column| !self.maze_grid.is_wall(column, row)));
+
+            if !overlaps_passage {
+                continue;
+            }
+
+            for row in y..y + height {
+                for column in x..x + width {
+                    self.maze_grid.set_wall(column, row, false);
+                }
+            }
+
+            carved_rooms += 1;
+        }
+    }
+
+    //Turn some straight corridors into crossings of weave maze
+    //Crossing needs walls between it and empty fields in tunnel direction, these walls become ramps down to the tunnel
+    //Maze grid isn't changed so ground level stays the same and tunnels only add new paths
+    pub fn add_weave_crossings(&mut self, crossing_count: usize) {
+        //Crossing with ramps and entrances needs 5 fields inside border
+        if self.maze_size < 7 {
+            return;
+        }
+
+        let mut attempts = 0;
+
+        while self.crossings.len() < crossing_count && attempts < crossing_count * 100 {
+            attempts += 1;
+
+            let x = self.random_engine.gen_range(3..=(self.maze_size - 4));
+            let y = self.random_engine.gen_range(3..=(self.maze_size - 4));
+            let vertical_tunnel = self.random_engine.gen_bool(0.5);
+
+            if self.can_place_crossing(x, y, vertical_tunnel) {
+                self.crossings.push(Crossing {
+                    position: PointU32(x as u32, y as u32),
+                    vertical_tunnel
+                });
+            }
+        }
+    }
+
+    fn can_place_crossing(&self, x: usize, y: usize, vertical_tunnel: bool) -> bool {
+        let is_wall = |x: usize, y: usize| self.maze_grid.is_wall(x, y);
+
+        //Offsets along the tunnel and along the bridge
+        let (tunnel_x, tunnel_y) = if vertical_tunnel { (0, 1) } else { (1, 0) };
+        let (bridge_x, bridge_y) = (tunnel_y, tunnel_x);
+
+        let too_close = self.crossings.iter().any(|crossing| {
+            (crossing.position.0 as usize).abs_diff(x) < MIN_CROSSING_DISTANCE && (crossing.position.1 as usize).abs_diff(y) < MIN_CROSSING_DISTANCE
+        });
+
+        //Bridge is straight corridor, ramps are walls with walls on their sides and tunnel entrances are empty
+        !too_close && !is_wall(x, y) && !is_wall(x - bridge_x, y - bridge_y) && !is_wall(x + bridge_x, y + bridge_y) &&
+            is_wall(x - tunnel_x, y - tunnel_y) && is_wall(x + tunnel_x, y + tunnel_y) &&
+            is_wall(x - tunnel_x - bridge_x, y - tunnel_y - bridge_y) && is_wall(x - tunnel_x + bridge_x, y - tunnel_y + bridge_y) &&
+            is_wall(x + tunnel_x - bridge_x, y + tunnel_y - bridge_y) && is_wall(x + tunnel_x + bridge_x, y + tunnel_y + bridge_y) &&
+            !is_wall(x - 2 * tunnel_x, y - 2 * tunnel_y) && !is_wall(x + 2 * tunnel_x, y + 2 * tunnel_y)
+    }
+
+    //Get weave field at given position, None for fields that aren't part of any crossing
+    pub fn get_weave_field(&self, x: usize, y: usize) -> Option<WeaveField> {
+        for crossing in &self.crossings {
+            let (crossing_x, crossing_y) = (crossing.position.0 as usize, crossing.position.1 as usize);
+
+            if (x, y) == (crossing_x, crossing_y) {
+                return Some(WeaveField::Crossing(crossing.vertical_tunnel));
+            }
+
+            //Ramps go down towards crossing
+            let ramp = match crossing.vertical_tunnel {
+                true if x == crossing_x && y + 1 == crossing_y => Some(Direction::Bottom),
+                true if x == crossing_x && y == crossing_y + 1 => Some(Direction::Top),
+                false if y == crossing_y && x + 1 == crossing_x => Some(Direction::Right),
+                false if y == crossing_y && x == crossing_x + 1 => Some(Direction::Left),
+                _ => None
+            };
+
+            if let Some(direction) = ramp {
+                return Some(WeaveField::Ramp(direction));
+            }
+        }
+
+        None
+    }
+
+    //Number of steps between neighbouring fields on the shortest path from start to exit, None if exit can't be reached
+    pub fn get_shortest_path_length(&self) -> Option<usize> {
+        self.get_shortest_path().map(|path| path.len() - 1)
+    }
+
+    //Fields on the shortest path from start to exit including both of them, None if exit can't be reached
+    //Breadth first search goes through tunnels of weave crossings too, field with tunnel is searched on both levels
+    pub fn get_shortest_path(&self) -> Option<Vec<PointU32>> {
+        let field_index = |x: usize, y: usize, in_tunnel: bool| (y * self.maze_size + x) * 2 + in_tunnel as usize;
+
+        //Previous field on the path for every reached field, start points to itself
+        let mut previous_fields = vec![usize::MAX; self.maze_size * self.maze_size * 2];
+        let mut queue = VecDeque::new();
+
+        let start = (self.start_position.0 as usize, self.start_position.1 as usize);
+        let exit = (self.end_position.0 as usize, self.end_position.1 as usize);
+
+        previous_fields[field_index(start.0, start.1, false)] = field_index(start.0, start.1, false);
+        queue.push_back((start.0, start.1, false));
+
+        while let Some((x, y, in_tunnel)) = queue.pop_front() {
+            if (x, y) == exit {
+                let mut path = Vec::new();
+                let mut index = field_index(x, y, in_tunnel);
+
+                loop {
+                    path.push(PointU32(((index / 2) % self.maze_size) as u32, ((index / 2) / self.maze_size) as u32));
+
+                    if previous_fields[index] == index {
+                        break;
+                    }
+
+                    index = previous_fields[index];
+                }
+
+                path.reverse();
+
+                return Some(path);
+            }
+
+            //Search doesn't leave maze through exit hole so neighbours can't be outside of array
+            for direction in [Direction::Top, Direction::Bottom, Direction::Left, Direction::Right] {
+                let (next_x, next_y) = match direction {
+                    Direction::Top => (x, y - 1),
+                    Direction::Bottom => (x, y + 1),
+                    Direction::Left => (x - 1, y),
+                    Direction::Right => (x + 1, y)
+                };
+
+                let is_vertical = matches!(direction, Direction::Top | Direction::Bottom);
+
+                //In tunnel player can only go along it, leaving ramp at the top means walking on the ground again
+                //On the ground ramp can be entered only from its top, bridge of crossing has no way down
+                let next_in_tunnel = match (in_tunnel, self.get_weave_field(x, y), self.get_weave_field(next_x, next_y)) {
+                    (true, Some(WeaveField::Crossing(vertical_tunnel)), _) if vertical_tunnel != is_vertical => continue,
+                    (true, Some(WeaveField::Ramp(ramp_direction)), _) if Self::is_vertical(ramp_direction) != is_vertical => continue,
+                    (true, _, Some(_)) => true,
+                    (true, _, None) => false,
+                    (false, Some(WeaveField::Crossing(_)), Some(WeaveField::Ramp(_))) => continue,
+                    (false, _, Some(WeaveField::Ramp(ramp_direction))) if Self::is_vertical(ramp_direction) != is_vertical => continue,
+                    (false, _, Some(WeaveField::Ramp(_))) => true,
+                    (false, _, _) => false
+                };
+
+                if !next_in_tunnel && self.maze_grid.is_wall(next_x, next_y) {
+                    continue;
+                }
+
+                let next_index = field_index(next_x, next_y, next_in_tunnel);
+
+                if previous_fields[next_index] == usize::MAX {
+                    previous_fields[next_index] = field_index(x, y, in_tunnel);
+                    queue.push_back((next_x, next_y, next_in_tunnel));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn is_vertical(direction: Direction) -> bool {
+        matches!(direction, Direction::Top | Direction::Bottom)
+    }
+
+    pub fn get_crossings(&self) -> &Vec<Crossing> {
+        &self.crossings
+    }
+
+    //Assign random wall material to every region of maze
+    //Uses the same random engine so materials are the same for the same seed
+    pub fn assign_wall_materials(&mut self, variety: usize) {
+        let regions_per_row = self.maze_size.div_ceil(WALL_MATERIAL_REGION_SIZE);
+
+        self.wall_materials = (0..regions_per_row * regions_per_row)
+            .map(|_| self.random_engine.gen_range(0..variety.max(1)) as u8)
+            .collect();
+    }
+
+    //Materials without loaded texture are replaced by default material, returns true if any material was replaced
+    pub fn limit_wall_materials(&mut self, variety: usize) -> bool {
+        let mut replaced = false;
+
+        for material in self.wall_materials.iter_mut().filter(|material| **material as usize >= variety.max(1)) {
+            *material = 0;
+            replaced = true;
+        }
+
+        replaced
+    }
+
+    //Get wall material of cell, 0 is default material
+    pub fn get_wall_material(&self, x: usize, y: usize) -> usize {
+        if self.wall_materials.is_empty() {
+            return 0;
+        }
+
+        let regions_per_row = self.maze_size.div_ceil(WALL_MATERIAL_REGION_SIZE);
+
+        self.wall_materials[(y / WALL_MATERIAL_REGION_SIZE) * regions_per_row + (x / WALL_MATERIAL_REGION_SIZE)] as usize
+    }
+
     pub fn get_start_position(&self) -> PointU32 {
         self.start_position
     }
@@ -183,8 +951,50 @@ impl MazeGenerator {
         self.end_border
     }
 
-    pub fn get_maze_array(&self) -> &Vec<bool> {
-        &self.maze_array
+    //Border field carved for exit, it's empty in maze but nothing is drawn there
+    pub fn get_exit_hole(&self) -> PointU32 {
+        let PointU32(x, y) = self.end_position;
+
+        match self.end_border {
+            Direction::Top => PointU32(x, y - 1),
+            Direction::Bottom => PointU32(x, y + 1),
+            Direction::Left => PointU32(x - 1, y),
+            Direction::Right => PointU32(x + 1, y)
+        }
+    }
+
+    //Check if point in world coordinates (field centers are at integer coordinates) is close enough to exit field center on both axes
+    //Collision margin is distance from border hole center player can get to, so player walking into exit always reaches it
+    pub fn is_exit_reached(&self, x: f32, z: f32, collision_margin: f32) -> bool {
+        let reach_distance = 1.0 - collision_margin + EXIT_REACH_TOLERANCE;
+
+        (x - self.end_position.0 as f32).abs() <= reach_distance && (z - self.end_position.1 as f32).abs() <= reach_distance
+    }
+
+    //Everything outside of maze counts as wall so neighbours of border cells can be checked without bounds math
+    pub fn is_wall(&self, x: usize, y: usize) -> bool {
+        x >= self.maze_size || y >= self.maze_size || self.maze_grid.is_wall(x, y)
+    }
+
+    //Columns and rows of cells at most radius cells away from cell containing point, clamped to maze
+    //Ranges are empty when point is too far outside of maze
+    pub fn get_area(&self, x: f32, y: f32, radius: usize) -> (Range<usize>, Range<usize>) {
+        let clamp_range = |center: f32| {
+            let cell = (center + 0.5).floor();
+
+            //Negative values are saturated to 0 by casting
+            let start = (cell - radius as f32) as usize;
+            let end = ((cell + radius as f32 + 1.0) as usize).min(self.maze_size);
+
+            start.min(end)..end
+        };
+
+        (clamp_range(x), clamp_range(y))
+    }
+
+    //Every cell of maze as (x, y, is wall), row by row
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+        (0..self.maze_size).flat_map(move |y| (0..self.maze_size).map(move |x| (x, y, self.maze_grid.is_wall(x, y))))
     }
 
     pub fn get_maze_size(&self) -> usize {
@@ -1,4 +1,5 @@
 //Maze generator that uses Depth First Search alghorithm
+//Search is iterative so big mazes don't overflow the stack
 use crate::maze_generator::*;
 
 use rand::{
@@ -6,6 +7,9 @@ use rand::{
     seq::SliceRandom,
 };
 
+//Part of inner fields that DFS maze usually has empty, used for progress estimate
+const VISITED_FIELDS_ESTIMATE: f32 = 0.59;
+
 pub struct GeneratorDFS<'a> {
     maze_size: usize,
     random_engine: &'a mut Pcg64
@@ -19,83 +23,115 @@ impl GeneratorDFS<'_> {
         }
     }
 
-    pub fn generate(&mut self) -> Vec<bool> {
-        //Init array (completely filled)
-        let mut maze_array = vec![true; self.maze_size * self.maze_size];
+    pub fn generate(&mut self) -> MazeGrid {
+        self.generate_with_progress(&mut |_| ())
+    }
+
+    //Progress is counted in visited fields, their final count isn't known so it's estimated from part of inner fields DFS usually visits
+    pub fn generate_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> MazeGrid {
+        //Init grid (completely filled)
+        let mut maze_grid = MazeGrid::new(self.maze_size, true);
 
         //Rand starting point and direction
         let x = self.random_engine.gen_range(3..=(self.maze_size - 3));
         let y = self.random_engine.gen_range(3..=(self.maze_size - 3));
         let direction: Direction = self.random_engine.gen();
         //Mark point as empty (visited)
-        maze_array[x * self.maze_size + y] = false;
+        maze_grid.set_wall(y, x, false);
 
         //Go to the selected direction
-        match direction {
-            Direction::Top => self.add_path(&mut maze_array, x, y - 1),
-            Direction::Bottom => self.add_path(&mut maze_array, x, y + 1),
-            Direction::Left => self.add_path(&mut maze_array, x - 1, y),
-            Direction::Right => self.add_path(&mut maze_array, x + 1, y),
+        let inner_fields = (self.maze_size - 2) * (self.maze_size - 2);
+        let mut progress = Progress::new(progress, (inner_fields as f32 * VISITED_FIELDS_ESTIMATE) as usize);
+
+        let (next_x, next_y) = Self::get_neighbour(x, y, direction);
+        self.add_path(&mut maze_grid, next_x, next_y, &mut progress);
+
+        maze_grid
+    }
+
+    //Visit fields starting from given point using explicit stack instead of recursion
+    //Every stack entry keeps field position, its shuffled directions and index of next direction to check
+    //Neighbours are visited in the same order as recursive version so the same seed gives the same maze
+    fn add_path(&mut self, maze_grid: &mut MazeGrid, x: usize, y: usize, progress: &mut Progress) {
+        let mut stack: Vec<(usize, usize, [Direction; 4], usize)> = Vec::new();
+
+        if let Some(directions) = self.visit_field(maze_grid, x, y) {
+            stack.push((x, y, directions, 0));
+            progress.advance(1);
         }
 
-        maze_array
+        while let Some((x, y, directions, next_direction)) = stack.last_mut() {
+            //All neighbours were checked so go back
+            if *next_direction == directions.len() {
+                stack.pop();
+                continue;
+            }
+
+            let (next_x, next_y) = Self::get_neighbour(*x, *y, directions[*next_direction]);
+            *next_direction += 1;
+
+            if let Some(directions) = self.visit_field(maze_grid, next_x, next_y) {
+                stack.push((next_x, next_y, directions, 0));
+                progress.advance(1);
+            }
+        }
     }
 
-    //Check if neighbours were visited and visit them in random order
-    fn add_path(&mut self, maze_array: &mut Vec<bool>, x: usize, y: usize) {
+    //Check if field can be visited, mark it as visited and return its neighbours in random order
+    //Here x is row and y is column of the grid
+    fn visit_field(&mut self, maze_grid: &mut MazeGrid, x: usize, y: usize) -> Option<[Direction; 4]> {
         //Check if we are out of bounds
         if x >= self.maze_size - 1 || x < 1 || y < 1 || y >= self.maze_size - 1 {
-            return;
+            return None;
         }
 
         //We are on empty field so return
-        if !maze_array[x * self.maze_size + y] {
-            return;
+        if !maze_grid.is_wall(y, x) {
+            return None;
         }
 
         //Count visited neighbours
         let mut count = 0;
 
-        if !maze_array[(x - 1) * self.maze_size + y ] {
+        if !maze_grid.is_wall(y, x - 1) {
             count = count + 1;
         }
 
-        if !maze_array[(x + 1) * self.maze_size + y] {
+        if !maze_grid.is_wall(y, x + 1) {
             count = count + 1;
         }
 
-        if !maze_array[x * self.maze_size + (y - 1)] {
+        if !maze_grid.is_wall(y - 1, x) {
             count = count + 1;
         }
 
-        if !maze_array[x * self.maze_size + (y + 1)] {
+        if !maze_grid.is_wall(y + 1, x) {
             count = count + 1;
         }
 
         if count > 1 {
-            return;
+            return None;
         }
 
         //Mark actual point as visited
-        maze_array[x * self.maze_size + y] = false;
+        maze_grid.set_wall(y, x, false);
 
-        //Create and init vector with possible directions and then shuffle it in random order
-        let mut directions: Vec<Direction> = Vec::new();
-        directions.push(Direction::Top);
-        directions.push(Direction::Bottom);
-        directions.push(Direction::Left);
-        directions.push(Direction::Right);
+        //Possible directions in random order
+        let mut directions = [Direction::Top, Direction::Bottom, Direction::Left, Direction::Right];
 
         directions.shuffle(self.random_engine);
 
-        //Visit every neighbour recursively
-        for direction in directions.iter() {
-            match direction {
-                Direction::Top => self.add_path(maze_array, x, y - 1),
-                Direction::Bottom => self.add_path(maze_array, x, y + 1),
-                Direction::Left => self.add_path(maze_array, x - 1, y),
-                Direction::Right => self.add_path(maze_array, x + 1, y),
-            }
+        Some(directions)
+    }
+
+    //Position of neighbour in given direction
+    //Fields on the border are never visited so it can't go below zero
+    fn get_neighbour(x: usize, y: usize, direction: Direction) -> (usize, usize) {
+        match direction {
+            Direction::Top => (x, y - 1),
+            Direction::Bottom => (x, y + 1),
+            Direction::Left => (x - 1, y),
+            Direction::Right => (x + 1, y),
         }
     }
 }
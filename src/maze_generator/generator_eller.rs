@@ -0,0 +1,161 @@
+//Maze generator that uses Eller's alghorithm
+//Maze is built row by row and only sets of current row are kept, so generator state doesn't grow with maze height
+//Like in RD generator maze fields have odd indices and walls are between them
+use crate::maze_generator::*;
+
+use rand::Rng;
+
+pub struct GeneratorEller<'a> {
+    maze_size: usize,
+    random_engine: &'a mut Pcg64
+}
+
+impl GeneratorEller<'_> {
+    pub fn new(maze_size: usize, random_engine: &mut Pcg64) -> GeneratorEller<'_> {
+        GeneratorEller {
+            maze_size,
+            random_engine
+        }
+    }
+
+    pub fn generate(&mut self) -> MazeGrid {
+        self.generate_with_progress(&mut |_| ())
+    }
+
+    //Progress is counted in finished rows
+    pub fn generate_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> MazeGrid {
+        //Init grid (completely filled with walls)
+        let mut maze_grid = MazeGrid::new(self.maze_size, true);
+
+        //Get count of maze fields in one row
+        let maze_fields = (self.maze_size - 1) / 2;
+
+        let mut progress = Progress::new(progress, maze_fields);
+
+        //Set of every field in current row, fields without set (not connected to row above) are None
+        //Set labels are always lower than number of fields so they can be used as indices
+        let mut row_sets: Vec<Option<usize>> = vec![None; maze_fields];
+
+        for row in 0..maze_fields {
+            let last_row = row == maze_fields - 1;
+
+            self.assign_new_sets(&mut row_sets);
+
+            //Sets are joined in union-find structure, labels are replaced by roots before moving to next row
+            let mut parents: Vec<usize> = (0..maze_fields).collect();
+            let mut sets: Vec<usize> = row_sets.iter().map(|set| set.unwrap()).collect();
+
+            for field in 0..maze_fields {
+                let (x, y) = Self::get_position(field, row);
+                maze_grid.set_wall(x, y, false);
+            }
+
+            //Join neighbouring fields from different sets, in last row all of them have to be joined
+            for field in 0..(maze_fields - 1) {
+                let left_set = Self::find_set(&mut parents, sets[field]);
+                let right_set = Self::find_set(&mut parents, sets[field + 1]);
+
+                if left_set != right_set && (last_row || self.random_engine.gen::<bool>()) {
+                    parents[right_set] = left_set;
+                    let (x, y) = Self::get_position(field, row);
+                    maze_grid.set_wall(x + 1, y, false);
+                }
+            }
+
+            for set in sets.iter_mut() {
+                *set = Self::find_set(&mut parents, *set);
+            }
+
+            progress.advance(1);
+
+            if last_row {
+                break;
+            }
+
+            row_sets = self.carve_down(&sets, row, &mut maze_grid);
+        }
+
+        maze_grid
+    }
+
+    //Grid position of maze field
+    fn get_position(field: usize, row: usize) -> (usize, usize) {
+        (field * 2 + 1, row * 2 + 1)
+    }
+
+    fn find_set(parents: &mut [usize], set: usize) -> usize {
+        let mut root = set;
+
+        while parents[root] != root {
+            root = parents[root];
+        }
+
+        //Point every set on the way directly to root so next search is faster
+        let mut current = set;
+
+        while parents[current] != root {
+            let next = parents[current];
+            parents[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    //Give every field without set its own new set using labels that aren't used in row
+    fn assign_new_sets(&self, row_sets: &mut [Option<usize>]) {
+        let mut used_labels = vec![false; row_sets.len()];
+
+        for set in row_sets.iter().flatten() {
+            used_labels[*set] = true;
+        }
+
+        let mut free_labels = (0..row_sets.len()).filter(|label| !used_labels[*label]);
+
+        for set in row_sets.iter_mut().filter(|set| set.is_none()) {
+            *set = free_labels.next();
+        }
+    }
+
+    //Every set has to continue to next row at least once, otherwise it would be cut off
+    //Returns sets of next row, fields below passages keep set of field above
+    fn carve_down(&mut self, sets: &[usize], row: usize, maze_grid: &mut MazeGrid) -> Vec<Option<usize>> {
+        let maze_fields = sets.len();
+
+        let mut next_row_sets = vec![None; maze_fields];
+        let mut set_continues = vec![false; maze_fields];
+
+        //Random field of every set (reservoir sampling), used when set didn't continue on its own
+        let mut set_sizes = vec![0; maze_fields];
+        let mut fallback_fields = vec![0; maze_fields];
+
+        for (field, &set) in sets.iter().enumerate() {
+            set_sizes[set] += 1;
+
+            if self.random_engine.gen_range(0..set_sizes[set]) == 0 {
+                fallback_fields[set] = field;
+            }
+
+            if self.random_engine.gen::<bool>() {
+                next_row_sets[field] = Some(set);
+                set_continues[set] = true;
+            }
+        }
+
+        for &set in sets {
+            if !set_continues[set] {
+                next_row_sets[fallback_fields[set]] = Some(set);
+                set_continues[set] = true;
+            }
+        }
+
+        for (field, set) in next_row_sets.iter().enumerate() {
+            if set.is_some() {
+                let (x, y) = Self::get_position(field, row);
+                maze_grid.set_wall(x, y + 1, false);
+            }
+        }
+
+        next_row_sets
+    }
+}
@@ -1,14 +1,25 @@
 //Maze generator that uses Recursive Division alghorithm
 use crate::maze_generator::*;
+use crate::maze_generator::maze_grid::SharedMazeGrid;
 
-use rand::Rng;
+use std::{ops::Range, sync::atomic::{AtomicUsize, Ordering}, thread, time::Duration};
+
+use rand::{Rng, SeedableRng};
 use rand::distributions::{Distribution, Standard};
 
+//Parallel generation splits work only for chambers with at least this many maze fields
+//Smaller chambers are divided by the thread that got them, scheduling them would take longer than dividing
+const PARALLEL_CHAMBER_FIELDS: usize = 64 * 64;
+
+//How often thread that started parallel generation checks progress
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 pub struct GeneratorRD<'a> {
     maze_size: usize,
     random_engine: &'a mut Pcg64
 }
 
+#[derive(Copy, Clone)]
 pub enum Orientation {
     Horizontal,
     Vertical
@@ -16,13 +27,60 @@ pub enum Orientation {
 
 impl Distribution<Orientation> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Orientation {
-        match rng.gen_range(0..=1) { 
+        match rng.gen_range(0..=1) {
             0 => Orientation::Horizontal,
             _ => Orientation::Vertical
         }
     }
 }
 
+//Chamber bounds in maze fields (inclusive)
+#[derive(Copy, Clone)]
+struct Chamber {
+    start_field_x: usize,
+    start_field_y: usize,
+    end_field_x: usize,
+    end_field_y: usize
+}
+
+impl Chamber {
+    fn field_count(&self) -> usize {
+        (self.end_field_x - self.start_field_x + 1) * (self.end_field_y - self.start_field_y + 1)
+    }
+}
+
+//Wall dividing chamber, its array indices and two chambers created by it with their orientations
+struct Division {
+    orientation: Orientation,
+    wall_index: usize,
+    wall_range: Range<usize>,
+    passage_index: usize,
+    chambers: [(Chamber, Orientation); 2]
+}
+
+impl Division {
+    //Draw wall with passage, grid is changed through callback so the same division works with serial and shared grid
+    fn draw(&self, mut set_wall: impl FnMut(usize, usize, bool)) {
+        match self.orientation {
+            Orientation::Horizontal => {
+                for n in self.wall_range.clone() {
+                    set_wall(n, self.wall_index, true);
+                }
+
+                set_wall(self.passage_index, self.wall_index, false);
+            }
+
+            Orientation::Vertical => {
+                for n in self.wall_range.clone() {
+                    set_wall(self.wall_index, n, true);
+                }
+
+                set_wall(self.wall_index, self.passage_index, false);
+            }
+        }
+    }
+}
+
 impl GeneratorRD<'_> {
     pub fn new(maze_size: usize, random_engine: &mut Pcg64) -> GeneratorRD<'_> {
         GeneratorRD {
@@ -31,99 +89,208 @@ impl GeneratorRD<'_> {
         }
     }
 
-    pub fn generate(&mut self) -> Vec<bool> {
-        //Init array (completely empty)
-        let mut maze_array = vec![false; self.maze_size * self.maze_size];
+    pub fn generate(&mut self) -> MazeGrid {
+        self.generate_with_progress(&mut |_| ())
+    }
+
+    //Progress is counted in maze fields of chambers that are too small to be divided
+    pub fn generate_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> MazeGrid {
+        let mut maze_grid = self.create_grid();
+        let maze_chamber = self.get_maze_chamber();
+        let mut progress = Progress::new(progress, maze_chamber.field_count());
+
+        let orientation: Orientation = self.random_engine.gen();
+
+        self.divide_chamber(maze_chamber, orientation, &mut maze_grid, &mut progress);
+
+        maze_grid
+    }
+
+    //Chambers on both sides of every wall are divided in parallel on given number of threads
+    //Every parallel chamber uses its own random engine seeded from its parent, so maze differs from serial one with the same seed
+    //Chambers are split the same way for any thread count so every count gives the same maze
+    //Warning is returned when thread pool with given number of threads can't be created and default pool is used instead
+    pub fn generate_parallel(&mut self, threads: usize) -> (MazeGrid, Option<String>) {
+        self.generate_parallel_with_progress(threads, &mut |_| ())
+    }
+
+    //Chambers are divided on separate thread and this thread reports fields of finished chambers counted by them
+    //Callback doesn't have to be sent to other threads this way
+    pub fn generate_parallel_with_progress(&mut self, threads: usize, progress: &mut dyn FnMut(f32)) -> (MazeGrid, Option<String>) {
+        let maze_grid = SharedMazeGrid::from_grid(self.create_grid());
+        let maze_chamber = self.get_maze_chamber();
+        let mut progress = Progress::new(progress, maze_chamber.field_count());
+        let completed_fields = AtomicUsize::new(0);
+
+        let orientation: Orientation = self.random_engine.gen();
+        let mut random_engine = Pcg64::seed_from_u64(self.random_engine.gen());
+
+        let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build();
+
+        let warning = thread_pool.as_ref().err()
+            .map(|error| format!("Can't create {} threads for maze generation ({}), using default thread pool instead.", threads, error));
+
+        thread::scope(|scope| {
+            let mut divide_maze = || divide_chamber_parallel(maze_chamber, orientation, &mut random_engine, &maze_grid, &completed_fields);
+
+            let generation = scope.spawn(|| match &thread_pool {
+                Ok(thread_pool) => thread_pool.install(divide_maze),
+                Err(_) => divide_maze()
+            });
+
+            while !generation.is_finished() {
+                progress.set_completed(completed_fields.load(Ordering::Relaxed));
+                thread::sleep(PROGRESS_POLL_INTERVAL);
+            }
+        });
+
+        (maze_grid.into_grid(), warning)
+    }
+
+    //Completely empty grid with border
+    fn create_grid(&self) -> MazeGrid {
+        let mut maze_grid = MazeGrid::new(self.maze_size, false);
 
-        //Make border
         for n in 0..self.maze_size {
-            maze_array[n * self.maze_size] = true;
-            maze_array[n] = true;
-            maze_array[n * self.maze_size + (self.maze_size - 1)] = true;
-            maze_array[(self.maze_size - 1) * self.maze_size + n] = true;
+            maze_grid.set_wall(0, n, true);
+            maze_grid.set_wall(n, 0, true);
+            maze_grid.set_wall(self.maze_size - 1, n, true);
+            maze_grid.set_wall(n, self.maze_size - 1, true);
         }
 
-        //Get count of maze fields in allocated array
-        //Maze fields are array fields with odd index
+        maze_grid
+    }
+
+    //Whole maze as one chamber
+    //Maze fields are array fields with odd index
+    fn get_maze_chamber(&self) -> Chamber {
         let maze_fields = (self.maze_size - 1) / 2;
 
-        let orientation: Orientation = self.random_engine.gen();
+        Chamber {
+            start_field_x: 0,
+            start_field_y: 0,
+            end_field_x: maze_fields - 1,
+            end_field_y: maze_fields - 1
+        }
+    }
+
+    fn divide_chamber(&mut self, chamber: Chamber, orientation: Orientation, maze_grid: &mut MazeGrid, progress: &mut Progress) {
+        let Some(division) = divide(chamber, orientation, self.random_engine) else {
+            progress.advance(chamber.field_count());
+            return;
+        };
 
-        self.divide_chamber(0, 0, maze_fields - 1, maze_fields - 1, orientation, &mut maze_array);
+        division.draw(|x, y, wall| maze_grid.set_wall(x, y, wall));
 
-        maze_array
+        let [(first_chamber, first_orientation), (second_chamber, second_orientation)] = division.chambers;
+
+        self.divide_chamber(first_chamber, first_orientation, maze_grid, progress);
+        self.divide_chamber(second_chamber, second_orientation, maze_grid, progress);
     }
+}
 
-    fn divide_chamber(&mut self, start_field_x: usize, start_field_y: usize, end_field_x: usize, end_field_y: usize, orientation: Orientation, maze_array: &mut Vec<bool>) {
-        if (end_field_x - start_field_x) < 1 || (end_field_y - start_field_y) < 1 {
-            return;
-        }
+fn divide_chamber_parallel(chamber: Chamber, orientation: Orientation, random_engine: &mut Pcg64, maze_grid: &SharedMazeGrid, 
+    completed_fields: &AtomicUsize) {
+    let Some(division) = divide(chamber, orientation, random_engine) else {
+        completed_fields.fetch_add(chamber.field_count(), Ordering::Relaxed);
+        return;
+    };
 
-        match orientation {
-            Orientation::Horizontal => {
-                let wall_field = self.random_engine.gen_range(start_field_y..end_field_y);
+    division.draw(|x, y, wall| maze_grid.set_wall(x, y, wall));
 
-                //Get array index of randomly selected maze field
-                let mut wall_index = wall_field * 2 + 1;
-                wall_index += 1; //Wall will be drawn in position next to the selected field
+    let [(first_chamber, first_orientation), (second_chamber, second_orientation)] = division.chambers;
 
-                for n in (start_field_x * 2 + 1)..(end_field_x * 2 + 2) { //Draw horizontal wall
-                    maze_array[wall_index * self.maze_size + n] = true;
-                }
+    if chamber.field_count() < PARALLEL_CHAMBER_FIELDS {
+        divide_chamber_parallel(first_chamber, first_orientation, random_engine, maze_grid, completed_fields);
+        divide_chamber_parallel(second_chamber, second_orientation, random_engine, maze_grid, completed_fields);
 
-                let passage_field = self.random_engine.gen_range(start_field_x..=end_field_x); //Select maze field where passage will be placed
+        return;
+    }
 
-                maze_array[wall_index * self.maze_size + (passage_field * 2 + 1)] = false; //Put passage on wall
+    let mut first_random_engine = Pcg64::seed_from_u64(random_engine.gen());
+    let mut second_random_engine = Pcg64::seed_from_u64(random_engine.gen());
 
-                //There are two chambers divided by horizontal wall
-                let first_chamber_orientation = self.get_orientation(start_field_x, start_field_y, end_field_x, wall_field);
-                let second_chamber_orientation = self.get_orientation(start_field_x, wall_field + 1, end_field_x, end_field_y);
+    rayon::join(
+        || divide_chamber_parallel(first_chamber, first_orientation, &mut first_random_engine, maze_grid, completed_fields),
+        || divide_chamber_parallel(second_chamber, second_orientation, &mut second_random_engine, maze_grid, completed_fields)
+    );
+}
 
-                //Divide created chambers
-                self.divide_chamber(start_field_x, start_field_y, end_field_x, wall_field, first_chamber_orientation, maze_array);
-                self.divide_chamber(start_field_x, wall_field + 1, end_field_x, end_field_y, second_chamber_orientation, maze_array);      
-            }
+//Select wall dividing chamber and passage in it, None if chamber is too small to be divided
+fn divide(chamber: Chamber, orientation: Orientation, random_engine: &mut Pcg64) -> Option<Division> {
+    let Chamber { start_field_x, start_field_y, end_field_x, end_field_y } = chamber;
 
-            _ => {
-                let wall_field = self.random_engine.gen_range(start_field_x..end_field_x);
+    if (end_field_x - start_field_x) < 1 || (end_field_y - start_field_y) < 1 {
+        return None;
+    }
 
-                //Same as before but vertically
-                let mut wall_index = wall_field * 2 + 1;
-                wall_index += 1;
+    match orientation {
+        Orientation::Horizontal => {
+            let wall_field = random_engine.gen_range(start_field_y..end_field_y);
 
-                for n in (start_field_y * 2 + 1)..(end_field_y * 2 + 2) { 
-                    maze_array[n * self.maze_size + wall_index] = true;
-                }
+            //Get array index of randomly selected maze field
+            let mut wall_index = wall_field * 2 + 1;
+            wall_index += 1; //Wall will be drawn in position next to the selected field
 
-                let passage_field = self.random_engine.gen_range(start_field_y..=end_field_y); 
+            let passage_field = random_engine.gen_range(start_field_x..=end_field_x); //Select maze field where passage will be placed
 
-                maze_array[(passage_field * 2 + 1) * self.maze_size + wall_index] = false;
+            //There are two chambers divided by horizontal wall
+            let first_chamber = Chamber { end_field_y: wall_field, ..chamber };
+            let second_chamber = Chamber { start_field_y: wall_field + 1, ..chamber };
 
-                let first_chamber_orientation = self.get_orientation(start_field_x, start_field_y, wall_field, end_field_y);
-                let second_chamber_orientation = self.get_orientation(wall_field + 1, start_field_y, end_field_x, end_field_y); 
+            let first_chamber_orientation = get_orientation(first_chamber, random_engine);
+            let second_chamber_orientation = get_orientation(second_chamber, random_engine);
 
-                self.divide_chamber(start_field_x, start_field_y, wall_field, end_field_y, first_chamber_orientation, maze_array);
-                self.divide_chamber(wall_field + 1, start_field_y, end_field_x, end_field_y, second_chamber_orientation, maze_array); 
-            }
+            Some(Division {
+                orientation,
+                wall_index,
+                wall_range: (start_field_x * 2 + 1)..(end_field_x * 2 + 2),
+                passage_index: passage_field * 2 + 1,
+                chambers: [(first_chamber, first_chamber_orientation), (second_chamber, second_chamber_orientation)]
+            })
         }
-    }
 
-    fn get_orientation(&mut self, start_field_x: usize, start_field_y: usize, end_field_x: usize, end_field_y: usize) -> Orientation {
-        let chamber_width = end_field_x - start_field_x;
-        let chamber_height = end_field_y - start_field_y;
+        Orientation::Vertical => {
+            let wall_field = random_engine.gen_range(start_field_x..end_field_x);
+
+            //Same as before but vertically
+            let mut wall_index = wall_field * 2 + 1;
+            wall_index += 1;
+
+            let passage_field = random_engine.gen_range(start_field_y..=end_field_y);
 
-        if chamber_width > chamber_height
-        {
-            return Orientation::Vertical;
-        } 
-    
-        if chamber_width < chamber_height
-        {
-            return Orientation::Horizontal;
+            let first_chamber = Chamber { end_field_x: wall_field, ..chamber };
+            let second_chamber = Chamber { start_field_x: wall_field + 1, ..chamber };
+
+            let first_chamber_orientation = get_orientation(first_chamber, random_engine);
+            let second_chamber_orientation = get_orientation(second_chamber, random_engine);
+
+            Some(Division {
+                orientation,
+                wall_index,
+                wall_range: (start_field_y * 2 + 1)..(end_field_y * 2 + 2),
+                passage_index: passage_field * 2 + 1,
+                chambers: [(first_chamber, first_chamber_orientation), (second_chamber, second_chamber_orientation)]
+            })
         }
+    }
+}
 
-        let orientation: Orientation = self.random_engine.gen();
+fn get_orientation(chamber: Chamber, random_engine: &mut Pcg64) -> Orientation {
+    let chamber_width = chamber.end_field_x - chamber.start_field_x;
+    let chamber_height = chamber.end_field_y - chamber.start_field_y;
 
-        orientation
+    if chamber_width > chamber_height
+    {
+        return Orientation::Vertical;
     }
+
+    if chamber_width < chamber_height
+    {
+        return Orientation::Horizontal;
+    }
+
+    let orientation: Orientation = random_engine.gen();
+
+    orientation
 }
@@ -0,0 +1,117 @@
+//Maze generator that uses Wilson's alghorithm (loop-erased random walks)
+//Every possible maze is generated with the same probability, unlike DFS or RD which prefer some shapes
+//Like in RD generator maze fields have odd indices and walls are between them
+use crate::maze_generator::*;
+
+use rand::Rng;
+
+pub struct GeneratorWilson<'a> {
+    maze_size: usize,
+    maze_fields: usize,
+    random_engine: &'a mut Pcg64
+}
+
+impl GeneratorWilson<'_> {
+    pub fn new(maze_size: usize, random_engine: &mut Pcg64) -> GeneratorWilson<'_> {
+        GeneratorWilson {
+            maze_size,
+            maze_fields: (maze_size - 1) / 2,
+            random_engine
+        }
+    }
+
+    pub fn generate(&mut self) -> MazeGrid {
+        self.generate_with_progress(&mut |_| ())
+    }
+
+    //Progress is counted in fields added to maze, first walks are the longest so it speeds up towards the end
+    pub fn generate_with_progress(&mut self, progress: &mut dyn FnMut(f32)) -> MazeGrid {
+        //Init grid (completely filled with walls)
+        let mut maze_grid = MazeGrid::new(self.maze_size, true);
+
+        let mut in_maze = vec![false; self.maze_fields * self.maze_fields];
+
+        //Direction in which walk left every field, later visits overwrite it so loops are erased automatically
+        let mut walk_directions = vec![Direction::Top; self.maze_fields * self.maze_fields];
+
+        //Maze starts with one random field, walks are added until every field is part of it
+        let first_field = (self.random_engine.gen_range(0..self.maze_fields), self.random_engine.gen_range(0..self.maze_fields));
+
+        in_maze[first_field.1 * self.maze_fields + first_field.0] = true;
+        let (first_x, first_y) = Self::get_position(first_field);
+        maze_grid.set_wall(first_x, first_y, false);
+
+        let mut progress = Progress::new(progress, self.maze_fields * self.maze_fields);
+        progress.advance(1);
+
+        for walk_start in 0..(self.maze_fields * self.maze_fields) {
+            if in_maze[walk_start] {
+                continue;
+            }
+
+            let walk_start = (walk_start % self.maze_fields, walk_start / self.maze_fields);
+
+            //Random walk until field that is already in maze is hit
+            let mut field = walk_start;
+
+            while !in_maze[field.1 * self.maze_fields + field.0] {
+                let (direction, next_field) = self.random_step(field);
+
+                walk_directions[field.1 * self.maze_fields + field.0] = direction;
+                field = next_field;
+            }
+
+            //Follow walk again using last direction from every field, this path doesn't have loops
+            let mut field = walk_start;
+
+            while !in_maze[field.1 * self.maze_fields + field.0] {
+                let next_field = self.get_neighbour(field, walk_directions[field.1 * self.maze_fields + field.0]);
+
+                in_maze[field.1 * self.maze_fields + field.0] = true;
+                let (x, y) = Self::get_position(field);
+                let (next_x, next_y) = Self::get_position(next_field);
+                maze_grid.set_wall(x, y, false);
+
+                //Passage is in the middle between two fields
+                maze_grid.set_wall((x + next_x) / 2, (y + next_y) / 2, false);
+
+                field = next_field;
+                progress.advance(1);
+            }
+        }
+
+        maze_grid
+    }
+
+    //Grid position of maze field
+    fn get_position(field: (usize, usize)) -> (usize, usize) {
+        (field.0 * 2 + 1, field.1 * 2 + 1)
+    }
+
+    //Move to random neighbour, directions leading outside of maze are drawn again
+    fn random_step(&mut self, field: (usize, usize)) -> (Direction, (usize, usize)) {
+        loop {
+            let direction: Direction = self.random_engine.gen();
+
+            let can_move = match direction {
+                Direction::Top => field.1 > 0,
+                Direction::Bottom => field.1 < self.maze_fields - 1,
+                Direction::Left => field.0 > 0,
+                Direction::Right => field.0 < self.maze_fields - 1
+            };
+
+            if can_move {
+                return (direction, self.get_neighbour(field, direction));
+            }
+        }
+    }
+
+    fn get_neighbour(&self, field: (usize, usize), direction: Direction) -> (usize, usize) {
+        match direction {
+            Direction::Top => (field.0, field.1 - 1),
+            Direction::Bottom => (field.0, field.1 + 1),
+            Direction::Left => (field.0 - 1, field.1),
+            Direction::Right => (field.0 + 1, field.1)
+        }
+    }
+}
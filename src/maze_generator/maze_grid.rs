@@ -0,0 +1,112 @@
+//Maze array packed into bits, every field takes one bit (set bit is wall)
+//Rows aren't aligned to words so whole grid takes size * size / 8 bytes
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MazeGrid {
+    size: usize,
+    words: Vec<u64>
+}
+
+impl MazeGrid {
+    //Square grid with every field set to wall or empty
+    pub fn new(size: usize, wall: bool) -> Self {
+        let field_count = size * size;
+        let mut words = vec![if wall { u64::MAX } else { 0 }; field_count.div_ceil(WORD_BITS)];
+
+        //Bits after the last field stay empty so grids with the same fields are always equal
+        if let Some(last_word) = words.last_mut() {
+            if !field_count.is_multiple_of(WORD_BITS) {
+                *last_word &= (1 << (field_count % WORD_BITS)) - 1;
+            }
+        }
+
+        MazeGrid {
+            size,
+            words
+        }
+    }
+
+    //Grid from array with one bool per field stored row by row
+    pub fn from_fields(size: usize, fields: &[bool]) -> Self {
+        let mut maze_grid = Self::new(size, false);
+
+        for (index, &wall) in fields.iter().enumerate().take(size * size) {
+            maze_grid.set_bit(index, wall);
+        }
+
+        maze_grid
+    }
+
+    //Unpacked copy with one bool per field, used for saving maze
+    pub fn to_fields(&self) -> Vec<bool> {
+        (0..self.size * self.size).map(|index| self.get_bit(index)).collect()
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_wall(&self, x: usize, y: usize) -> bool {
+        debug_assert!(x < self.size && y < self.size, "Field ({}, {}) is outside of maze grid", x, y);
+
+        self.get_bit(y * self.size + x)
+    }
+
+    pub fn set_wall(&mut self, x: usize, y: usize, wall: bool) {
+        debug_assert!(x < self.size && y < self.size, "Field ({}, {}) is outside of maze grid", x, y);
+
+        self.set_bit(y * self.size + x, wall);
+    }
+
+    fn get_bit(&self, index: usize) -> bool {
+        (self.words[index / WORD_BITS] >> (index % WORD_BITS)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, index: usize, value: bool) {
+        let mask = 1 << (index % WORD_BITS);
+
+        match value {
+            true => self.words[index / WORD_BITS] |= mask,
+            false => self.words[index / WORD_BITS] &= !mask
+        }
+    }
+}
+
+//Maze grid that can be changed from many threads at once, used by parallel generators
+//Fields are changed by atomic operations on their words so threads can work on neighbouring fields sharing one word
+pub struct SharedMazeGrid {
+    size: usize,
+    words: Vec<AtomicU64>
+}
+
+impl SharedMazeGrid {
+    pub fn from_grid(maze_grid: MazeGrid) -> Self {
+        SharedMazeGrid {
+            size: maze_grid.size,
+            words: maze_grid.words.into_iter().map(AtomicU64::new).collect()
+        }
+    }
+
+    pub fn into_grid(self) -> MazeGrid {
+        MazeGrid {
+            size: self.size,
+            words: self.words.into_iter().map(AtomicU64::into_inner).collect()
+        }
+    }
+
+    //Writes from different threads aren't ordered, threads have to be joined before grid is read
+    pub fn set_wall(&self, x: usize, y: usize, wall: bool) {
+        debug_assert!(x < self.size && y < self.size, "Field ({}, {}) is outside of maze grid", x, y);
+
+        let index = y * self.size + x;
+        let mask = 1 << (index % WORD_BITS);
+
+        match wall {
+            true => self.words[index / WORD_BITS].fetch_or(mask, Ordering::Relaxed),
+            false => self.words[index / WORD_BITS].fetch_and(!mask, Ordering::Relaxed)
+        };
+    }
+}
@@ -0,0 +1,38 @@
+//Generation progress reported to callback as completed fraction (0 - 1)
+//Callback is called only when progress grows by at least PROGRESS_STEP, so it doesn't slow down generation
+const PROGRESS_STEP: f32 = 0.01;
+
+pub struct Progress<'a> {
+    callback: &'a mut dyn FnMut(f32),
+    total: usize,
+    completed: usize,
+    step: usize,
+    next_report: usize
+}
+
+impl<'a> Progress<'a> {
+    //Total is amount of work in any unit generator can count (fields, rows)
+    pub fn new(callback: &'a mut dyn FnMut(f32), total: usize) -> Self {
+        Progress {
+            callback,
+            total: total.max(1),
+            completed: 0,
+            step: ((total as f32 * PROGRESS_STEP) as usize).max(1),
+            next_report: 0
+        }
+    }
+
+    pub fn advance(&mut self, count: usize) {
+        self.set_completed(self.completed + count);
+    }
+
+    //Estimated totals can be exceeded so progress is clamped to 1
+    pub fn set_completed(&mut self, completed: usize) {
+        self.completed = completed;
+
+        if completed >= self.next_report {
+            (self.callback)(f32::min(completed as f32 / self.total as f32, 1.0));
+            self.next_report = completed + self.step;
+        }
+    }
+}
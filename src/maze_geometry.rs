@@ -0,0 +1,321 @@
+//Static maze geometry
+//Maze doesn't change after generation so model matrices of quads are calculated once, when their chunk is drawn for the first time
+//Quads of chunk are stored in one array and every cell keeps range of its quads
+//Only chunks around the player are kept, whole geometry of big maze wouldn't fit into memory
+
+use std::collections::HashMap;
+
+use dsdmaze::maze_generator::{Direction, MazeGenerator, WeaveField};
+
+use crate::maze_renderer::MAX_TEXTURES;
+
+//Texture indices used for maze quads
+const WALL_TEXTURE: i32 = 0;
+const FLOOR_TEXTURE: i32 = 1;
+pub const CEILING_TEXTURE: i32 = 2;
+const EXIT_TEXTURE: i32 = 3;
+const FAKE_EXIT_TEXTURE: i32 = 4;
+const ADDITIONAL_WALL_TEXTURES: i32 = 5; //Textures of wall materials other than default are loaded after exit textures
+
+//Accessibility markers of exits, colors are from Okabe-Ito palette so they are distinguishable with color vision deficiency
+//Pattern is in W component: 1 is pulsing solid outline, 2 is dashed outline, so exits differ by shape too
+const EXIT_MARKER: [f32; 4] = [0.94, 0.89, 0.26, 1.0];
+const DECOY_MARKER: [f32; 4] = [0.34, 0.71, 0.91, 2.0];
+
+//Tunnel floor of weave crossing is this much lower than ground floor, tunnel ceiling is ground floor of the bridge
+pub const TUNNEL_DEPTH: f32 = 1.0;
+
+//Chunk is square of cells with given size
+//When too many chunks are cached, chunks further (in chunks) than kept distance from the one being built are dropped
+//Kept distance covers the biggest render distance so chunks around the player aren't built again
+const CHUNK_SIZE: usize = 16;
+const MAX_CACHED_CHUNKS: usize = 1024;
+const KEPT_CHUNK_DISTANCE: usize = 8;
+
+struct GeometryChunk {
+    quads: Vec<(glm::Mat4, i32)>,
+    cell_offsets: Vec<usize>
+}
+
+pub struct MazeGeometry {
+    draw_ceiling: bool,
+    wall_height: f32,
+    chunks: HashMap<(usize, usize), GeometryChunk>
+}
+
+impl MazeGeometry {
+    pub fn new(draw_ceiling: bool, wall_height: f32) -> Self {
+        Self {
+            draw_ceiling,
+            wall_height,
+            chunks: HashMap::new()
+        }
+    }
+
+    fn build_chunk(maze_generator: &MazeGenerator, chunk_x: usize, chunk_z: usize, draw_ceiling: bool, wall_height: f32) -> GeometryChunk {
+        let maze_size = maze_generator.get_maze_size();
+        let mut quads = Vec::new();
+        let mut cell_offsets = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE + 1);
+
+        for i in chunk_z * CHUNK_SIZE..(chunk_z + 1) * CHUNK_SIZE {
+            for j in chunk_x * CHUNK_SIZE..(chunk_x + 1) * CHUNK_SIZE {
+                cell_offsets.push(quads.len());
+
+                //Chunks on the edge can reach outside of maze
+                if i >= maze_size || j >= maze_size {
+                    continue;
+                }
+
+                //Ramps of weave crossings are walls on the ground level
+                if let Some(WeaveField::Ramp(direction)) = maze_generator.get_weave_field(j, i) {
+                    Self::add_ramp_quads(&mut quads, maze_generator, i, j, direction, draw_ceiling, wall_height);
+                    continue;
+                }
+
+                //Walls and border don't have any geometry, only empty fields are drawn
+                //Cells on the edge are skipped too since they have no neighbours on one side
+                if maze_generator.is_wall(j, i) || i == 0 || j == 0 || i == maze_size - 1 || j == maze_size - 1 {
+                    continue;
+                }
+
+                Self::add_cell_quads(&mut quads, maze_generator, i, j, draw_ceiling, wall_height);
+            }
+        }
+
+        cell_offsets.push(quads.len());
+
+        GeometryChunk {
+            quads,
+            cell_offsets
+        }
+    }
+
+    //Tint of every texture used by maze quads, additional wall textures use wall tint and exits aren't tinted
+    pub fn get_texture_tints(wall_variety: usize, wall_tint: [f32; 3], floor_tint: [f32; 3], ceiling_tint: [f32; 3]) -> [[f32; 4]; MAX_TEXTURES] {
+        let mut texture_tints = [[1.0; 4]; MAX_TEXTURES];
+        let to_vec4 = |tint: [f32; 3]| [tint[0], tint[1], tint[2], 1.0];
+
+        texture_tints[WALL_TEXTURE as usize] = to_vec4(wall_tint);
+        texture_tints[FLOOR_TEXTURE as usize] = to_vec4(floor_tint);
+        texture_tints[CEILING_TEXTURE as usize] = to_vec4(ceiling_tint);
+
+        for texture_tint in texture_tints.iter_mut().skip(ADDITIONAL_WALL_TEXTURES as usize).take(wall_variety.saturating_sub(1)) {
+            *texture_tint = to_vec4(wall_tint);
+        }
+
+        texture_tints
+    }
+
+    //Markers drawn over exit and decoy exit textures, no texture is marked when they are disabled
+    pub fn get_texture_markers(exit_marker: bool) -> [[f32; 4]; MAX_TEXTURES] {
+        let mut texture_markers = [[0.0; 4]; MAX_TEXTURES];
+
+        if exit_marker {
+            texture_markers[EXIT_TEXTURE as usize] = EXIT_MARKER;
+            texture_markers[FAKE_EXIT_TEXTURE as usize] = DECOY_MARKER;
+        }
+
+        texture_markers
+    }
+
+    //Get quads of one cell as (model matrix, texture index), chunk containing the cell is built if it isn't cached
+    pub fn get_cell(&mut self, maze_generator: &MazeGenerator, x: usize, z: usize) -> &[(glm::Mat4, i32)] {
+        let chunk_key = (x / CHUNK_SIZE, z / CHUNK_SIZE);
+
+        if !self.chunks.contains_key(&chunk_key) && self.chunks.len() >= MAX_CACHED_CHUNKS {
+            self.chunks.retain(|&(chunk_x, chunk_z), _| chunk_x.abs_diff(chunk_key.0) <= KEPT_CHUNK_DISTANCE && chunk_z.abs_diff(chunk_key.1) <= KEPT_CHUNK_DISTANCE);
+        }
+
+        let (draw_ceiling, wall_height) = (self.draw_ceiling, self.wall_height);
+        let chunk = self.chunks.entry(chunk_key)
+            .or_insert_with(|| Self::build_chunk(maze_generator, chunk_key.0, chunk_key.1, draw_ceiling, wall_height));
+        let cell_index = (z % CHUNK_SIZE) * CHUNK_SIZE + x % CHUNK_SIZE;
+
+        &chunk.quads[chunk.cell_offsets[cell_index]..chunk.cell_offsets[cell_index + 1]]
+    }
+
+    //Stretch wall quad vertically, bottom edge stays on the floor
+    fn scale_wall(model: glm::Mat4, wall_height: f32) -> glm::Mat4 {
+        let model = glm::translate(&model, &glm::vec3(0.0, (wall_height - 1.0) * 0.5, 0.0));
+
+        glm::scale(&model, &glm::vec3(1.0, wall_height, 1.0))
+    }
+
+    //Wall face is drawn towards walls, ramps are open so tunnel entrance and bridge are visible
+    fn is_wall_face(maze_generator: &MazeGenerator, x: usize, y: usize) -> bool {
+        maze_generator.is_wall(x, y) && 
+            !matches!(maze_generator.get_weave_field(x, y), Some(WeaveField::Ramp(_)))
+    }
+
+    //Texture of walls in cell depending on wall material of its region
+    fn get_wall_texture(maze_generator: &MazeGenerator, i: usize, j: usize) -> i32 {
+        match maze_generator.get_wall_material(j, i) {
+            0 => WALL_TEXTURE,
+            material => ADDITIONAL_WALL_TEXTURES + material as i32 - 1
+        }
+    }
+
+    //Ramp has sloped floor going down one tunnel depth and walls on its sides reaching down to the ramp
+    fn add_ramp_quads(quads: &mut Vec<(glm::Mat4, i32)>, maze_generator: &MazeGenerator, i: usize, j: usize, direction: Direction, draw_ceiling: bool, wall_height: f32) {
+        let wall_texture = Self::get_wall_texture(maze_generator, i, j);
+
+        //Angle around Y which turns ramp going down to the bottom into given direction
+        let angle: f32 = match direction {
+            Direction::Bottom => 0.0,
+            Direction::Top => 180.0,
+            Direction::Right => 90.0,
+            Direction::Left => -90.0
+        };
+
+        //Side walls are the same as left and right walls of ramp going down to the bottom, turned with the ramp
+        for side in [-1.0, 1.0] {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3(j as f32, 0.0, i as f32));
+            model = glm::rotate(&model, f32::to_radians(angle), &glm::vec3(0.0, 1.0, 0.0));
+            model = glm::translate(&model, &glm::vec3(0.5 * side, -TUNNEL_DEPTH, 0.0));
+            model = glm::rotate(&model, f32::to_radians(90.0 * side), &glm::vec3(0.0, 1.0, 0.0));
+
+            quads.push((Self::scale_wall(model, wall_height + TUNNEL_DEPTH), wall_texture));
+        }
+
+        //Floor is tilted by 45 degrees so it's longer than one field
+        let mut model = glm::Mat4::identity();
+        model = glm::translate(&model, &glm::vec3(j as f32, -0.5 - TUNNEL_DEPTH * 0.5, i as f32));
+        model = glm::rotate(&model, f32::to_radians(angle), &glm::vec3(0.0, 1.0, 0.0));
+        model = glm::rotate(&model, f32::to_radians(135.0), &glm::vec3(1.0, 0.0, 0.0));
+        model = glm::scale(&model, &glm::vec3(1.0, std::f32::consts::SQRT_2, 1.0));
+
+        quads.push((model, FLOOR_TEXTURE));
+
+        if draw_ceiling {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3(j as f32, wall_height - 0.5, i as f32));
+            model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(1.0, 0.0, 0.0));
+
+            quads.push((model, CEILING_TEXTURE));
+        }
+    }
+
+    //Tunnel under the bridge of weave crossing has walls on its sides, its ceiling is under floor of the bridge
+    fn add_tunnel_quads(quads: &mut Vec<(glm::Mat4, i32)>, wall_texture: i32, i: usize, j: usize, vertical_tunnel: bool) {
+        let angle: f32 = if vertical_tunnel { 0.0 } else { 90.0 };
+
+        for side in [-1.0, 1.0] {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3(j as f32, 0.0, i as f32));
+            model = glm::rotate(&model, f32::to_radians(angle), &glm::vec3(0.0, 1.0, 0.0));
+            model = glm::translate(&model, &glm::vec3(0.5 * side, -TUNNEL_DEPTH, 0.0));
+            model = glm::rotate(&model, f32::to_radians(90.0 * side), &glm::vec3(0.0, 1.0, 0.0));
+
+            quads.push((Self::scale_wall(model, TUNNEL_DEPTH), wall_texture));
+        }
+
+        let mut model = glm::Mat4::identity();
+        model = glm::translate(&model, &glm::vec3(j as f32, -0.5 - TUNNEL_DEPTH, i as f32));
+        model = glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(1.0, 0.0, 0.0));
+
+        quads.push((model, FLOOR_TEXTURE));
+
+        let mut model = glm::Mat4::identity();
+        model = glm::translate(&model, &glm::vec3(j as f32, -0.5, i as f32));
+        model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(1.0, 0.0, 0.0));
+
+        quads.push((model, CEILING_TEXTURE));
+    }
+
+    fn add_cell_quads(quads: &mut Vec<(glm::Mat4, i32)>, maze_generator: &MazeGenerator, i: usize, j: usize, draw_ceiling: bool, wall_height: f32) {
+        let wall_texture = Self::get_wall_texture(maze_generator, i, j);
+
+        //Decoy exits replace border wall texture
+        let side_texture = |border: Direction| match maze_generator.is_decoy_exit(j, i, border) {
+            true => FAKE_EXIT_TEXTURE,
+            false => wall_texture
+        };
+
+        //Left wall
+        if Self::is_wall_face(maze_generator, j - 1, i) {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
+            model = glm::translate(&model, &glm::vec3(-0.5, 0.0, 0.0)); //Move left a bit
+            model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(0.0, 1.0, 0.0)); //Rotate by 90 degrees around Y
+
+            quads.push((Self::scale_wall(model, wall_height), side_texture(Direction::Left)));
+        }
+
+        //Right wall
+        if Self::is_wall_face(maze_generator, j + 1, i) {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
+            model = glm::translate(&model, &glm::vec3(0.5, 0.0, 0.0)); //Move right a bit
+            model = glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(0.0, 1.0, 0.0)); //Rotate by 90 degrees around Y
+
+            quads.push((Self::scale_wall(model, wall_height), side_texture(Direction::Right)));
+        }
+
+        //Front wall
+        if Self::is_wall_face(maze_generator, j, i - 1) {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
+            model = glm::translate(&model, &glm::vec3(0.0, 0.0, -0.5)); //Move front a bit
+            model = glm::rotate(&model, f32::to_radians(180.0), &glm::vec3(0.0, 1.0, 0.0));
+
+            quads.push((Self::scale_wall(model, wall_height), side_texture(Direction::Top)));
+        }
+
+        //Back wall
+        if Self::is_wall_face(maze_generator, j, i + 1) {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0)); //Move to right position
+            model = glm::translate(&model, &glm::vec3(0.0, 0.0, 0.5)); //Move back a bit
+
+            quads.push((Self::scale_wall(model, wall_height), side_texture(Direction::Bottom)));
+        }
+
+        //Floor
+        let mut model = glm::Mat4::identity();
+        model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0));
+        model = glm::translate(&model, &glm::vec3(0.0, -0.5, 0.0));
+        model = glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(1.0, 0.0, 0.0));
+
+        quads.push((model, FLOOR_TEXTURE));
+
+        //Ceiling
+        if draw_ceiling {
+            let mut model = glm::Mat4::identity();
+            model = glm::translate(&model, &glm::vec3((j as f32)*1.0, 0.0, (i as f32)*1.0));
+            model = glm::translate(&model, &glm::vec3(0.0, wall_height - 0.5, 0.0)); //Ceiling is on top of the walls
+            model = glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(1.0, 0.0, 0.0));
+
+            quads.push((model, CEILING_TEXTURE));
+        }
+
+        if let Some(WeaveField::Crossing(vertical_tunnel)) = maze_generator.get_weave_field(j, i) {
+            Self::add_tunnel_quads(quads, wall_texture, i, j, vertical_tunnel);
+        }
+
+        //Exit
+        if j == maze_generator.get_exit().0 as usize && i == maze_generator.get_exit().1 as usize {
+            let mut model = glm::Mat4::identity();
+
+            match maze_generator.get_end_border() {
+                Direction::Top => {
+                    model = model * glm::translate(&model, &glm::vec3(j as f32, 0.0, (i as f32) - 0.5));
+                    model = model * glm::rotate(&model, f32::to_radians(180.0), &glm::vec3(0.0, 1.0, 0.0));
+                },
+                Direction::Bottom => {
+                    model = model * glm::translate(&model, &glm::vec3(j as f32, 0.0, (i as f32) + 0.5));
+                },
+                Direction::Left => {
+                    model = model * glm::translate(&model, &glm::vec3((j as f32) - 0.5, 0.0, i as f32));
+                    model = model * glm::rotate(&model, f32::to_radians(-90.0), &glm::vec3(0.0, 1.0, 0.0));
+                },
+                Direction::Right => {
+                    model = model * glm::translate(&model, &glm::vec3((j as f32) + 0.5, 0.0, i as f32));
+                    model = model * glm::rotate(&model, f32::to_radians(90.0), &glm::vec3(0.0, 1.0, 0.0));
+                },
+            }
+
+            quads.push((Self::scale_wall(model, wall_height), EXIT_TEXTURE));
+        }
+    }
+}
@@ -1,18 +1,94 @@
-use std::fmt;
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
 
 pub mod vulkan_renderer;
 pub mod gl_renderer;
+mod bitmap_font;
 
+#[derive(Serialize, Deserialize)]
 pub enum RenderingAPI {
+    #[serde(rename = "OpenGL")]
     OPENGL,
+    #[serde(rename = "Vulkan")]
     VULKAN
 }
 
+//Filtering of maze textures, nearest keeps texels sharp for pixel art texture packs
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum TextureFilter {
+    #[serde(rename = "Linear")]
+    LINEAR,
+    #[serde(rename = "Nearest")]
+    NEAREST
+}
+
+//Effect applied to rendered scene by post process pass, overlays are drawn after it
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub enum PostEffect {
+    #[serde(rename = "Vignette")]
+    Vignette
+}
+
+impl PostEffect {
+    //Name of fragment shader applying the effect, every effect shares post vertex shader
+    pub fn shader_name(&self) -> &str {
+        match *self {
+            PostEffect::Vignette => "vignettefragmentshader"
+        }
+    }
+}
+
+//Color space of texture data, colors are stored as sRGB while data (like normal vectors) has to be read without conversion
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorSpace {
+    SRGB,
+    LINEAR
+}
+
+//Texture file with color space it's stored in
+#[derive(Clone)]
+pub struct TextureDescriptor {
+    pub path: String,
+    pub color_space: ColorSpace
+}
+
+impl TextureDescriptor {
+    pub fn new(path: &str, color_space: ColorSpace) -> Self {
+        TextureDescriptor {
+            path: String::from(path),
+            color_space
+        }
+    }
+}
+
 pub enum RenderResult {
     RenderFinished,
     VkOutOfDate
 }
 
+//Errors of renderer setup, they are returned instead of panicking so program can report what went wrong
+#[derive(Debug)]
+pub enum RendererError {
+    InvalidMesh(String),
+    TextureLoad(String),
+    ShaderLoad(String),
+    PipelineCreation(String)
+}
+
+impl fmt::Display for RendererError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RendererError::InvalidMesh(message) => write!(f, "Invalid mesh: {}", message),
+            RendererError::TextureLoad(message) => write!(f, "Loading textures failed: {}", message),
+            RendererError::ShaderLoad(message) => write!(f, "Loading shaders failed: {}", message),
+            RendererError::PipelineCreation(message) => write!(f, "Creating pipeline failed: {}", message)
+        }
+    }
+}
+
+impl Error for RendererError {}
+
 impl fmt::Display for RenderingAPI {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -22,33 +98,428 @@ impl fmt::Display for RenderingAPI {
     }
 }
 
+impl fmt::Display for TextureFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TextureFilter::LINEAR => write!(f, "Linear"),
+            TextureFilter::NEAREST => write!(f, "Nearest")
+        }
+    }
+}
+
+impl fmt::Display for PostEffect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PostEffect::Vignette => write!(f, "Vignette")
+        }
+    }
+}
+
+//Maximum number of lights, shaders use array of this size
+pub const MAX_LIGHTS: usize = 4;
+
+//Maximum number of maze textures, shaders use arrays of this size
+pub const MAX_TEXTURES: usize = 16;
+
+//Mesh registered by init_mesh, it's used for maze quads and overlays
+pub const QUAD_MESH_NAME: &str = "quad";
+
+//Mesh drawn by draw_skybox, it has to be registered before skybox is enabled
+pub const SKYBOX_MESH_NAME: &str = "skybox";
+
+//Cubemap faces are loaded in this order (+X, -X, +Y, -Y, +Z, -Z)
+pub const SKYBOX_FACES: [&str; 6] = ["right.png", "left.png", "top.png", "bottom.png", "front.png", "back.png"];
+
+//Width and height of shadow map in pixels
+pub const SHADOW_MAP_SIZE: u32 = 2048;
+
+//Mesh registered in renderer, it's index into renderer's list of meshes
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MeshId(usize);
+
+//Point light in world space, radius is distance at which light fades out completely
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct Light {
+    pub position: glm::Vec3,
+    pub radius: f32, //vec3 needs to be aligned for 16 bytes, float fits into last 4 bytes so it doesn't need padding
+    pub color: glm::Vec3,
+    pub _padding: [u8; 4] //Array elements are aligned for 16 bytes too
+}
+
+#[repr(C)]
 #[derive(Copy, Clone)]
 pub struct UniformData {
     pub view_matrix: glm::Mat4,
     pub projection_matrix: glm::Mat4,
-    pub light_position: glm::Vec3,
-    pub _padding: [u8; 4], //vec3 needs to be aligned for 16 bytes, since it's 12 bytes in size, additional 4 bytes are needed between
-    pub light_color: glm::Vec3
+    pub lights: [Light; MAX_LIGHTS],
+    pub light_count: i32, //Only this many lights from the beginning of array are used
+    pub gamma: f32,
+    pub normal_mapping: i32, //Used as bool in shaders, 1 when normal maps are loaded
+    pub shadows: i32, //Used as bool in shaders, 1 when first light casts shadows
+    pub light_space_matrix: glm::Mat4, //Projection and view of first light, used for rendering and sampling shadow map
+    pub texture_tints: [[f32; 4]; MAX_TEXTURES], //Color multiplied with texture of given index, vec4 because array elements are aligned for 16 bytes
+    pub texture_markers: [[f32; 4]; MAX_TEXTURES], //Marker drawn over texture of given index, RGB is its color and W its pattern (0 for no marker)
+    pub marker_time: f32 //Seconds used to animate markers
+}
+
+//Minimap settings, size is relative to viewport height and radius is in maze cells
+const MINIMAP_SCALE: f32 = 0.3;
+const MINIMAP_MARGIN: f32 = 10.0;
+const MINIMAP_RADIUS: f32 = 10.0;
+
+const MINIMAP_BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const MINIMAP_WALL_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 0.9];
+const MINIMAP_FLOOR_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 0.9];
+const MINIMAP_PLAYER_COLOR: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+
+const PAUSE_TINT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+//Win screen message width relative to viewport width
+const WIN_SCREEN_SCALE: f32 = 0.6;
+
+//Text is drawn with bitmap font, every font pixel is square of this size in screen pixels
+//Characters and lines are separated by one font pixel and text has background with padding around it
+const TEXT_PIXEL_SIZE: f32 = 2.0;
+const TEXT_PADDING: f32 = 4.0;
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const TEXT_BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+
+//Loading screen progress bar, width is relative to viewport width and height is in pixels
+const LOADING_BAR_SCALE: f32 = 0.5;
+const LOADING_BAR_HEIGHT: f32 = 20.0;
+const LOADING_BAR_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 1.0];
+const LOADING_BAR_BACKGROUND_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 1.0];
+
+//Lines are overlay quads of this width in pixels
+//World space lines are cut at this clip space w so part behind camera isn't projected
+const LINE_WIDTH: f32 = 2.0;
+const LINE_NEAR_W: f32 = 0.01;
+
+//Vertex buffer has to consist of whole vertices and indices can't point outside of it
+pub fn check_mesh(vertex_buffer: &[f32], index_buffer: &[u32]) -> Result<(), RendererError> {
+    if !vertex_buffer.len().is_multiple_of(11) {
+        return Err(RendererError::InvalidMesh(format!("Vertex buffer length {} isn't multiple of 11", vertex_buffer.len())));
+    }
+
+    if let Some(&index) = index_buffer.iter().find(|&&index| index as usize >= vertex_buffer.len() / 11) {
+        return Err(RendererError::InvalidMesh(format!("Index {} is out of range of {} vertices", index, vertex_buffer.len() / 11)));
+    }
+
+    Ok(())
+}
+
+//Textures are stored in arrays of fixed size in shaders
+pub fn check_texture_count(texture_count: usize) -> Result<(), RendererError> {
+    match texture_count {
+        0 => Err(RendererError::TextureLoad(String::from("No texture was selected"))),
+        count if count > MAX_TEXTURES => Err(RendererError::TextureLoad(format!("Too many textures selected ({}), maximum is {}", count, MAX_TEXTURES))),
+        _ => Ok(())
+    }
+}
+
+//Load normal map or create flat one (pointing straight out of the surface) when texture doesn't have it
+pub fn load_normal_map(normal_map_path: Option<&str>) -> image::RgbaImage {
+    match normal_map_path.map(image::open) {
+        Some(Ok(normal_map)) => normal_map.into_rgba8(),
+        _ => image::RgbaImage::from_pixel(1, 1, image::Rgba([128, 128, 255, 255]))
+    }
+}
+
+//Faces of cubemap have to be square and of the same size
+pub fn load_cubemap_faces(faces_paths: &[String]) -> Result<Vec<image::RgbaImage>, RendererError> {
+    if faces_paths.len() != 6 {
+        return Err(RendererError::TextureLoad(format!("Cubemap needs 6 faces, {} were selected", faces_paths.len())));
+    }
+
+    let mut faces: Vec<image::RgbaImage> = Vec::new();
+
+    for face_path in faces_paths {
+        let face = image::open(face_path)
+            .map_err(|error| RendererError::TextureLoad(format!("{}: {}", face_path, error)))?
+            .into_rgba8();
+
+        if face.width() != face.height() || faces.first().is_some_and(|first_face| first_face.dimensions() != face.dimensions()) {
+            return Err(RendererError::TextureLoad(format!("{}: Cubemap faces have to be square and of the same size", face_path)));
+        }
+
+        faces.push(face);
+    }
+
+    Ok(faces)
+}
+
+//Cube around the camera with faces pointing inwards, only positions are used by skybox shaders
+//Every face is built from its outward direction and up vector so all of them have the same winding seen from inside
+pub fn skybox_mesh() -> (Vec<f32>, Vec<u32>) {
+    let faces = [
+        (glm::vec3(1.0, 0.0, 0.0), glm::vec3(0.0, 1.0, 0.0)),
+        (glm::vec3(-1.0, 0.0, 0.0), glm::vec3(0.0, 1.0, 0.0)),
+        (glm::vec3(0.0, 1.0, 0.0), glm::vec3(0.0, 0.0, 1.0)),
+        (glm::vec3(0.0, -1.0, 0.0), glm::vec3(0.0, 0.0, -1.0)),
+        (glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 1.0, 0.0)),
+        (glm::vec3(0.0, 0.0, -1.0), glm::vec3(0.0, 1.0, 0.0))
+    ];
+
+    let mut vertex_buffer = Vec::new();
+    let mut index_buffer = Vec::new();
+
+    for (face_index, (direction, up)) in faces.into_iter().enumerate() {
+        let right: glm::Vec3 = glm::cross(&direction, &up);
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+
+        for (x, y) in corners {
+            let position = direction + right * x + up * y;
+
+            vertex_buffer.extend_from_slice(&[position.x, position.y, position.z, (x + 1.0) / 2.0, (y + 1.0) / 2.0, 
+                -direction.x, -direction.y, -direction.z, right.x, right.y, right.z]);
+        }
+
+        let first_vertex = face_index as u32 * 4;
+        index_buffer.extend([0, 1, 2, 0, 2, 3].iter().map(|index| first_vertex + index));
+    }
+
+    (vertex_buffer, index_buffer)
+}
+
+//Build model matrix for overlay quad
+//Overlay uses screen coordinates in pixels with origin in top left corner
+pub fn overlay_transform(center: glm::Vec2, size: glm::Vec2, rotation: f32) -> glm::Mat4 {
+    let mut model = glm::Mat4::identity();
+    model = glm::translate(&model, &glm::vec3(center.x, center.y, 0.0));
+    model = glm::rotate(&model, rotation, &glm::vec3(0.0, 0.0, 1.0));
+    model = glm::scale(&model, &glm::vec3(size.x, size.y, 1.0));
+
+    model
+}
+
+//Size of text drawn by draw_text in pixels (including background)
+pub fn text_size(text: &str) -> glm::Vec2 {
+    let line_count = text.lines().count();
+    let longest_line = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+
+    glm::vec2(longest_line as f32 * (bitmap_font::GLYPH_WIDTH + 1) as f32, line_count as f32 * (bitmap_font::GLYPH_HEIGHT + 1) as f32) * TEXT_PIXEL_SIZE + 
+        glm::vec2(TEXT_PADDING, TEXT_PADDING) * 2.0
 }
 
 pub trait Renderer {
-    fn init_mesh(&mut self, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>);
+    //Upload mesh that can be drawn by returned id, mesh registered with name that is already used replaces the old one and keeps its id
+    //Vertex buffer has 11 components per vertex: position XYZ, texture UV, normal XYZ and tangent XYZ
+    fn register_mesh(&mut self, name: &str, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) -> Result<MeshId, RendererError>;
+
+    fn get_mesh_id(&self, name: &str) -> Option<MeshId>;
+
+    //Quad mesh used for maze and overlays
+    fn init_mesh(&mut self, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) -> Result<(), RendererError> {
+        self.register_mesh(QUAD_MESH_NAME, vertex_buffer, index_buffer).map(|_| ())
+    }
+
+    //Filter used by textures and normal maps loaded after it's set, linear by default
+    fn set_texture_filter(&mut self, texture_filter: TextureFilter);
+
+    //At most MAX_TEXTURES textures can be loaded, every texture is loaded in its own color space
+    fn load_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError>;
+
+    //Replace loaded textures with new ones, current textures are kept if loading fails
+    //Texture count can change, shaders loaded before are kept
+    fn reload_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError>;
+
+    //Normal maps are matched with textures by index, None means texture has no normal map
+    //Needs to be called after textures and before shaders are loaded
+    fn load_normal_maps(&mut self, normal_maps_paths: Vec<Option<String>>);
+
+    //Needs to be called after textures are loaded
+    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError>;
 
-    fn load_textures(&mut self, textures_paths: Vec<String>);
+    //Render shadow map from first light using given shaders
+    //Needs to be called before shaders are loaded, shadows stay disabled if it fails
+    fn enable_shadows(&mut self, vertex_shader_path: &str, fragment_shader_path: &str);
 
-    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str);
+    //Render scene into offscreen image and draw it to the screen with fullscreen triangle using given shaders, overlays are drawn on top of it
+    //Needs to be called before shaders are loaded, scene is drawn directly to the screen if it fails
+    fn enable_post_process(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError>;
 
     fn update_uniform_data(&mut self, uniform_data: UniformData);
 
-    fn draw(&mut self, model_matrix: glm::Mat4, texture_index: i32);
+    //Draw many instances of mesh at once, every instance is (model matrix, texture index)
+    fn draw_mesh_instanced(&mut self, mesh_id: MeshId, instances: &[(glm::Mat4, i32)]);
+
+    //Draw many quads at once, every instance is (model matrix, texture index)
+    fn draw_instanced(&mut self, instances: &[(glm::Mat4, i32)]) {
+        if let Some(quad_mesh) = self.get_mesh_id(QUAD_MESH_NAME) {
+            self.draw_mesh_instanced(quad_mesh, instances);
+        }
+    }
+
+    //Overlay shaders are shipped with the program, minimap, text and win screen can't be drawn without them
+    fn load_overlay_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError>;
+
+    //Load cubemap faces (in SKYBOX_FACES order) and shaders drawing it behind the scene
+    //Needs skybox mesh to be registered and maze shaders to be loaded, skybox stays disabled if it fails
+    fn enable_skybox(&mut self, faces_paths: Vec<String>, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError>;
+
+    //Transform is projection and view without translation so skybox doesn't move with the camera
+    //Skybox is drawn only where nothing else was drawn in the frame
+    fn draw_skybox(&mut self, transform: glm::Mat4);
+
+    //Build shaders again from paths they were loaded from, current shaders are kept if it fails
+    fn reload_shaders(&mut self);
+
+    //Draw maze as lines instead of filled polygons, overlays aren't affected
+    fn set_wireframe(&mut self, wireframe: bool);
+
+    //Draw quad on top of the scene, texture index lower than 0 draws quad with solid color
+    fn draw_overlay(&mut self, model_matrix: glm::Mat4, color: [f32; 4], texture_index: i32);
+
+    fn get_viewport_size(&self) -> (u32, u32);
 
     fn clear_color(&mut self, color: [f32; 4]);
 
     fn render(&mut self) -> RenderResult;
 
+    //Next render reads frame back before it's presented (while it's still back buffer or not presented swapchain image)
+    fn request_capture(&mut self);
+
+    //Frame read back by last render, None if it wasn't requested or can't be captured
+    fn capture_frame(&mut self) -> Option<image::RgbaImage>;
+
     fn resize_viewport(&mut self, window_width: u32, window_height: u32);
 
     fn cleanup(&mut self);
+
+    //Dim whole screen while game is paused
+    fn draw_pause_overlay(&mut self) {
+        let (viewport_width, viewport_height) = self.get_viewport_size();
+        let viewport_size = glm::vec2(viewport_width as f32, viewport_height as f32);
+
+        self.draw_overlay(overlay_transform(viewport_size / 2.0, viewport_size, 0.0), PAUSE_TINT_COLOR, -1);
+    }
+
+    //Dim screen and draw message texture in the middle, aspect is width/height of texture
+    fn draw_win_screen(&mut self, texture_index: i32, texture_aspect: f32) {
+        let (viewport_width, viewport_height) = self.get_viewport_size();
+        let viewport_size = glm::vec2(viewport_width as f32, viewport_height as f32);
+        let message_width = viewport_size.x * WIN_SCREEN_SCALE;
+
+        self.draw_overlay(overlay_transform(viewport_size / 2.0, viewport_size, 0.0), PAUSE_TINT_COLOR, -1);
+        self.draw_overlay(overlay_transform(viewport_size / 2.0, glm::vec2(message_width, message_width / texture_aspect), 0.0), 
+            [1.0, 1.0, 1.0, 1.0], texture_index);
+    }
+
+    //Draw minimap in top right corner of the screen
+    //Cells are (x, z, is_wall) in maze coordinates and player is (x, z, heading in radians)
+    fn draw_minimap(&mut self, cells: &[(f32, f32, bool)], player: (f32, f32, f32)) {
+        let (viewport_width, viewport_height) = self.get_viewport_size();
+
+        let minimap_size = viewport_height as f32 * MINIMAP_SCALE;
+        let cell_size = minimap_size / (MINIMAP_RADIUS * 2.0 + 1.0);
+        let center = glm::vec2(viewport_width as f32 - MINIMAP_MARGIN - minimap_size / 2.0, MINIMAP_MARGIN + minimap_size / 2.0);
+
+        self.draw_overlay(overlay_transform(center, glm::vec2(minimap_size, minimap_size), 0.0), MINIMAP_BACKGROUND_COLOR, -1);
+
+        for &(x, z, is_wall) in cells {
+            let offset = glm::vec2(x - player.0, z - player.1);
+
+            if offset.x.abs() > MINIMAP_RADIUS || offset.y.abs() > MINIMAP_RADIUS {
+                continue;
+            }
+
+            let color = if is_wall { MINIMAP_WALL_COLOR } else { MINIMAP_FLOOR_COLOR };
+
+            self.draw_overlay(overlay_transform(center + offset * cell_size, glm::vec2(cell_size, cell_size), 0.0), color, -1);
+        }
+
+        self.draw_player_marker(center, cell_size, player.2);
+    }
+
+    //Draw text with top left corner at given screen position, lines are separated by newline character
+    //Every horizontal run of font pixels is drawn as one solid color overlay quad so text doesn't need font texture
+    fn draw_text(&mut self, text: &str, x: f32, y: f32) {
+        let character_width = (bitmap_font::GLYPH_WIDTH + 1) as f32 * TEXT_PIXEL_SIZE;
+        let line_height = (bitmap_font::GLYPH_HEIGHT + 1) as f32 * TEXT_PIXEL_SIZE;
+
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let background_size = text_size(text);
+
+        self.draw_overlay(overlay_transform(glm::vec2(x, y) + background_size / 2.0, background_size, 0.0), TEXT_BACKGROUND_COLOR, -1);
+
+        for (line_index, line) in text.lines().enumerate() {
+            for (character_index, character) in line.chars().enumerate() {
+                let glyph_position = glm::vec2(x + TEXT_PADDING + character_index as f32 * character_width, y + TEXT_PADDING + line_index as f32 * line_height);
+
+                for (row_index, row) in bitmap_font::get_glyph(character).into_iter().enumerate() {
+                    for (run_start, run_length) in bitmap_font::get_row_runs(row) {
+                        let run_size = glm::vec2(run_length as f32, 1.0) * TEXT_PIXEL_SIZE;
+                        let run_position = glyph_position + glm::vec2(run_start as f32, row_index as f32) * TEXT_PIXEL_SIZE;
+
+                        self.draw_overlay(overlay_transform(run_position + run_size / 2.0, run_size, 0.0), TEXT_COLOR, -1);
+                    }
+                }
+            }
+        }
+    }
+
+    //Player marker with smaller quad in front of it showing heading
+    //Position is in screen coordinates and cell size is size of one maze cell in pixels
+    fn draw_player_marker(&mut self, position: glm::Vec2, cell_size: f32, heading: f32) {
+        let heading_direction = glm::vec2(heading.cos(), heading.sin());
+
+        self.draw_overlay(overlay_transform(position, glm::vec2(cell_size * 0.6, cell_size * 0.6), heading), MINIMAP_PLAYER_COLOR, -1);
+        self.draw_overlay(overlay_transform(position + heading_direction * cell_size * 0.5, glm::vec2(cell_size * 0.3, cell_size * 0.3), heading), 
+            MINIMAP_PLAYER_COLOR, -1);
+    }
+
+    //Progress bar in the middle of the screen with text above it, progress is completed fraction (0 - 1)
+    fn draw_loading_screen(&mut self, progress: f32) {
+        let (viewport_width, viewport_height) = self.get_viewport_size();
+        let center = glm::vec2(viewport_width as f32, viewport_height as f32) / 2.0;
+        let bar_size = glm::vec2(viewport_width as f32 * LOADING_BAR_SCALE, LOADING_BAR_HEIGHT);
+        let fill_size = glm::vec2(bar_size.x * progress.clamp(0.0, 1.0), bar_size.y);
+
+        self.draw_overlay(overlay_transform(center, bar_size, 0.0), LOADING_BAR_BACKGROUND_COLOR, -1);
+        self.draw_overlay(overlay_transform(center - glm::vec2((bar_size.x - fill_size.x) / 2.0, 0.0), fill_size, 0.0), LOADING_BAR_COLOR, -1);
+
+        let text = format!("Generating maze... {:.0}%", progress * 100.0);
+        let text_size = text_size(&text);
+
+        self.draw_text(&text, center.x - text_size.x / 2.0, center.y - bar_size.y / 2.0 - text_size.y - TEXT_PADDING);
+    }
+
+    //Draw line between two points in screen coordinates
+    fn draw_line(&mut self, start: glm::Vec2, end: glm::Vec2, color: [f32; 4]) {
+        let direction = end - start;
+
+        self.draw_overlay(overlay_transform((start + end) / 2.0, glm::vec2(glm::length(&direction), LINE_WIDTH), direction.y.atan2(direction.x)), 
+            color, -1);
+    }
+
+    //Draw line between two points in world space, it's drawn over the scene so walls don't hide it
+    //Transform goes from world to clip space with y axis pointing down like overlay coordinates
+    fn draw_world_line(&mut self, transform: glm::Mat4, start: glm::Vec3, end: glm::Vec3, color: [f32; 4]) {
+        let mut start_clip = transform * glm::vec4(start.x, start.y, start.z, 1.0);
+        let mut end_clip = transform * glm::vec4(end.x, end.y, end.z, 1.0);
+
+        if start_clip.w < LINE_NEAR_W && end_clip.w < LINE_NEAR_W {
+            return;
+        }
+
+        if start_clip.w < LINE_NEAR_W {
+            start_clip = glm::lerp(&start_clip, &end_clip, (LINE_NEAR_W - start_clip.w) / (end_clip.w - start_clip.w));
+        }
+
+        if end_clip.w < LINE_NEAR_W {
+            end_clip = glm::lerp(&end_clip, &start_clip, (LINE_NEAR_W - end_clip.w) / (start_clip.w - end_clip.w));
+        }
+
+        let (viewport_width, viewport_height) = self.get_viewport_size();
+        let viewport_size = glm::vec2(viewport_width as f32, viewport_height as f32);
+        let to_screen = |clip: glm::Vec4| (glm::vec2(clip.x, clip.y) / clip.w * 0.5 + glm::vec2(0.5, 0.5)).component_mul(&viewport_size);
+
+        self.draw_line(to_screen(start_clip), to_screen(end_clip), color);
+    }
 }
 
 pub struct MazeRenderer {
@@ -1,7 +1,7 @@
-use std::{ffi::{CStr, CString}, mem, num::NonZeroU32, os::raw::c_void, ptr};
+use std::{error::Error, ffi::{CStr, CString}, mem, num::NonZeroU32, os::raw::c_void, ptr};
 
 use gl::types::{GLsizeiptr, GLuint};
-use glutin::{config::{ConfigTemplateBuilder, GlConfig}, context::{ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentGlContext, PossiblyCurrentContext, Version}, 
+use glutin::{config::{ColorBufferType, ConfigTemplateBuilder, GlConfig}, context::{ContextApi, ContextAttributesBuilder, GlProfile, NotCurrentGlContext, PossiblyCurrentContext, Version}, 
     display::{GetGlDisplay, GlDisplay}, surface::{GlSurface, Surface, WindowSurface}};
 use glutin_winit::{DisplayBuilder, GlWindow};
 use raw_window_handle::HasRawWindowHandle;
@@ -9,70 +9,211 @@ use winit::{event_loop::EventLoopWindowTarget, window::{Window, WindowBuilder}};
 
 use self::gl_shader::GlShader;
 
-use super::{RenderResult, Renderer, UniformData};
+use super::{ColorSpace, MeshId, RenderResult, Renderer, RendererError, TextureDescriptor, TextureFilter, UniformData, MAX_TEXTURES, QUAD_MESH_NAME, SHADOW_MAP_SIZE, SKYBOX_MESH_NAME};
 
 mod gl_shader;
 
-pub struct GLRenderer {
-    gl_surface: Surface<WindowSurface>,
-    gl_context: PossiblyCurrentContext,
+//Mesh uploaded to GPU, every mesh has its own vertex array object with instance attributes
+struct GlMesh {
+    name: String,
     vertex_array_object: GLuint,
     vertex_buffer_object: GLuint,
     element_buffer_object: GLuint,
+    index_count: i32
+}
+
+//Instances of mesh waiting to be drawn, meshes are drawn in render so shadow map contains all of them
+struct MeshDraw {
+    mesh_index: usize,
+    instances: Vec<(glm::Mat4, i32)>
+}
+
+//Overlay quad waiting to be drawn on top of the scene
+struct OverlayQuad {
+    model_matrix: glm::Mat4,
+    color: [f32; 4],
+    texture_index: i32
+}
+
+pub struct GLRenderer {
+    gl_surface: Surface<WindowSurface>,
+    gl_context: PossiblyCurrentContext,
+    meshes: Vec<GlMesh>,
+    meshes_to_draw: Vec<MeshDraw>,
+    instance_buffer_object: GLuint,
     maze_textures: Vec<GLuint>,
-    maze_shader: GlShader
+    maze_normal_maps: Vec<GLuint>,
+    texture_tints: [[f32; 4]; MAX_TEXTURES],
+    texture_markers: [[f32; 4]; MAX_TEXTURES],
+    maze_shader: GlShader,
+    overlay_shader: GlShader,
+    shadow_shader: Option<GlShader>,
+    shadow_framebuffer: GLuint,
+    shadow_map: GLuint,
+    skybox_shader: Option<GlShader>,
+    skybox_texture: GLuint,
+    skybox_transform: Option<glm::Mat4>, //Set by draw_skybox for current frame
+    post_shader: Option<GlShader>,
+    scene_framebuffer: GLuint, //Scene is rendered here with post process, multisampled like default framebuffer
+    scene_color_buffer: GLuint,
+    scene_depth_buffer: GLuint,
+    post_framebuffer: GLuint, //Scene is resolved into post process texture which is drawn to the screen
+    post_texture: GLuint,
+    post_vertex_array: GLuint, //Fullscreen triangle has no vertex data but core profile needs bound vertex array to draw
+    framebuffer_samples: i32, //Sample count of default framebuffer, 0 when it isn't multisampled
+    wireframe: bool,
+    texture_filter: TextureFilter,
+    overlays_to_draw: Vec<OverlayQuad>,
+    capture_requested: bool,
+    captured_frame: Option<image::RgbaImage>,
+    viewport_width: u32,
+    viewport_height: u32
 }
 
 impl Renderer for GLRenderer {
-    fn init_mesh(&mut self, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) {
+    fn register_mesh(&mut self, name: &str, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) -> Result<MeshId, RendererError> {
+        super::check_mesh(&vertex_buffer, &index_buffer)?;
+
+        let mut mesh = GlMesh {
+            name: name.to_string(),
+            vertex_array_object: 0,
+            vertex_buffer_object: 0,
+            element_buffer_object: 0,
+            index_count: index_buffer.len() as i32
+        };
+
         unsafe {
             //VAO
-            gl::GenVertexArrays(1, &mut self.vertex_array_object);
-            gl::BindVertexArray(self.vertex_array_object);
+            gl::GenVertexArrays(1, &mut mesh.vertex_array_object);
+            gl::BindVertexArray(mesh.vertex_array_object);
 
             //VBO
-            gl::GenBuffers(1, &mut self.vertex_buffer_object);
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.vertex_buffer_object);
+            gl::GenBuffers(1, &mut mesh.vertex_buffer_object);
+            gl::BindBuffer(gl::ARRAY_BUFFER, mesh.vertex_buffer_object);
             gl::BufferData(gl::ARRAY_BUFFER, (vertex_buffer.len()*mem::size_of::<f32>()) as GLsizeiptr,
                         vertex_buffer.as_ptr() as *const gl::types::GLvoid, gl::STATIC_DRAW);
         
             //VBO Position
             gl::EnableVertexAttribArray(0);
-            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 8 * mem::size_of::<f32>() as i32, ptr::null());
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 11 * mem::size_of::<f32>() as i32, ptr::null());
 
             //VBO Texture UV
             gl::EnableVertexAttribArray(1);
-            gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::FALSE, 8 * mem::size_of::<f32>() as i32, 
+            gl::VertexAttribPointer(1, 2, gl::FLOAT, gl::FALSE, 11 * mem::size_of::<f32>() as i32, 
                             (3 * std::mem::size_of::<f32>()) as *const gl::types::GLvoid);
 
             //VBO Normal vector
             gl::EnableVertexAttribArray(2);
-            gl::VertexAttribPointer(2, 3, gl::FLOAT, gl::FALSE, 8 * mem::size_of::<f32>() as i32, 
+            gl::VertexAttribPointer(2, 3, gl::FLOAT, gl::FALSE, 11 * mem::size_of::<f32>() as i32, 
                             (5 * std::mem::size_of::<f32>()) as *const gl::types::GLvoid);
 
+            //VBO Tangent vector (locations 3 to 6 are taken by model matrix)
+            gl::EnableVertexAttribArray(7);
+            gl::VertexAttribPointer(7, 3, gl::FLOAT, gl::FALSE, 11 * mem::size_of::<f32>() as i32, 
+                            (8 * std::mem::size_of::<f32>()) as *const gl::types::GLvoid);
+
             //EBO
-            gl::GenBuffers(1, &mut self.element_buffer_object);
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.element_buffer_object);
+            gl::GenBuffers(1, &mut mesh.element_buffer_object);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, mesh.element_buffer_object);
             gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, (index_buffer.len()*mem::size_of::<u32>()) as GLsizeiptr,
                         index_buffer.as_ptr() as *const gl::types::GLvoid, gl::STATIC_DRAW);
+
+            //Instance buffer with model matrices is shared by all meshes and filled on every frame
+            //Matrix takes four attribute locations (one for every column) and advances once per instance
+            if self.instance_buffer_object == 0 {
+                gl::GenBuffers(1, &mut self.instance_buffer_object);
+            }
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.instance_buffer_object);
+
+            for column in 0..4 {
+                gl::EnableVertexAttribArray(3 + column);
+                gl::VertexAttribDivisor(3 + column, 1);
+                Self::set_instance_offset(column, 0);
+            }
+
+            gl::BindVertexArray(0);
+        }
+
+        //Mesh with the same name is replaced
+        match self.get_mesh_id(name) {
+            Some(MeshId(mesh_index)) => {
+                let mut old_mesh = mem::replace(&mut self.meshes[mesh_index], mesh);
+                Self::delete_mesh(&mut old_mesh);
+
+                Ok(MeshId(mesh_index))
+            },
+            None => {
+                self.meshes.push(mesh);
+
+                Ok(MeshId(self.meshes.len() - 1))
+            }
         }
     }
 
-    fn load_textures(&mut self, textures_paths: Vec<String>) {
-        for texture_path in textures_paths {
+    fn get_mesh_id(&self, name: &str) -> Option<MeshId> {
+        self.meshes.iter().position(|mesh| mesh.name == name).map(MeshId)
+    }
+
+    fn set_texture_filter(&mut self, texture_filter: TextureFilter) {
+        self.texture_filter = texture_filter;
+    }
+
+    fn load_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError> {
+        super::check_texture_count(textures.len())?;
+
+        for texture_descriptor in textures {
+            let texture = image::open(&texture_descriptor.path)
+                .map_err(|error| RendererError::TextureLoad(format!("{}: {}", texture_descriptor.path, error)))?;
+
             unsafe {
                 let mut texture_id: GLuint = 0;
 
                 gl::GenTextures(1, &mut texture_id);
-                self.load_texture(texture_id, &texture_path);
+                self.load_texture(texture_id, texture.into_rgba8(), texture_descriptor.color_space, true);
 
                 self.maze_textures.push(texture_id);
             }
         }
+
+        Ok(())
     }
 
-    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) {
-        self.maze_shader.load_shaders(vertex_shader_path, fragment_shader_path).unwrap();
+    fn reload_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError> {
+        let old_textures = mem::take(&mut self.maze_textures);
+
+        //Textures loaded before failed one are deleted too
+        let (load_result, mut unused_textures) = match self.load_textures(textures) {
+            Ok(()) => (Ok(()), old_textures),
+            Err(error) => (Err(error), mem::replace(&mut self.maze_textures, old_textures))
+        };
+
+        unsafe {
+            for texture in unused_textures.iter_mut() {
+                gl::DeleteTextures(1, texture);
+            }
+        }
+
+        load_result
+    }
+
+    fn load_normal_maps(&mut self, normal_maps_paths: Vec<Option<String>>) {
+        for normal_map_path in normal_maps_paths {
+            unsafe {
+                let mut texture_id: GLuint = 0;
+
+                gl::GenTextures(1, &mut texture_id);
+
+                //Normal maps store vectors, not colors so they can't be treated as sRGB
+                self.load_texture(texture_id, super::load_normal_map(normal_map_path.as_deref()), ColorSpace::LINEAR, true);
+
+                self.maze_normal_maps.push(texture_id);
+            }
+        }
+    }
+
+    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        self.maze_shader.load_shaders(vertex_shader_path, fragment_shader_path).map_err(|error| RendererError::ShaderLoad(error.to_string()))
     }
 
     fn update_uniform_data(&mut self, uniform_data: UniformData) {
@@ -81,83 +222,327 @@ impl Renderer for GLRenderer {
         self.maze_shader.set_uniform_matrix4fv("view", uniform_data.view_matrix);
         self.maze_shader.set_uniform_matrix4fv("projection", uniform_data.projection_matrix);
 
-        self.maze_shader.set_uniform_vec3fv("lightColor", uniform_data.light_color);
-        self.maze_shader.set_uniform_vec3fv("lightVector", uniform_data.light_position);
+        self.maze_shader.set_uniform_1i("lightCount", uniform_data.light_count);
+
+        for (light_index, light) in uniform_data.lights.iter().enumerate().take(uniform_data.light_count as usize) {
+            self.maze_shader.set_uniform_vec3fv(&format!("lights[{}].position", light_index), light.position);
+            self.maze_shader.set_uniform_1f(&format!("lights[{}].radius", light_index), light.radius);
+            self.maze_shader.set_uniform_vec3fv(&format!("lights[{}].color", light_index), light.color);
+        }
+
+        self.maze_shader.set_uniform_1f("gamma", uniform_data.gamma);
+        self.maze_shader.set_uniform_1i("normalMapping", uniform_data.normal_mapping);
+
+        self.maze_shader.set_uniform_1i("shadows", uniform_data.shadows);
+        self.maze_shader.set_uniform_matrix4fv("lightSpaceMatrix", uniform_data.light_space_matrix);
+
+        self.maze_shader.set_uniform_1f("markerTime", uniform_data.marker_time);
+
+        //Textures are drawn in separate groups so tint and marker are set for every group while drawing
+        self.texture_tints = uniform_data.texture_tints;
+        self.texture_markers = uniform_data.texture_markers;
+
+        //Texture units used by samplers
+        self.maze_shader.set_uniform_1i("texture1", 0);
+        self.maze_shader.set_uniform_1i("normalMap", 1);
+        self.maze_shader.set_uniform_1i("shadowMap", 2);
+
+        if let Some(shadow_shader) = &mut self.shadow_shader {
+            shadow_shader.use_shader();
+            shadow_shader.set_uniform_matrix4fv("lightSpaceMatrix", uniform_data.light_space_matrix);
+
+            self.maze_shader.use_shader();
+        }
+    }
+
+    fn draw_mesh_instanced(&mut self, mesh_id: MeshId, instances: &[(glm::Mat4, i32)]) {
+        if instances.is_empty() || mesh_id.0 >= self.meshes.len() {
+            return;
+        }
+
+        self.meshes_to_draw.push(MeshDraw {
+            mesh_index: mesh_id.0,
+            instances: instances.to_vec()
+        });
+    }
+
+    fn load_overlay_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        self.overlay_shader.load_shaders(vertex_shader_path, fragment_shader_path).map_err(|error| RendererError::ShaderLoad(error.to_string()))
+    }
+
+    fn enable_skybox(&mut self, faces_paths: Vec<String>, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        if self.get_mesh_id(SKYBOX_MESH_NAME).is_none() {
+            return Err(RendererError::InvalidMesh(String::from("Skybox mesh isn't registered")));
+        }
+
+        let faces = super::load_cubemap_faces(&faces_paths)?;
+
+        let mut skybox_shader = GlShader::new();
+        skybox_shader.load_shaders(vertex_shader_path, fragment_shader_path).map_err(|error| RendererError::ShaderLoad(error.to_string()))?;
 
         unsafe {
-            gl::BindVertexArray(self.vertex_array_object);
+            let mut skybox_texture: GLuint = 0;
+
+            gl::GenTextures(1, &mut skybox_texture);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, skybox_texture);
+
+            //Cubemap face targets follow each other in the same order as faces
+            for (face_index, face) in faces.into_iter().enumerate() {
+                gl::TexImage2D(gl::TEXTURE_CUBE_MAP_POSITIVE_X + face_index as u32, 0, gl::SRGB_ALPHA as i32, face.width() as i32, face.height() as i32, 
+                                0, gl::RGBA, gl::UNSIGNED_BYTE, face.into_raw().as_ptr() as *const c_void);
+            }
+
+            //Clamping hides seams between faces
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+
+            self.skybox_texture = skybox_texture;
         }
+
+        self.skybox_shader = Some(skybox_shader);
+
+        Ok(())
     }
 
-    fn draw(&mut self, model_matrix: glm::Mat4, texture_index: i32) {
+    fn draw_skybox(&mut self, transform: glm::Mat4) {
+        if self.skybox_shader.is_some() {
+            self.skybox_transform = Some(transform);
+        }
+    }
+
+    fn enable_shadows(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) {
+        let mut shadow_shader = GlShader::new();
+
+        if let Err(error) = shadow_shader.load_shaders(vertex_shader_path, fragment_shader_path) {
+            println!("Warning: Loading shadow shaders failed, shadows disabled: {}", error);
+            return;
+        }
+
         unsafe {
-            gl::BindTexture(gl::TEXTURE_2D, self.maze_textures[texture_index as usize]);
+            //Fragments outside of shadow map are handled in shader so only clamping is needed
+            gl::GenTextures(1, &mut self.shadow_map);
+            gl::BindTexture(gl::TEXTURE_2D, self.shadow_map);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::DEPTH_COMPONENT32F as i32, SHADOW_MAP_SIZE as i32, SHADOW_MAP_SIZE as i32, 
+                            0, gl::DEPTH_COMPONENT, gl::FLOAT, ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+
+            gl::GenFramebuffers(1, &mut self.shadow_framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.shadow_framebuffer);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::TEXTURE_2D, self.shadow_map, 0);
+            gl::DrawBuffer(gl::NONE);
+            gl::ReadBuffer(gl::NONE);
+
+            let framebuffer_status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            if framebuffer_status != gl::FRAMEBUFFER_COMPLETE {
+                println!("Warning: Shadow map framebuffer is incomplete, shadows disabled.");
+
+                gl::DeleteFramebuffers(1, &mut self.shadow_framebuffer);
+                gl::DeleteTextures(1, &mut self.shadow_map);
+                shadow_shader.delete_program();
+
+                return;
+            }
+        }
+
+        self.shadow_shader = Some(shadow_shader);
+    }
+
+    fn enable_post_process(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        let mut post_shader = GlShader::new();
+        post_shader.load_shaders(vertex_shader_path, fragment_shader_path).map_err(|error| RendererError::ShaderLoad(error.to_string()))?;
 
-            self.maze_shader.set_uniform_matrix4fv("model", model_matrix);
+        unsafe {
+            gl::GenFramebuffers(1, &mut self.scene_framebuffer);
+            gl::GenRenderbuffers(1, &mut self.scene_color_buffer);
+            gl::GenRenderbuffers(1, &mut self.scene_depth_buffer);
+            gl::GenFramebuffers(1, &mut self.post_framebuffer);
+            gl::GenTextures(1, &mut self.post_texture);
+            gl::GenVertexArrays(1, &mut self.post_vertex_array);
+        }
+
+        if let Err(error) = self.create_post_process_buffers() {
+            post_shader.delete_program();
+            self.delete_post_process();
+
+            return Err(error);
+        }
+
+        self.post_shader = Some(post_shader);
+
+        Ok(())
+    }
 
-            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, 0 as *const _);
+    fn reload_shaders(&mut self) {
+        for shader in [&mut self.maze_shader, &mut self.overlay_shader].into_iter().chain(self.shadow_shader.iter_mut()).chain(self.skybox_shader.iter_mut())
+            .chain(self.post_shader.iter_mut()) {
+            if let Err(error) = shader.reload() {
+                println!("Error: Shader reload failed: {}", error);
+            }
         }
     }
 
+    fn draw_overlay(&mut self, model_matrix: glm::Mat4, color: [f32; 4], texture_index: i32) {
+        self.overlays_to_draw.push(OverlayQuad {
+            model_matrix,
+            color,
+            texture_index
+        });
+    }
+
+    fn get_viewport_size(&self) -> (u32, u32) {
+        (self.viewport_width, self.viewport_height)
+    }
+
+    fn set_wireframe(&mut self, wireframe: bool) {
+        self.wireframe = wireframe;
+    }
+
     fn clear_color(&mut self, color: [f32; 4]) {
         unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_framebuffer);
             gl::ClearColor(color[0], color[1], color[2], color[3]);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
     }
 
     fn render(&mut self) -> RenderResult {
+        self.render_meshes();
+        self.render_skybox();
+        self.render_post_process();
+        self.render_overlays();
+
+        //Frame is read from back buffer before swap, its content isn't defined after it and front buffer can't be read reliably
+        if self.capture_requested {
+            self.capture_requested = false;
+            self.captured_frame = self.read_back_buffer();
+        }
+
         self.gl_surface.swap_buffers(&self.gl_context).unwrap();
 
         RenderResult::RenderFinished
     }
 
+    fn request_capture(&mut self) {
+        self.capture_requested = true;
+        self.captured_frame = None;
+    }
+
+    fn capture_frame(&mut self) -> Option<image::RgbaImage> {
+        self.captured_frame.take()
+    }
+
     fn resize_viewport(&mut self, window_width: u32, window_height: u32) {
         unsafe {
             gl::Viewport(0, 0, window_width as i32, window_height as i32);
         }
 
+        self.viewport_width = window_width;
+        self.viewport_height = window_height;
+
         self.gl_surface.resize(&self.gl_context, NonZeroU32::new(window_width).unwrap(), NonZeroU32::new(window_height).unwrap());
+
+        //Post process buffers are allocated again with new size, scene is drawn directly to the screen if it fails
+        if self.post_shader.is_some() {
+            if let Err(error) = self.create_post_process_buffers() {
+                println!("Warning: Post process disabled: {}", error);
+                self.delete_post_process();
+            }
+        }
     }
 
     fn cleanup(&mut self) {
         self.maze_shader.delete_program();
+        self.overlay_shader.delete_program();
+
+        if let Some(mut shadow_shader) = self.shadow_shader.take() {
+            shadow_shader.delete_program();
+
+            unsafe {
+                gl::DeleteFramebuffers(1, &mut self.shadow_framebuffer);
+                gl::DeleteTextures(1, &mut self.shadow_map);
+            }
+        }
+
+        if let Some(mut skybox_shader) = self.skybox_shader.take() {
+            skybox_shader.delete_program();
+
+            unsafe {
+                gl::DeleteTextures(1, &mut self.skybox_texture);
+            }
+        }
+
+        self.delete_post_process();
+
+        for mut mesh in mem::take(&mut self.meshes) {
+            Self::delete_mesh(&mut mesh);
+        }
 
         unsafe {
-            gl::DeleteBuffers(1, &mut self.vertex_buffer_object);
-            gl::DeleteBuffers(1, &mut self.element_buffer_object);
-            gl::DeleteVertexArrays(1, &mut self.vertex_array_object);
+            gl::DeleteBuffers(1, &mut self.instance_buffer_object);
 
             for texture in self.maze_textures.iter_mut() {
                 gl::DeleteTextures(1, texture);
             }
+
+            for normal_map in self.maze_normal_maps.iter_mut() {
+                gl::DeleteTextures(1, normal_map);
+            }
         }
     }
 }
 
 impl GLRenderer {
-    pub fn new<T>(window_builder: WindowBuilder, window_target: &EventLoopWindowTarget<T>, vsync_enabled: bool) -> (Self, Window) {
+    pub fn new<T>(window_builder: WindowBuilder, window_target: &EventLoopWindowTarget<T>, vsync_enabled: bool, msaa_samples: u32, 
+        verbose: bool) -> Result<(Self, Window), Box<dyn Error>> {
         let display_builder = DisplayBuilder::new().with_window_builder(Some(window_builder));
 
+        //Pick config with highest sample count that isn't higher than requested one, same as Vulkan renderer
         let (window, gl_config) = display_builder.build(window_target, ConfigTemplateBuilder::new(), |configs| {
             configs
                 .reduce(|accum, config| {
-                    if config.num_samples() == 4 {
+                    let samples = config.num_samples() as u32;
+                    let accum_samples = accum.num_samples() as u32;
+
+                    let better = match (samples <= msaa_samples, accum_samples <= msaa_samples) {
+                        (true, true) => samples > accum_samples,
+                        (true, false) => true,
+                        (false, true) => false,
+                        (false, false) => samples < accum_samples
+                    };
+
+                    if better {
                         config
                     } else {
                         accum
                     }
                 })
                 .unwrap()
-        }).unwrap();
+        }).map_err(|error| format!("Couldn't find suitable OpenGL config: {}", error))?;
+
+        let selected_samples = (gl_config.num_samples() as u32).max(1);
+        let framebuffer_samples = gl_config.num_samples() as i32;
+
+        if selected_samples != msaa_samples {
+            println!("Warning: {}x MSAA isn't supported, using {}x instead.", msaa_samples, selected_samples);
+        }
 
         let gl_display = gl_config.display();
         let raw_window_handle = window.as_ref().map(|window| window.raw_window_handle());
-        let window = window.unwrap();
+        let window = window.ok_or("Window creation failed.")?;
         let attrs = window.build_surface_attributes(Default::default());
 
         let gl_surface = unsafe {
-            gl_display.create_window_surface(&gl_config, &attrs).unwrap()
+            gl_display.create_window_surface(&gl_config, &attrs).map_err(|error| format!("OpenGL surface creation failed: {}", error))?
         };
 
         let context_attributes = ContextAttributesBuilder::new()
@@ -166,12 +551,33 @@ impl GLRenderer {
             .build(raw_window_handle);
 
         let gl_context = unsafe {
-            gl_display.create_context(&gl_config, &context_attributes).expect("Failed to create OpenGL context.").make_current(&gl_surface).unwrap()
+            gl_display.create_context(&gl_config, &context_attributes)
+                .map_err(|error| format!("OpenGL context creation failed: {}", error))?
+                .make_current(&gl_surface)
+                .map_err(|error| format!("Couldn't make OpenGL context current: {}", error))?
+        };
+
+        //Not every platform allows changing swap interval, it's not fatal
+        let swap_interval_result = match vsync_enabled {
+            false => gl_surface.set_swap_interval(&gl_context, glutin::surface::SwapInterval::DontWait),
+            true => gl_surface.set_swap_interval(&gl_context, glutin::surface::SwapInterval::Wait(NonZeroU32::new(1).unwrap()))
         };
 
-        match vsync_enabled {
-            false => gl_surface.set_swap_interval(&gl_context, glutin::surface::SwapInterval::DontWait).unwrap(),
-            true => gl_surface.set_swap_interval(&gl_context, glutin::surface::SwapInterval::Wait(NonZeroU32::new(1).unwrap())).unwrap()
+        //Swap interval can't be read back, platform default is used when setting it fails
+        match swap_interval_result {
+            Ok(()) => println!("Swap interval: {}", vsync_enabled as u32),
+            Err(error) => println!("Warning: Couldn't set swap interval, using platform default: {}", error)
+        }
+
+        if verbose {
+            let color_bits = match gl_config.color_buffer_type() {
+                Some(ColorBufferType::Rgb { r_size, g_size, b_size }) => r_size + g_size + b_size,
+                Some(ColorBufferType::Luminance(size)) => size,
+                None => 0
+            };
+
+            println!("OpenGL config: {} samples, color {} bits, alpha {} bits, depth {} bits, sRGB capable: {}", selected_samples, color_bits, 
+                gl_config.alpha_size(), gl_config.depth_size(), gl_config.srgb_capable());
         }
 
         gl::load_with(|symbol| {
@@ -180,6 +586,7 @@ impl GLRenderer {
         });
 
         unsafe {
+            //Maze quads face into the cells, back faces are culled on both renderers
             gl::Enable(gl::DEPTH_TEST);
             gl::Enable(gl::CULL_FACE);
             gl::Enable(gl::FRAMEBUFFER_SRGB);
@@ -202,33 +609,393 @@ impl GLRenderer {
             println!("Version: {}", version);
         }
 
-        (Self {
+        let viewport_width = window.inner_size().width;
+        let viewport_height = window.inner_size().height;
+
+        Ok((Self {
             gl_surface, 
             gl_context,
-            vertex_array_object: 0,
-            vertex_buffer_object: 0,
-            element_buffer_object: 0,
+            meshes: Vec::new(),
+            meshes_to_draw: Vec::new(),
+            instance_buffer_object: 0,
             maze_textures: Vec::new(),
-            maze_shader: GlShader::new()
-        }, window)
+            maze_normal_maps: Vec::new(),
+            texture_tints: [[1.0; 4]; MAX_TEXTURES],
+            texture_markers: [[0.0; 4]; MAX_TEXTURES],
+            maze_shader: GlShader::new(),
+            overlay_shader: GlShader::new(),
+            shadow_shader: None,
+            shadow_framebuffer: 0,
+            shadow_map: 0,
+            skybox_shader: None,
+            skybox_texture: 0,
+            skybox_transform: None,
+            post_shader: None,
+            scene_framebuffer: 0,
+            scene_color_buffer: 0,
+            scene_depth_buffer: 0,
+            post_framebuffer: 0,
+            post_texture: 0,
+            post_vertex_array: 0,
+            framebuffer_samples,
+            wireframe: false,
+            texture_filter: TextureFilter::LINEAR,
+            overlays_to_draw: Vec::new(),
+            capture_requested: false,
+            captured_frame: None,
+            viewport_width,
+            viewport_height
+        }, window))
     }
 
-    fn load_texture(&mut self, texture_id: GLuint, texture_file: &str) {
-        let texture = image::open(texture_file).unwrap().into_rgba8();
-        
+    //Draw queued meshes, instances of all meshes are uploaded at once
+    //Textures are separate objects so instances of every mesh are sorted by texture and every group is drawn with one call
+    fn render_meshes(&mut self) {
+        if self.meshes_to_draw.is_empty() {
+            return;
+        }
+
+        let mut model_matrices: Vec<glm::Mat4> = Vec::new();
+        let mut instance_groups = Vec::new(); //Mesh index, texture index, first instance and instance count
+
+        for mesh_draw in self.meshes_to_draw.iter() {
+            for texture_index in 0..self.maze_textures.len() {
+                let first_instance = model_matrices.len();
+
+                model_matrices.extend(mesh_draw.instances.iter().filter(|instance| instance.1 == texture_index as i32).map(|instance| instance.0));
+
+                if model_matrices.len() > first_instance {
+                    instance_groups.push((mesh_draw.mesh_index, texture_index, first_instance, model_matrices.len() - first_instance));
+                }
+            }
+        }
+
+        self.meshes_to_draw.clear();
+
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.instance_buffer_object);
+            gl::BufferData(gl::ARRAY_BUFFER, (model_matrices.len()*mem::size_of::<glm::Mat4>()) as GLsizeiptr,
+                        model_matrices.as_ptr() as *const gl::types::GLvoid, gl::STREAM_DRAW);
+        }
+
+        self.render_shadow_map(&instance_groups);
+
+        self.maze_shader.use_shader();
+
+        unsafe {
+            if self.shadow_shader.is_some() {
+                gl::ActiveTexture(gl::TEXTURE2);
+                gl::BindTexture(gl::TEXTURE_2D, self.shadow_map);
+                gl::ActiveTexture(gl::TEXTURE0);
+            }
+
+            //Polygon mode is global state so it's set only for maze and restored afterwards
+            if self.wireframe {
+                gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
+            }
+
+            for &(mesh_index, texture_index, first_instance, instance_count) in instance_groups.iter() {
+                if let Some(&normal_map) = self.maze_normal_maps.get(texture_index) {
+                    gl::ActiveTexture(gl::TEXTURE1);
+                    gl::BindTexture(gl::TEXTURE_2D, normal_map);
+                    gl::ActiveTexture(gl::TEXTURE0);
+                }
+
+                gl::BindTexture(gl::TEXTURE_2D, self.maze_textures[texture_index]);
+
+                let texture_tint = self.texture_tints.get(texture_index).copied().unwrap_or([1.0; 4]);
+                self.maze_shader.set_uniform_vec4fv("tint", glm::make_vec4(&texture_tint));
+
+                let texture_marker = self.texture_markers.get(texture_index).copied().unwrap_or([0.0; 4]);
+                self.maze_shader.set_uniform_vec4fv("marker", glm::make_vec4(&texture_marker));
+
+                self.draw_instance_group(mesh_index, first_instance, instance_count);
+            }
+
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    //Draw instance group of mesh, instance buffer has to be bound
+    unsafe fn draw_instance_group(&self, mesh_index: usize, first_instance: usize, instance_count: usize) {
+        let mesh = &self.meshes[mesh_index];
+
+        gl::BindVertexArray(mesh.vertex_array_object);
+
+        //Point matrix attributes at first instance of the group (base instance needs OpenGL 4.2)
+        for column in 0..4 {
+            Self::set_instance_offset(column, first_instance);
+        }
+
+        gl::DrawElementsInstanced(gl::TRIANGLES, mesh.index_count, gl::UNSIGNED_INT, ptr::null(), instance_count as i32);
+    }
+
+    //Point model matrix column attribute of bound vertex array at selected instance of bound instance buffer
+    unsafe fn set_instance_offset(column: GLuint, first_instance: usize) {
+        gl::VertexAttribPointer(3 + column, 4, gl::FLOAT, gl::FALSE, mem::size_of::<glm::Mat4>() as i32, 
+            ((first_instance * mem::size_of::<glm::Mat4>()) + (column as usize * 4 * mem::size_of::<f32>())) as *const gl::types::GLvoid);
+    }
+
+    //Draw instances into shadow map from light's point of view
+    //Walls are single sided so culling is disabled, otherwise light would go through walls seen from behind
+    //Instance data has to be already uploaded
+    fn render_shadow_map(&mut self, instance_groups: &[(usize, usize, usize, usize)]) {
+        let Some(shadow_shader) = &mut self.shadow_shader else {
+            return;
+        };
+
+        shadow_shader.use_shader();
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.shadow_framebuffer);
+            gl::Viewport(0, 0, SHADOW_MAP_SIZE as i32, SHADOW_MAP_SIZE as i32);
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+
+            gl::Disable(gl::CULL_FACE);
+            gl::Enable(gl::POLYGON_OFFSET_FILL);
+            gl::PolygonOffset(1.75, 1.25);
+
+            for &(mesh_index, _, first_instance, instance_count) in instance_groups {
+                self.draw_instance_group(mesh_index, first_instance, instance_count);
+            }
+
+            gl::Disable(gl::POLYGON_OFFSET_FILL);
+            gl::Enable(gl::CULL_FACE);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_framebuffer);
+            gl::Viewport(0, 0, self.viewport_width as i32, self.viewport_height as i32);
+        }
+    }
+
+    fn delete_mesh(mesh: &mut GlMesh) {
+        unsafe {
+            gl::DeleteBuffers(1, &mut mesh.vertex_buffer_object);
+            gl::DeleteBuffers(1, &mut mesh.element_buffer_object);
+            gl::DeleteVertexArrays(1, &mut mesh.vertex_array_object);
+        }
+    }
+
+    //Skybox is drawn after maze so only pixels not covered by it are shaded
+    //It's at far plane so depth test has to pass for depth equal to cleared one and it doesn't write depth
+    fn render_skybox(&mut self) {
+        let (Some(skybox_shader), Some(transform)) = (&mut self.skybox_shader, self.skybox_transform.take()) else {
+            return;
+        };
+
+        let Some(skybox_mesh) = self.meshes.iter().find(|mesh| mesh.name == SKYBOX_MESH_NAME) else {
+            return;
+        };
+
+        skybox_shader.use_shader();
+        skybox_shader.set_uniform_matrix4fv("transform", transform);
+        skybox_shader.set_uniform_1i("skybox", 0);
+
+        unsafe {
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.skybox_texture);
+            gl::BindVertexArray(skybox_mesh.vertex_array_object);
+
+            gl::DrawElements(gl::TRIANGLES, skybox_mesh.index_count, gl::UNSIGNED_INT, ptr::null());
+
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+
+            gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
+        }
+    }
+
+    //Allocate storage of post process buffers with viewport size, objects have to be already generated
+    //Scene buffers have the same sample count as default framebuffer and they are resolved into texture read by post shader
+    fn create_post_process_buffers(&mut self) -> Result<(), RendererError> {
+        let (width, height) = (self.viewport_width as i32, self.viewport_height as i32);
+
+        unsafe {
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self.scene_color_buffer);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, self.framebuffer_samples, gl::SRGB8_ALPHA8, width, height);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self.scene_depth_buffer);
+            gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, self.framebuffer_samples, gl::DEPTH_COMPONENT24, width, height);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_framebuffer);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, self.scene_color_buffer);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, self.scene_depth_buffer);
+            let scene_framebuffer_status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+
+            //Texture covers the screen pixel by pixel so it doesn't need mipmaps
+            gl::BindTexture(gl::TEXTURE_2D, self.post_texture);
+            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::SRGB8_ALPHA8 as i32, width, height, 0, gl::RGBA, gl::UNSIGNED_BYTE, ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.post_framebuffer);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, self.post_texture, 0);
+            let post_framebuffer_status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            if scene_framebuffer_status != gl::FRAMEBUFFER_COMPLETE || post_framebuffer_status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(RendererError::PipelineCreation(String::from("Post process framebuffer is incomplete")));
+            }
+        }
+
+        Ok(())
+    }
+
+    //Scene is drawn directly to the screen afterwards, objects that weren't generated are ignored
+    fn delete_post_process(&mut self) {
+        if let Some(mut post_shader) = self.post_shader.take() {
+            post_shader.delete_program();
+        }
+
+        unsafe {
+            gl::DeleteFramebuffers(1, &mut self.scene_framebuffer);
+            gl::DeleteRenderbuffers(1, &mut self.scene_color_buffer);
+            gl::DeleteRenderbuffers(1, &mut self.scene_depth_buffer);
+            gl::DeleteFramebuffers(1, &mut self.post_framebuffer);
+            gl::DeleteTextures(1, &mut self.post_texture);
+            gl::DeleteVertexArrays(1, &mut self.post_vertex_array);
+        }
+
+        //Scene framebuffer is bound at the start of every frame, 0 is default framebuffer
+        self.scene_framebuffer = 0;
+    }
+
+    //Resolve scene into post process texture and draw it to the screen with post shader
+    //Overlays are drawn after it directly to the screen
+    fn render_post_process(&mut self) {
+        let Some(post_shader) = &mut self.post_shader else {
+            return;
+        };
+
+        let (width, height) = (self.viewport_width as i32, self.viewport_height as i32);
+
+        post_shader.use_shader();
+        post_shader.set_uniform_1i("scene", 0);
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.scene_framebuffer);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.post_framebuffer);
+            gl::BlitFramebuffer(0, 0, width, height, 0, 0, width, height, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            gl::Disable(gl::DEPTH_TEST);
+
+            gl::BindTexture(gl::TEXTURE_2D, self.post_texture);
+            gl::BindVertexArray(self.post_vertex_array);
+
+            gl::DrawArrays(gl::TRIANGLES, 0, 3);
+
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::Enable(gl::DEPTH_TEST);
+        }
+    }
+
+    //Read finished frame from back buffer of default framebuffer
+    //Framebuffer keeps sRGB encoded values and reading doesn't convert them so they can be saved directly
+    fn read_back_buffer(&self) -> Option<image::RgbaImage> {
+        let mut pixels = vec![0u8; (self.viewport_width * self.viewport_height * 4) as usize];
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::ReadBuffer(gl::BACK);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(0, 0, self.viewport_width as i32, self.viewport_height as i32, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut c_void);
+        }
+
+        //Overlay blending changes alpha, screenshot should be opaque
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+
+        let frame = image::RgbaImage::from_raw(self.viewport_width, self.viewport_height, pixels)?;
+
+        //OpenGL origin is in bottom left corner
+        Some(image::imageops::flip_vertical(&frame))
+    }
+
+    //Draw overlay quads on top of the scene with orthographic projection
+    //Depth test and culling are disabled and alpha blending is enabled only for this pass
+    fn render_overlays(&mut self) {
+        let Some(quad_mesh) = self.meshes.iter().find(|mesh| mesh.name == QUAD_MESH_NAME) else {
+            self.overlays_to_draw.clear();
+            return;
+        };
+
+        if self.overlays_to_draw.is_empty() {
+            return;
+        }
+
+        let projection = glm::ortho(0.0, self.viewport_width as f32, self.viewport_height as f32, 0.0, -1.0, 1.0);
+
+        self.overlay_shader.use_shader();
+
+        unsafe {
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Disable(gl::CULL_FACE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            gl::BindVertexArray(quad_mesh.vertex_array_object);
+
+            for overlay in self.overlays_to_draw.iter() {
+                if overlay.texture_index >= 0 {
+                    gl::BindTexture(gl::TEXTURE_2D, self.maze_textures[overlay.texture_index as usize]);
+                }
+
+                self.overlay_shader.set_uniform_matrix4fv("transform", projection * overlay.model_matrix);
+                self.overlay_shader.set_uniform_vec4fv("color", glm::make_vec4(&overlay.color));
+                self.overlay_shader.set_uniform_1i("useTexture", (overlay.texture_index >= 0) as i32);
+
+                gl::DrawElements(gl::TRIANGLES, quad_mesh.index_count, gl::UNSIGNED_INT, ptr::null());
+            }
+
+            gl::BindVertexArray(0);
+            gl::Disable(gl::BLEND);
+            gl::Enable(gl::CULL_FACE);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+
+        self.overlays_to_draw.clear();
+    }
+
+    fn load_texture(&mut self, texture_id: GLuint, texture: image::RgbaImage, color_space: ColorSpace, generate_mipmaps: bool) {
+        let internal_format = match color_space {
+            ColorSpace::SRGB => gl::SRGB_ALPHA,
+            ColorSpace::LINEAR => gl::RGBA
+        };
+
+        //Without mipmapped filter generated mipmaps would never be used
+        let (min_filter, mag_filter) = match (self.texture_filter, generate_mipmaps) {
+            (TextureFilter::LINEAR, true) => (gl::LINEAR_MIPMAP_LINEAR, gl::LINEAR),
+            (TextureFilter::LINEAR, false) => (gl::LINEAR, gl::LINEAR),
+            (TextureFilter::NEAREST, true) => (gl::NEAREST_MIPMAP_NEAREST, gl::NEAREST),
+            (TextureFilter::NEAREST, false) => (gl::NEAREST, gl::NEAREST)
+        };
+
         unsafe {
             gl::BindTexture(gl::TEXTURE_2D, texture_id);
     
             //Setup wrapping and filtering
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, min_filter as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, mag_filter as i32);
     
-            gl::TexImage2D(gl::TEXTURE_2D, 0, gl::SRGB_ALPHA as i32, texture.width() as i32, texture.height() as i32, 
+            gl::TexImage2D(gl::TEXTURE_2D, 0, internal_format as i32, texture.width() as i32, texture.height() as i32, 
                             0, gl::RGBA, gl::UNSIGNED_BYTE, texture.into_raw().as_ptr() as *const c_void);
     
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            if generate_mipmaps {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+            }
         }    
     }
 }
@@ -1,6 +1,7 @@
 extern crate gl;
 
 use gl::types::*;
+use std::cmp;
 use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
@@ -9,68 +10,131 @@ use std::ptr;
 
 pub struct GlShader {
     program_id: GLuint,     
+    vertex_shader_path: String,
+    fragment_shader_path: String
 }
 
 impl GlShader {
     pub fn new() -> GlShader {
         GlShader {
             program_id: 0,
+            vertex_shader_path: String::new(),
+            fragment_shader_path: String::new()
         }
     }
 
     pub fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), Box<dyn Error>> {
-        let mut vertex_shader_source = String::new();
-        let mut fragment_shader_source = String::new();
+        let vertex_shader_source = Self::read_source(vertex_shader_path)?;
+        let fragment_shader_source = Self::read_source(fragment_shader_path)?;
 
-        let mut vertex_shader_file = File::open(vertex_shader_path).unwrap();
-        let mut fragment_shader_file = File::open(fragment_shader_path).unwrap();
+        unsafe {
+            let vertex_shader = Self::compile_shader(gl::VERTEX_SHADER, vertex_shader_source, vertex_shader_path)?;
 
-        vertex_shader_file.read_to_string(&mut vertex_shader_source).unwrap();
-        fragment_shader_file.read_to_string(&mut fragment_shader_source).unwrap();
-        
-        let vertex_shader: GLuint;
-        let fragment_shader: GLuint;
+            let fragment_shader = match Self::compile_shader(gl::FRAGMENT_SHADER, fragment_shader_source, fragment_shader_path) {
+                Ok(fragment_shader) => fragment_shader,
+                Err(error) => {
+                    gl::DeleteShader(vertex_shader);
+                    return Err(error);
+                }
+            };
 
-        unsafe {
-            vertex_shader = gl::CreateShader(gl::VERTEX_SHADER);
-            let vertex_shader_source = CString::new(vertex_shader_source).unwrap();
-            gl::ShaderSource(vertex_shader, 1, &vertex_shader_source.as_ptr(), ptr::null());
-            gl::CompileShader(vertex_shader);
+            let program_id = gl::CreateProgram();
+            gl::AttachShader(program_id, vertex_shader);
+            gl::AttachShader(program_id, fragment_shader);
+            gl::LinkProgram(program_id);
+
+            gl::DeleteShader(vertex_shader);
+            gl::DeleteShader(fragment_shader);
 
             let mut status = gl::FALSE as GLint;
-            gl::GetShaderiv(vertex_shader, gl::COMPILE_STATUS, &mut status);
+            gl::GetProgramiv(program_id, gl::LINK_STATUS, &mut status);
 
             if status != (gl::TRUE as GLint) {
-                Err("Vertex shader compilation failed.")?;
+                let info_log = Self::program_info_log(program_id);
+                gl::DeleteProgram(program_id);
+
+                Err(format!("Shader program link failed ({}, {}):\n{}", vertex_shader_path, fragment_shader_path, info_log))?;
             }
 
-            fragment_shader = gl::CreateShader(gl::FRAGMENT_SHADER);
-            let fragment_shader_source = CString::new(fragment_shader_source).unwrap();
-            gl::ShaderSource(fragment_shader, 1, &fragment_shader_source.as_ptr(), ptr::null());
-            gl::CompileShader(fragment_shader);
+            //Previous program is replaced only when new one was built successfully
+            if self.program_id != 0 {
+                gl::DeleteProgram(self.program_id);
+            }
 
-            gl::GetShaderiv(fragment_shader, gl::COMPILE_STATUS, &mut status);
+            self.program_id = program_id;
+        }
 
-            if status != (gl::TRUE as GLint) {
-                Err("Fragment shader compilation failed.")?;
-            }
+        self.vertex_shader_path = vertex_shader_path.to_string();
+        self.fragment_shader_path = fragment_shader_path.to_string();
+        
+        Ok(())
+    }
 
-            self.program_id = gl::CreateProgram();
-            gl::AttachShader(self.program_id, vertex_shader);
-            gl::AttachShader(self.program_id, fragment_shader);
-            gl::LinkProgram(self.program_id);
+    //Build program again from the same files
+    pub fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.program_id == 0 {
+            return Ok(());
+        }
 
-            gl::GetProgramiv(self.program_id, gl::LINK_STATUS, &mut status);
+        let vertex_shader_path = self.vertex_shader_path.clone();
+        let fragment_shader_path = self.fragment_shader_path.clone();
 
-            if status != (gl::TRUE as GLint) {
-                Err("Shader program link failed.")?;
-            }
+        self.load_shaders(&vertex_shader_path, &fragment_shader_path)
+    }
 
-            gl::DeleteShader(vertex_shader);
-            gl::DeleteShader(fragment_shader);
+    fn read_source(path: &str) -> Result<String, Box<dyn Error>> {
+        let mut source = String::new();
+
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut source))
+            .map_err(|error| format!("Couldn't read shader {}: {}", path, error))?;
+
+        Ok(source)
+    }
+
+    //Compile single shader stage, on failure error contains driver's info log
+    unsafe fn compile_shader(shader_type: GLenum, source: String, path: &str) -> Result<GLuint, Box<dyn Error>> {
+        let source = CString::new(source).map_err(|_| format!("Shader {} contains null character.", path))?;
+
+        let shader = gl::CreateShader(shader_type);
+        gl::ShaderSource(shader, 1, &source.as_ptr(), ptr::null());
+        gl::CompileShader(shader);
+
+        let mut status = gl::FALSE as GLint;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+
+        if status != (gl::TRUE as GLint) {
+            let info_log = Self::shader_info_log(shader);
+            gl::DeleteShader(shader);
+
+            Err(format!("Shader {} compilation failed:\n{}", path, info_log))?;
         }
-        
-        Ok(())
+
+        Ok(shader)
+    }
+
+    unsafe fn shader_info_log(shader: GLuint) -> String {
+        let mut log_length: GLint = 0;
+        gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut log_length);
+
+        let mut info_log = vec![0u8; cmp::max(log_length, 1) as usize];
+        let mut written: GLsizei = 0;
+        gl::GetShaderInfoLog(shader, info_log.len() as GLsizei, &mut written, info_log.as_mut_ptr() as *mut GLchar);
+        info_log.truncate(written as usize);
+
+        String::from_utf8_lossy(&info_log).trim_end().to_string()
+    }
+
+    unsafe fn program_info_log(program: GLuint) -> String {
+        let mut log_length: GLint = 0;
+        gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut log_length);
+
+        let mut info_log = vec![0u8; cmp::max(log_length, 1) as usize];
+        let mut written: GLsizei = 0;
+        gl::GetProgramInfoLog(program, info_log.len() as GLsizei, &mut written, info_log.as_mut_ptr() as *mut GLchar);
+        info_log.truncate(written as usize);
+
+        String::from_utf8_lossy(&info_log).trim_end().to_string()
     }
 
     pub fn use_shader(&mut self) {
@@ -95,10 +159,36 @@ impl GlShader {
         }
     }
 
+    pub fn set_uniform_vec4fv(&mut self, name: &str, uniform: glm::Vec4) {
+        unsafe {
+            let uniform_name = CString::new(name).unwrap();
+            let location = gl::GetUniformLocation(self.program_id, uniform_name.as_ptr());
+            gl::Uniform4fv(location, 1, uniform.as_ptr());
+        }
+    }
+
+    pub fn set_uniform_1i(&mut self, name: &str, uniform: i32) {
+        unsafe {
+            let uniform_name = CString::new(name).unwrap();
+            let location = gl::GetUniformLocation(self.program_id, uniform_name.as_ptr());
+            gl::Uniform1i(location, uniform);
+        }
+    }
+
+    pub fn set_uniform_1f(&mut self, name: &str, uniform: f32) {
+        unsafe {
+            let uniform_name = CString::new(name).unwrap();
+            let location = gl::GetUniformLocation(self.program_id, uniform_name.as_ptr());
+            gl::Uniform1f(location, uniform);
+        }
+    }
+
     pub fn delete_program(&mut self) {
         unsafe {
             gl::DeleteProgram(self.program_id);
         }
+
+        self.program_id = 0;
     }
 }
 
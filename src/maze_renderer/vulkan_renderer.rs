@@ -1,7 +1,7 @@
 //Main rendering code
 //Responsible for initialization needed Vulkan objects (command pool, comand buffer, render pass etc.) and drawing
 
-use std::{fs::File, mem::{self, size_of}};
+use std::{error::Error, fs::File, mem, path::Path};
 
 use ash::{util::read_spv, vk::{self, AttachmentDescription, AttachmentDescriptionFlags, AttachmentLoadOp, AttachmentStoreOp, BorderColor, Buffer, BufferUsageFlags, CommandBuffer, CommandBufferAllocateInfo, 
     CommandBufferBeginInfo, CommandBufferLevel, CommandBufferResetFlags, CommandPool, CommandPoolCreateFlags, CommandPoolCreateInfo, CompareOp, DescriptorSet, DescriptorSetLayout, Fence, FenceCreateFlags, 
@@ -11,11 +11,11 @@ use ash::{util::read_spv, vk::{self, AttachmentDescription, AttachmentDescriptio
 
 use winit::window::Window;
 
-use crate::maze_renderer::vulkan_renderer::{vulkan_buffer::VulkanBuffer, vulkan_vertex_input::VertexData};
+use crate::maze_renderer::vulkan_renderer::{vulkan_buffer::VulkanBuffer, vulkan_vertex_input::{InstanceData, VertexData}};
 
-use self::{vulkan_context::VulkanContext, vulkan_descriptor::VulkanDescriptor, vulkan_image::VulkanImage, vulkan_mesh::{PushConstant, VulkanMesh}, vulkan_pipeline::VulkanPipeline, vulkan_vertex_input::VertexInput};
+use self::{vulkan_context::{PhysicalDeviceInfo, VulkanContext}, vulkan_descriptor::{DescriptorImages, VulkanDescriptor}, vulkan_image::VulkanImage, vulkan_mesh::{OverlayPushConstant, PushConstantData, VulkanMesh}, vulkan_pipeline::VulkanPipeline, vulkan_shadow_map::VulkanShadowMap, vulkan_vertex_input::VertexInput};
 
-use super::{RenderResult, Renderer, UniformData};
+use super::{ColorSpace, MeshId, RenderResult, Renderer, RendererError, TextureDescriptor, TextureFilter, UniformData, MAX_TEXTURES, QUAD_MESH_NAME, SHADOW_MAP_SIZE, SKYBOX_MESH_NAME};
 
 pub mod vulkan_context;
 pub mod vulkan_pipeline;
@@ -24,13 +24,12 @@ pub mod vulkan_vertex_input;
 pub mod vulkan_mesh;
 pub mod vulkan_image;
 pub mod vulkan_descriptor;
+pub mod vulkan_shadow_map;
 
 impl Renderer for VulkanRenderer {
-    fn init_mesh(&mut self, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) {
-        //Expect 8 components which is vertex position XYZ, vertex normal XYZ an texture UV
-        if vertex_buffer.len() & 8 != 0 {
-            panic!("Incorrect vertex data.");
-        }
+    fn register_mesh(&mut self, name: &str, vertex_buffer: Vec<f32>, index_buffer: Vec<u32>) -> Result<MeshId, RendererError> {
+        //Expect 11 components which is vertex position XYZ, texture UV, vertex normal XYZ and vertex tangent XYZ
+        super::check_mesh(&vertex_buffer, &index_buffer)?;
 
         let mut i = 0;
         let mut vertex_data = Vec::new();
@@ -38,54 +37,215 @@ impl Renderer for VulkanRenderer {
         while i < vertex_buffer.len() {
             vertex_data.push(VertexData::new(glm::vec3(vertex_buffer[i], vertex_buffer[i + 1], vertex_buffer[i + 2]), 
                 glm::vec3(vertex_buffer[i + 5], vertex_buffer[i + 6], vertex_buffer[i + 7]), 
-                glm::vec2(vertex_buffer[i + 3], vertex_buffer[i + 4])));
+                glm::vec2(vertex_buffer[i + 3], vertex_buffer[i + 4]),
+                glm::vec3(vertex_buffer[i + 8], vertex_buffer[i + 9], vertex_buffer[i + 10])));
+
+            i += 11;
+        }
 
-            i += 8;
+        let mut mesh = VulkanMesh::new();
+        self.populate_vertex_buffer(&mut mesh, vertex_data, index_buffer);
+
+        //Replaced mesh can't be destroyed while it's in use
+        match self.get_mesh_id(name) {
+            Some(MeshId(mesh_index)) => {
+                unsafe {
+                    self.vulkan_context.logical_device.device_wait_idle().unwrap();
+                }
+
+                let mut old_mesh = mem::replace(&mut self.meshes[mesh_index].1, mesh);
+                self.destroy_mesh(&mut old_mesh);
+
+                Ok(MeshId(mesh_index))
+            },
+            None => {
+                self.meshes.push((name.to_string(), mesh));
+
+                Ok(MeshId(self.meshes.len() - 1))
+            }
         }
+    }
+
+    fn get_mesh_id(&self, name: &str) -> Option<MeshId> {
+        self.meshes.iter().position(|(mesh_name, _)| mesh_name == name).map(MeshId)
+    }
 
-        let mut maze_mesh = VulkanMesh::new();
-        self.populate_vertex_buffer(&mut maze_mesh, vertex_data, index_buffer);
-        self.maze_mesh = Some(maze_mesh);
+    fn set_texture_filter(&mut self, texture_filter: TextureFilter) {
+        self.texture_filter = texture_filter;
     }
 
-    fn load_textures(&mut self, textures_paths: Vec<String>) {
+    fn load_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError> {
+        super::check_texture_count(textures.len())?;
+
         let mut maze_textures = Vec::new();
 
         let mut texture_index = 0;
-        for texture_path in textures_paths.iter() {
+        for texture_descriptor in textures.iter() {
             let texture_name = "Maze texture ".to_owned() + texture_index.to_string().as_str();
             texture_index += 1;
 
-            maze_textures.push(self.create_texture(texture_path, texture_name.as_str(), true));
+            match self.create_texture(&texture_descriptor.path, texture_name.as_str(), true, texture_descriptor.color_space) {
+                Ok(texture) => maze_textures.push(texture),
+                Err(error) => {
+                    //Textures loaded before failed one would be leaked
+                    for texture in maze_textures.iter_mut() {
+                        self.destroy_texture(texture);
+                    }
+
+                    return Err(error);
+                }
+            }
         }
 
-        let sampler = self.create_sampler(Filter::LINEAR, SamplerAddressMode::REPEAT, SamplerMipmapMode::LINEAR, 0.0, 15.0);
+        let (filter, mipmap_mode) = match self.texture_filter {
+            TextureFilter::LINEAR => (Filter::LINEAR, SamplerMipmapMode::LINEAR),
+            TextureFilter::NEAREST => (Filter::NEAREST, SamplerMipmapMode::NEAREST)
+        };
+
+        let sampler = self.create_sampler(filter, SamplerAddressMode::REPEAT, mipmap_mode, 0.0, 15.0);
 
         self.maze_texture_sampler = Some(sampler);
         self.maze_textures = Some(maze_textures);
+
+        Ok(())
     }
 
-    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) {
-        let maze_textures = self.maze_textures.take().unwrap();
+    fn reload_textures(&mut self, textures: Vec<TextureDescriptor>) -> Result<(), RendererError> {
+        //Textures can't be destroyed while they are in use
+        unsafe {
+            self.vulkan_context.logical_device.device_wait_idle().unwrap();
+        }
 
-        let mut maze_textures_ref = Vec::new();
+        let old_textures = self.maze_textures.take();
+        let old_sampler = self.maze_texture_sampler.take();
 
-        for maze_texture in maze_textures.iter().as_ref() {
-            maze_textures_ref.push(maze_texture.image_view.clone());
+        if let Err(error) = self.load_textures(textures) {
+            self.maze_textures = old_textures;
+            self.maze_texture_sampler = old_sampler;
+
+            return Err(error);
         }
 
-        let maze_descriptors = self.create_descriptor(mem::size_of::<UniformData>() as u64, "Maze uniform data", maze_textures_ref, self.maze_texture_sampler);
+        //Descriptor keeps views of old textures so it's created again together with pipelines using its layout
+        let old_descriptors = self.maze_descriptors.take();
+        let old_pipelines = [self.maze_pipeline.take(), self.shadow_pipeline.take(), self.overlay_pipeline.take()];
+
+        let reload_result = match self.maze_shader_paths.clone() {
+            Some((vertex_shader_path, fragment_shader_path)) => self.load_shaders(&vertex_shader_path, &fragment_shader_path),
+            None => Ok(())
+        };
+
+        if let (Ok(()), Some((vertex_shader_path, fragment_shader_path))) = (&reload_result, self.overlay_shader_paths.clone()) {
+            if let Err(error) = self.load_overlay_shaders(&vertex_shader_path, &fragment_shader_path) {
+                println!("Warning: Overlay disabled: {}", error);
+            }
+        }
+
+        //Everything created from new textures is destroyed and old objects are used again when pipelines can't be created
+        let (mut unused_textures, unused_sampler, unused_descriptors, unused_pipelines) = match &reload_result {
+            Ok(()) => (old_textures, old_sampler, old_descriptors, old_pipelines),
+            Err(_) => {
+                let new_pipelines = [self.maze_pipeline.take(), self.shadow_pipeline.take(), self.overlay_pipeline.take()];
+                let new_objects = (self.maze_textures.take(), self.maze_texture_sampler.take(), self.maze_descriptors.take(), new_pipelines);
+
+                [self.maze_pipeline, self.shadow_pipeline, self.overlay_pipeline] = old_pipelines;
+                self.maze_textures = old_textures;
+                self.maze_texture_sampler = old_sampler;
+                self.maze_descriptors = old_descriptors;
 
-        let maze_pipeline = self.create_pipeline(vertex_shader_path, fragment_shader_path, Some(&maze_descriptors));
+                new_objects
+            }
+        };
+
+        for mut pipeline in unused_pipelines.into_iter().flatten() {
+            self.destroy_pipeline(&mut pipeline);
+        }
+
+        if let Some(mut descriptors) = unused_descriptors {
+            self.destroy_descriptor(&mut descriptors);
+        }
+
+        for texture in unused_textures.iter_mut().flatten() {
+            self.destroy_texture(texture);
+        }
+
+        if let Some(sampler) = unused_sampler {
+            self.destroy_sampler(sampler);
+        }
+
+        reload_result
+    }
+
+    fn load_normal_maps(&mut self, normal_maps_paths: Vec<Option<String>>) {
+        let mut maze_normal_maps = Vec::new();
+
+        for (normal_map_index, normal_map_path) in normal_maps_paths.iter().enumerate() {
+            let normal_map_name = "Maze normal map ".to_owned() + normal_map_index.to_string().as_str();
+
+            //Normal maps store vectors, not colors so they can't be treated as sRGB
+            maze_normal_maps.push(self.create_texture_from_image(super::load_normal_map(normal_map_path.as_deref()), normal_map_name.as_str(), 
+                true, texture_format(ColorSpace::LINEAR)));
+        }
+
+        self.maze_normal_maps = Some(maze_normal_maps);
+    }
+
+    fn load_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        let Some(maze_textures) = &self.maze_textures else {
+            return Err(RendererError::ShaderLoad(String::from("Textures need to be loaded before shaders")));
+        };
+
+        let mut maze_textures_ref: Vec<ImageView> = maze_textures.iter()
+            .map(|maze_texture| maze_texture.image_view)
+            .collect();
+
+        //Shaders use texture array with fixed size so it needs to be filled completely, texture count was checked when loading them
+        let last_texture = *maze_textures_ref.last().unwrap();
+        maze_textures_ref.resize(MAX_TEXTURES, last_texture);
+
+        //Normal maps array needs to be filled even if normal mapping is disabled, it's not sampled then so color textures are used
+        let mut maze_normal_maps_ref: Vec<ImageView> = match &self.maze_normal_maps {
+            Some(maze_normal_maps) => maze_normal_maps.iter().map(|normal_map| normal_map.image_view).collect(),
+            None => maze_textures_ref.clone()
+        };
+
+        let last_normal_map = *maze_normal_maps_ref.last().unwrap();
+        maze_normal_maps_ref.resize(MAX_TEXTURES, last_normal_map);
+
+        //Same goes for shadow map
+        let shadow_map_ref = match &self.shadow_map {
+            Some(shadow_map) => shadow_map.depth_image.image_view,
+            None => last_texture
+        };
+
+        let maze_images = self.maze_texture_sampler.map(|sampler| DescriptorImages::Separate(sampler, vec![maze_textures_ref, maze_normal_maps_ref, vec![shadow_map_ref]]));
+        let mut maze_descriptors = self.create_descriptor(mem::size_of::<UniformData>() as u64, "Maze uniform data", maze_images);
+
+        let maze_pipeline = match self.create_pipeline(vertex_shader_path, fragment_shader_path, Some(&maze_descriptors), PipelineType::Maze) {
+            Ok(maze_pipeline) => maze_pipeline,
+            Err(error) => {
+                self.destroy_descriptor(&mut maze_descriptors);
+                return Err(error);
+            }
+        };
+
+        //Shadows are optional so failed shadow pipeline only disables them
+        if let Some((shadow_vertex_shader_path, shadow_fragment_shader_path)) = &self.shadow_shader_paths {
+            match self.create_pipeline(shadow_vertex_shader_path, shadow_fragment_shader_path, Some(&maze_descriptors), PipelineType::Shadow) {
+                Ok(shadow_pipeline) => self.shadow_pipeline = Some(shadow_pipeline),
+                Err(error) => println!("Warning: Shadows disabled: {}", error)
+            }
+        }
 
         self.maze_descriptors = Some(maze_descriptors);
         self.maze_pipeline = Some(maze_pipeline);
-        self.maze_textures = Some(maze_textures);
+        self.maze_shader_paths = Some((vertex_shader_path.to_string(), fragment_shader_path.to_string()));
+
+        Ok(())
     }
 
     fn update_uniform_data(&mut self, uniform_data: UniformData) {
-        let maze_descriptors = self.maze_descriptors.take().unwrap();
-        let uniform_buffers = maze_descriptors.get_uniform_buffers_memory();
+        let uniform_buffers = self.maze_descriptors.as_ref().unwrap().get_uniform_buffers_memory();
 
         unsafe {
             for n in uniform_buffers.iter() {
@@ -95,19 +255,197 @@ impl Renderer for VulkanRenderer {
                 std::ptr::copy_nonoverlapping(uniform_buffer_data, uniform_buffer_memory.cast(), uniform_buffer_data.len());
             }
         }
+    }
 
-        self.maze_descriptors = Some(maze_descriptors);
+    fn draw_mesh_instanced(&mut self, mesh_id: MeshId, instances: &[(glm::Mat4, i32)]) {
+        if instances.is_empty() {
+            return;
+        }
+
+        let (Some((_, mesh)), Some(maze_pipeline)) = (self.meshes.get(mesh_id.0), &self.maze_pipeline) else {
+            return;
+        };
+
+        let instance_data: Vec<InstanceData> = instances.iter()
+            .map(|&(model_matrix, texture_index)| InstanceData { model_matrix, texture_index })
+            .collect();
+
+        self.draw_queue.add_mesh(mesh, maze_pipeline, &instance_data);
     }
 
-    fn draw(&mut self, model_matrix: glm::Mat4, texture_index: i32) {
-        let mut maze_mesh = self.maze_mesh.take().unwrap();
-        let mut maze_pipeline = self.maze_pipeline.take().unwrap();
+    fn load_overlay_shaders(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        let overlay_pipeline = self.create_pipeline(vertex_shader_path, fragment_shader_path, self.maze_descriptors.as_ref(), PipelineType::Overlay)?;
 
-        maze_mesh.set_mesh_data(PushConstant {model_matrix, texture_index});
-        self.draw_mesh(&mut maze_mesh, &mut maze_pipeline);
+        self.overlay_pipeline = Some(overlay_pipeline);
+        self.overlay_shader_paths = Some((vertex_shader_path.to_string(), fragment_shader_path.to_string()));
 
-        self.maze_mesh = Some(maze_mesh);
-        self.maze_pipeline = Some(maze_pipeline);
+        Ok(())
+    }
+
+    fn enable_skybox(&mut self, faces_paths: Vec<String>, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        if self.get_mesh_id(SKYBOX_MESH_NAME).is_none() {
+            return Err(RendererError::InvalidMesh(String::from("Skybox mesh isn't registered")));
+        }
+
+        let faces = super::load_cubemap_faces(&faces_paths)?;
+
+        let mut skybox_texture = self.create_cubemap(faces, "Skybox cubemap");
+        let skybox_sampler = self.create_sampler(Filter::LINEAR, SamplerAddressMode::CLAMP_TO_EDGE, SamplerMipmapMode::LINEAR, 0.0, 0.0);
+
+        //Skybox transform is kept in uniform buffer, cubemap is sampled with combined sampler
+        let mut skybox_descriptors = self.create_descriptor(mem::size_of::<glm::Mat4>() as u64, "Skybox transform", 
+            Some(DescriptorImages::Combined(skybox_sampler, skybox_texture.image_view)));
+
+        match self.create_pipeline(vertex_shader_path, fragment_shader_path, Some(&skybox_descriptors), PipelineType::Skybox) {
+            Ok(skybox_pipeline) => {
+                self.skybox_texture = Some(skybox_texture);
+                self.skybox_sampler = Some(skybox_sampler);
+                self.skybox_descriptors = Some(skybox_descriptors);
+                self.skybox_pipeline = Some(skybox_pipeline);
+                self.skybox_shader_paths = Some((vertex_shader_path.to_string(), fragment_shader_path.to_string()));
+
+                Ok(())
+            },
+            Err(error) => {
+                self.destroy_descriptor(&mut skybox_descriptors);
+                self.destroy_sampler(skybox_sampler);
+                self.destroy_texture(&mut skybox_texture);
+
+                Err(error)
+            }
+        }
+    }
+
+    fn draw_skybox(&mut self, transform: glm::Mat4) {
+        let (Some((_, skybox_mesh)), Some(skybox_pipeline)) = (self.meshes.iter().find(|(mesh_name, _)| mesh_name == SKYBOX_MESH_NAME), &self.skybox_pipeline) else {
+            return;
+        };
+
+        self.draw_queue.set_skybox(skybox_mesh, skybox_pipeline, transform);
+    }
+
+    fn enable_shadows(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) {
+        if !Path::new(vertex_shader_path).exists() || !Path::new(fragment_shader_path).exists() {
+            println!("Warning: Shadow shaders not found, shadows disabled.");
+            return;
+        }
+
+        self.shadow_map = Some(VulkanShadowMap::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, SHADOW_MAP_SIZE));
+        self.shadow_shader_paths = Some((vertex_shader_path.to_string(), fragment_shader_path.to_string()));
+    }
+
+    fn enable_post_process(&mut self, vertex_shader_path: &str, fragment_shader_path: &str) -> Result<(), RendererError> {
+        //Pipelines are created for render pass they are used in, post process adds subpass to it
+        if self.maze_pipeline.is_some() || self.overlay_pipeline.is_some() {
+            return Err(RendererError::PipelineCreation(String::from("Post process has to be enabled before shaders are loaded")));
+        }
+
+        unsafe {
+            self.vulkan_context.logical_device.device_wait_idle().unwrap();
+        }
+
+        self.recreate_render_pass(true);
+
+        let mut post_descriptors = self.create_post_descriptor();
+
+        //Render pass without post process subpass is used again when pipeline can't be created
+        match self.create_pipeline(vertex_shader_path, fragment_shader_path, Some(&post_descriptors), PipelineType::Post) {
+            Ok(post_pipeline) => {
+                self.post_descriptors = Some(post_descriptors);
+                self.post_pipeline = Some(post_pipeline);
+                self.post_shader_paths = Some((vertex_shader_path.to_string(), fragment_shader_path.to_string()));
+
+                Ok(())
+            },
+            Err(error) => {
+                self.destroy_descriptor(&mut post_descriptors);
+                self.recreate_render_pass(false);
+
+                Err(error)
+            }
+        }
+    }
+
+    fn reload_shaders(&mut self) {
+        //Pipelines are rebuilt only if all shader files are still there
+        let shaders_found = [&self.maze_shader_paths, &self.overlay_shader_paths, &self.shadow_shader_paths, &self.skybox_shader_paths, &self.post_shader_paths].iter()
+            .flat_map(|shader_paths| shader_paths.iter())
+            .all(|(vertex_shader_path, fragment_shader_path)| Path::new(vertex_shader_path).exists() && Path::new(fragment_shader_path).exists());
+
+        if !shaders_found {
+            println!("Error: Shader reload failed, shader files not found.");
+            return;
+        }
+
+        //Pipelines can't be destroyed while they are in use
+        unsafe {
+            self.vulkan_context.logical_device.device_wait_idle().unwrap();
+        }
+
+        //Old pipeline is destroyed only when new one was created
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(maze_pipeline)) = (self.maze_shader_paths.clone(), self.maze_pipeline.take()) {
+            self.maze_pipeline = Some(self.replace_pipeline(maze_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Maze));
+        }
+
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(overlay_pipeline)) = (self.overlay_shader_paths.clone(), self.overlay_pipeline.take()) {
+            self.overlay_pipeline = Some(self.replace_pipeline(overlay_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Overlay));
+        }
+
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(shadow_pipeline)) = (self.shadow_shader_paths.clone(), self.shadow_pipeline.take()) {
+            self.shadow_pipeline = Some(self.replace_pipeline(shadow_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Shadow));
+        }
+
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(skybox_pipeline)) = (self.skybox_shader_paths.clone(), self.skybox_pipeline.take()) {
+            self.skybox_pipeline = Some(self.replace_pipeline(skybox_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Skybox));
+        }
+
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(post_pipeline)) = (self.post_shader_paths.clone(), self.post_pipeline.take()) {
+            self.post_pipeline = Some(self.replace_pipeline(post_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Post));
+        }
+    }
+
+    fn draw_overlay(&mut self, model_matrix: glm::Mat4, color: [f32; 4], texture_index: i32) {
+        let (Some((_, quad_mesh)), Some(overlay_pipeline)) = (self.meshes.iter().find(|(mesh_name, _)| mesh_name == QUAD_MESH_NAME), &self.overlay_pipeline) else {
+            return;
+        };
+
+        //Vulkan has Y axis pointing down so top of the screen is -1 without flipping
+        let projection = glm::ortho_rh_zo(0.0, self.vulkan_context.surface_resolution.width as f32, 
+            0.0, self.vulkan_context.surface_resolution.height as f32, -1.0, 1.0);
+
+        let push_constants = PushConstantData::Overlay(OverlayPushConstant {
+            transform: projection * model_matrix,
+            color: glm::make_vec4(&color),
+            texture_index
+        });
+
+        self.draw_queue.add_overlay(quad_mesh, overlay_pipeline, push_constants);
+    }
+
+    fn get_viewport_size(&self) -> (u32, u32) {
+        (self.vulkan_context.surface_resolution.width, self.vulkan_context.surface_resolution.height)
+    }
+
+    fn set_wireframe(&mut self, wireframe: bool) {
+        if wireframe && !self.vulkan_context.wireframe_supported {
+            println!("Warning: Wireframe rendering isn't supported by selected device.");
+            return;
+        }
+
+        if self.wireframe == wireframe {
+            return;
+        }
+
+        self.wireframe = wireframe;
+
+        //Polygon mode is part of pipeline so maze pipeline has to be rebuilt (if it was already created)
+        if let (Some((vertex_shader_path, fragment_shader_path)), Some(maze_pipeline)) = (self.maze_shader_paths.clone(), self.maze_pipeline.take()) {
+            unsafe {
+                self.vulkan_context.logical_device.device_wait_idle().unwrap();
+            }
+
+            self.maze_pipeline = Some(self.replace_pipeline(maze_pipeline, &vertex_shader_path, &fragment_shader_path, PipelineType::Maze));
+        }
     }
 
     fn clear_color(&mut self, color: [f32; 4]) {
@@ -118,6 +456,14 @@ impl Renderer for VulkanRenderer {
         self.render()
     }
 
+    fn request_capture(&mut self) {
+        self.request_capture();
+    }
+
+    fn capture_frame(&mut self) -> Option<image::RgbaImage> {
+        self.capture_frame()
+    }
+
     fn resize_viewport(&mut self, window_width: u32, window_height: u32) {
         self.resize_viewport(window_width, window_height);
     }
@@ -127,7 +473,6 @@ impl Renderer for VulkanRenderer {
             self.vulkan_context.logical_device.device_wait_idle().unwrap();
         }
 
-        let mut maze_mesh = self.maze_mesh.take().unwrap();
         let mut maze_pipeline = self.maze_pipeline.take().unwrap();
         let mut maze_descriptors = self.maze_descriptors.take().unwrap();
         let mut maze_textures = self.maze_textures.take().unwrap();
@@ -138,14 +483,72 @@ impl Renderer for VulkanRenderer {
             self.destroy_texture(maze_texture);
         }
 
-        self.destroy_mesh(&mut maze_mesh);
+        if let Some(mut maze_normal_maps) = self.maze_normal_maps.take() {
+            for maze_normal_map in maze_normal_maps.iter_mut() {
+                self.destroy_texture(maze_normal_map);
+            }
+        }
+
+        for (_, mut mesh) in mem::take(&mut self.meshes) {
+            self.destroy_mesh(&mut mesh);
+        }
+
         self.destroy_descriptor(&mut maze_descriptors);
         self.destroy_pipeline(&mut maze_pipeline);
+
+        if let Some(mut overlay_pipeline) = self.overlay_pipeline.take() {
+            self.destroy_pipeline(&mut overlay_pipeline);
+        }
+
+        if let Some(mut shadow_pipeline) = self.shadow_pipeline.take() {
+            self.destroy_pipeline(&mut shadow_pipeline);
+        }
+
+        if let Some(mut shadow_map) = self.shadow_map.take() {
+            shadow_map.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+        }
+
+        if let Some(mut skybox_pipeline) = self.skybox_pipeline.take() {
+            self.destroy_pipeline(&mut skybox_pipeline);
+        }
+
+        if let Some(mut skybox_descriptors) = self.skybox_descriptors.take() {
+            self.destroy_descriptor(&mut skybox_descriptors);
+        }
+
+        if let Some(skybox_sampler) = self.skybox_sampler.take() {
+            self.destroy_sampler(skybox_sampler);
+        }
+
+        if let Some(mut skybox_texture) = self.skybox_texture.take() {
+            self.destroy_texture(&mut skybox_texture);
+        }
+
+        if let Some(mut post_pipeline) = self.post_pipeline.take() {
+            self.destroy_pipeline(&mut post_pipeline);
+        }
+
+        if let Some(mut post_descriptors) = self.post_descriptors.take() {
+            self.destroy_descriptor(&mut post_descriptors);
+        }
+
+        for instance_buffer in self.instance_buffers.iter_mut() {
+            if let Some(mut buffer) = instance_buffer.take() {
+                buffer.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+            }
+        }
     }
 }
 
 const MAX_FRAMES_IN_FLIGHT: usize = 2;
-const SAMPLE_COUNT: SampleCountFlags = SampleCountFlags::TYPE_4;
+
+//sRGB format converts texels to linear colors when sampling, UNORM returns stored values
+fn texture_format(color_space: ColorSpace) -> Format {
+    match color_space {
+        ColorSpace::SRGB => Format::R8G8B8A8_SRGB,
+        ColorSpace::LINEAR => Format::R8G8B8A8_UNORM
+    }
+}
 
 //Per frame data
 struct FrameData {
@@ -161,81 +564,176 @@ struct RenderableMesh {
     index_buffer: Option<Buffer>,
     vertices_count: u32,
     indices_count: u32,
-    push_constants: PushConstant,
+    push_constants: Option<PushConstantData>,
+    instances: Option<(u32, u32)>, //First instance and instance count in frame instance buffer
+    push_constant_stages: ShaderStageFlags,
     pipeline_layout: PipelineLayout,
     graphics_pipeline: Pipeline,
     descriptor_sets: Vec<DescriptorSet>
 }
 
+//Everything requested for drawing in current frame
+//Kept apart from meshes and pipelines so they can be borrowed while queue is modified
+struct DrawQueue {
+    meshes: Vec<RenderableMesh>,
+    skybox: Option<RenderableMesh>,
+    skybox_transform: glm::Mat4,
+    overlays: Vec<RenderableMesh>,
+    instances: Vec<InstanceData>
+}
+
+//Kinds of pipelines used by renderer
+//Maze pipeline draws lit 3D geometry, overlay pipeline draws 2D quads on top of it
+//Shadow pipeline draws maze geometry into shadow map (depth only)
+//Skybox pipeline draws cubemap at far plane where maze wasn't drawn
+//Post pipeline draws rendered scene into swapchain image with fullscreen triangle
+#[derive(Copy, Clone, PartialEq)]
+pub enum PipelineType {
+    Maze,
+    Overlay,
+    Shadow,
+    Skybox,
+    Post
+}
+
 //Graphics pipeline and related objects. Each mesh can be rendered with different pipeline.
 pub struct RenderPipeline {
     pipeline_layout: PipelineLayout,
     graphics_pipeline: Pipeline,
     vertex_shader: ShaderModule,
     fragment_shader: ShaderModule,
-    descriptor_sets: Vec<DescriptorSet>
+    descriptor_sets: Vec<DescriptorSet>,
+    push_constant_stages: ShaderStageFlags
+}
+
+impl RenderableMesh {
+    fn new(mesh: &VulkanMesh, render_pipeline: &RenderPipeline, push_constants: Option<PushConstantData>, instances: Option<(u32, u32)>) -> Self {
+        let index_buffer = match &mesh.index_buffer {
+            Some(value) => Some(value.buffer),
+            None => None,
+        };
+
+        Self {
+            vertex_buffer: mesh.vertex_buffer.as_ref().unwrap().buffer,
+            index_buffer: index_buffer,
+            vertices_count: mesh.vertex_input.as_ref().unwrap().vertex_data.len() as u32,
+            indices_count: mesh.vertex_indices.len() as u32,
+            push_constants,
+            instances,
+            push_constant_stages: render_pipeline.push_constant_stages,
+            pipeline_layout: render_pipeline.pipeline_layout,
+            graphics_pipeline: render_pipeline.graphics_pipeline,
+            descriptor_sets: render_pipeline.descriptor_sets.clone()
+        }
+    }
+}
+
+impl DrawQueue {
+    fn new() -> Self {
+        Self {
+            meshes: Vec::new(),
+            skybox: None,
+            skybox_transform: glm::Mat4::identity(),
+            overlays: Vec::new(),
+            instances: Vec::new()
+        }
+    }
+
+    fn add_mesh(&mut self, mesh: &VulkanMesh, render_pipeline: &RenderPipeline, instances: &[InstanceData]) {
+        let first_instance = self.instances.len() as u32;
+        self.instances.extend_from_slice(instances);
+
+        self.meshes.push(RenderableMesh::new(mesh, render_pipeline, None, Some((first_instance, instances.len() as u32))));
+    }
+
+    fn add_overlay(&mut self, mesh: &VulkanMesh, render_pipeline: &RenderPipeline, push_constants: PushConstantData) {
+        self.overlays.push(RenderableMesh::new(mesh, render_pipeline, Some(push_constants), None));
+    }
+
+    //Transform is uploaded to skybox uniform buffer of the frame before recording
+    fn set_skybox(&mut self, mesh: &VulkanMesh, render_pipeline: &RenderPipeline, transform: glm::Mat4) {
+        self.skybox = Some(RenderableMesh::new(mesh, render_pipeline, None, None));
+        self.skybox_transform = transform;
+    }
+
+    fn clear(&mut self) {
+        self.meshes.clear();
+        self.skybox = None;
+        self.overlays.clear();
+        self.instances.clear();
+    }
 }
 
 pub struct VulkanRenderer {
     _vulkan_entry: Entry,
     vulkan_context: VulkanContext,
-    color_image: VulkanImage,
+    color_image: Option<VulkanImage>, //Multisampled color image, not used without MSAA
     depth_image: VulkanImage,
+    post_image: Option<VulkanImage>, //Scene read by post process subpass, not used without post process
+    sample_count: SampleCountFlags,
     render_pass: RenderPass,
     framebuffers: Vec<Framebuffer>,
     command_pool: CommandPool,
     frame_data: Vec<FrameData>,
     current_frame: usize,
+    capture_requested: bool,
+    captured_frame: Option<image::RgbaImage>,
     clear_color: [f32; 4],
-    meshes_to_draw: Vec<RenderableMesh>,
+    draw_queue: DrawQueue,
+    instance_buffers: Vec<Option<VulkanBuffer>>,
 
-    maze_mesh: Option<VulkanMesh>,
+    meshes: Vec<(String, VulkanMesh)>, //Registered meshes with their names, MeshId is index into this list
     maze_textures: Option<Vec<VulkanImage>>,
+    maze_normal_maps: Option<Vec<VulkanImage>>,
     maze_texture_sampler: Option<Sampler>,
     maze_descriptors: Option<VulkanDescriptor>,
-    maze_pipeline: Option<RenderPipeline>
+    maze_pipeline: Option<RenderPipeline>,
+    overlay_pipeline: Option<RenderPipeline>,
+    maze_shader_paths: Option<(String, String)>,
+    overlay_shader_paths: Option<(String, String)>,
+    shadow_map: Option<VulkanShadowMap>,
+    shadow_pipeline: Option<RenderPipeline>,
+    shadow_shader_paths: Option<(String, String)>,
+    skybox_texture: Option<VulkanImage>,
+    skybox_sampler: Option<Sampler>,
+    skybox_descriptors: Option<VulkanDescriptor>,
+    skybox_pipeline: Option<RenderPipeline>,
+    skybox_shader_paths: Option<(String, String)>,
+    post_descriptors: Option<VulkanDescriptor>,
+    post_pipeline: Option<RenderPipeline>,
+    post_shader_paths: Option<(String, String)>,
+    wireframe: bool,
+    texture_filter: TextureFilter
 }
 
 impl VulkanRenderer {
-    pub fn new(window: &Window, vsync_enabled: bool) -> Self {
-        let _vulkan_entry = Entry::linked();
-        let mut vulkan_context = VulkanContext::new(window, &_vulkan_entry, vsync_enabled);
+    //List Vulkan devices without creating window or renderer
+    pub fn list_devices(app_name: &str) -> Result<Vec<PhysicalDeviceInfo>, Box<dyn Error>> {
+        let vulkan_entry = unsafe {
+            Entry::load().map_err(|error| format!("Vulkan loader not found: {}", error))?
+        };
 
-        let supported_sample_count = vulkan_context.get_physical_device_properties().limits.framebuffer_color_sample_counts;
+        VulkanContext::list_devices(&vulkan_entry, app_name)
+    }
 
-        if (SAMPLE_COUNT & supported_sample_count).is_empty() {
-            panic!("Unsupported sample count.");
-        }
+    pub fn new(window: &Window, app_name: &str, vsync_enabled: bool, triple_buffering: bool, gpu_index: Option<usize>, debug_enabled: bool, msaa_samples: u32, 
+        verbose: bool) -> Result<Self, Box<dyn Error>> {
+        //Vulkan library is loaded at runtime so missing loader can be reported instead of failing at startup
+        let _vulkan_entry = unsafe {
+            Entry::load().map_err(|error| format!("Vulkan loader not found: {}", error))?
+        };
 
-        let color_image = VulkanImage::new(&vulkan_context.logical_device, &mut vulkan_context.allocator, "Color image", vulkan_context.surface_resolution.width, 
-            vulkan_context.surface_resolution.height, vulkan_context.surface_format.format, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSIENT_ATTACHMENT | ImageUsageFlags::COLOR_ATTACHMENT, 
-            ImageAspectFlags::COLOR, false, SAMPLE_COUNT);
+        let mut vulkan_context = VulkanContext::new(window, &_vulkan_entry, app_name, vsync_enabled, triple_buffering, gpu_index, 
+            debug_enabled, verbose)?;
 
-        let depth_image = VulkanImage::new(&vulkan_context.logical_device, &mut vulkan_context.allocator, "Depth buffer", vulkan_context.surface_resolution.width, 
-            vulkan_context.surface_resolution.height, Format::D32_SFLOAT, ImageTiling::OPTIMAL, ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT, 
-            ImageAspectFlags::DEPTH, false, SAMPLE_COUNT);
+        let device_limits = vulkan_context.get_physical_device_properties().limits;
+        let sample_count = Self::select_sample_count(msaa_samples, device_limits.framebuffer_color_sample_counts & device_limits.framebuffer_depth_sample_counts);
 
-        let render_pass = Self::create_render_pass(vulkan_context.surface_format.format, &vulkan_context.logical_device, &depth_image);
+        let (color_image, depth_image) = Self::create_render_targets(&mut vulkan_context, sample_count);
 
-        let framebuffers: Vec<vk::Framebuffer> = vulkan_context
-            .swapchain_image_views
-            .iter()
-            .map(|&swapchain_image_view| {
-                let framebuffer_attachments = [color_image.image_view, depth_image.image_view, swapchain_image_view];
-                let frame_buffer_create_info = vk::FramebufferCreateInfo::builder()
-                    .render_pass(render_pass)
-                    .attachments(&framebuffer_attachments)
-                    .width(vulkan_context.surface_resolution.width)
-                    .height(vulkan_context.surface_resolution.height)
-                    .layers(1);
+        let render_pass = Self::create_render_pass(vulkan_context.surface_format.format, &vulkan_context.logical_device, &depth_image, sample_count, false);
 
-                unsafe {
-                    vulkan_context.logical_device
-                        .create_framebuffer(&frame_buffer_create_info, None)
-                        .unwrap()
-                }
-            })
-            .collect();
+        let framebuffers = Self::create_framebuffers(&vulkan_context, render_pass, color_image.as_ref(), &depth_image, None);
 
         let (command_pool, command_buffers) = Self::create_commands(&vulkan_context.logical_device, vulkan_context.queue_family_index, MAX_FRAMES_IN_FLIGHT as u32);
 
@@ -272,28 +770,214 @@ impl VulkanRenderer {
             println!("Selected device: {}", String::from_utf8(device_name).unwrap());
         }
         
-        Self {
+        Ok(Self {
             _vulkan_entry,
             vulkan_context,
             color_image,
             depth_image,
+            post_image: None,
+            sample_count,
             render_pass,
             framebuffers,
             command_pool,
             frame_data,
             current_frame: 0,
+            capture_requested: false,
+            captured_frame: None,
             clear_color: [0.0, 0.0, 0.0, 1.0],
-            meshes_to_draw: Vec::new(),
+            draw_queue: DrawQueue::new(),
+            instance_buffers: (0..MAX_FRAMES_IN_FLIGHT).map(|_| None).collect(),
 
-            maze_mesh: None,
+            meshes: Vec::new(),
             maze_textures: None,
+            maze_normal_maps: None,
             maze_texture_sampler: None,
             maze_descriptors: None,
-            maze_pipeline: None
+            maze_pipeline: None,
+            overlay_pipeline: None,
+            maze_shader_paths: None,
+            overlay_shader_paths: None,
+            shadow_map: None,
+            shadow_pipeline: None,
+            shadow_shader_paths: None,
+            skybox_texture: None,
+            skybox_sampler: None,
+            skybox_descriptors: None,
+            skybox_pipeline: None,
+            skybox_shader_paths: None,
+            post_descriptors: None,
+            post_pipeline: None,
+            post_shader_paths: None,
+            wireframe: false,
+            texture_filter: TextureFilter::LINEAR
+        })
+    }
+
+    //Draw queued maze meshes into shadow map, nothing is recorded if shadows aren't enabled
+    fn record_shadow_pass(&self, command_buffer: CommandBuffer) {
+        let (Some(shadow_map), Some(shadow_pipeline)) = (&self.shadow_map, &self.shadow_pipeline) else {
+            return;
+        };
+
+        let Some(instance_buffer) = &self.instance_buffers[self.current_frame] else {
+            return;
+        };
+
+        let logical_device = &self.vulkan_context.logical_device;
+
+        let clear_values = &[
+            vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 1.0,
+                    stencil: 0
+                }
+            }
+        ];
+
+        let shadow_map_area = vk::Extent2D {
+            width: shadow_map.size,
+            height: shadow_map.size
+        };
+
+        let render_pass_begin_info = RenderPassBeginInfo::builder()
+            .render_pass(shadow_map.render_pass)
+            .framebuffer(shadow_map.framebuffer)
+            .render_area(shadow_map_area.into())
+            .clear_values(clear_values)
+            .build();
+
+        unsafe {
+            logical_device.cmd_begin_render_pass(command_buffer, &render_pass_begin_info, SubpassContents::INLINE);
+
+            let viewports = [vk::Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: shadow_map.size as f32,
+                height: shadow_map.size as f32,
+                min_depth: 0.0,
+                max_depth: 1.0,
+            }];
+
+            logical_device.cmd_set_viewport(command_buffer, 0, &viewports);
+            logical_device.cmd_set_scissor(command_buffer, 0, &[shadow_map_area.into()]);
+
+            logical_device.cmd_bind_pipeline(command_buffer, PipelineBindPoint::GRAPHICS, shadow_pipeline.graphics_pipeline);
+            logical_device.cmd_bind_descriptor_sets(command_buffer, PipelineBindPoint::GRAPHICS, shadow_pipeline.pipeline_layout, 0, 
+                &[shadow_pipeline.descriptor_sets[self.current_frame]], &[]);
+            logical_device.cmd_bind_vertex_buffers(command_buffer, 1, &[instance_buffer.buffer], &[0]);
+
+            for mesh in self.draw_queue.meshes.iter() {
+                let Some((first_instance, instance_count)) = mesh.instances else {
+                    continue;
+                };
+
+                logical_device.cmd_bind_vertex_buffers(command_buffer, 0, &[mesh.vertex_buffer], &[0]);
+
+                match mesh.index_buffer {
+                    Some(index_buffer) => {
+                        logical_device.cmd_bind_index_buffer(command_buffer, index_buffer, 0, IndexType::UINT32);
+                        logical_device.cmd_draw_indexed(command_buffer, mesh.indices_count, instance_count, 0, 0, first_instance);
+                    },
+                    None => logical_device.cmd_draw(command_buffer, mesh.vertices_count, instance_count, 0, first_instance)
+                }
+            }
+
+            logical_device.cmd_end_render_pass(command_buffer);
+        }
+    }
+
+    //Record draw commands of meshes inside render pass
+    fn record_meshes<'a>(&self, command_buffer: CommandBuffer, meshes: impl Iterator<Item = &'a RenderableMesh>) {
+        let logical_device = &self.vulkan_context.logical_device;
+
+        unsafe {
+            //Store last used pipelines, descriptor sets and buffers to avoid binding same thing every time
+            let mut last_pipeline = Pipeline::null();
+            let mut last_pipeline_layout = PipelineLayout::null();
+            let mut last_descriptor_set = DescriptorSet::null();
+            let mut last_vertex_buffer = Buffer::null();
+            let mut last_index_buffer = Buffer::null();
+            let mut last_instance_buffer = Buffer::null();
+
+            let instance_buffer = match &self.instance_buffers[self.current_frame] {
+                Some(buffer) => buffer.buffer,
+                None => Buffer::null()
+            };
+
+            for mesh in meshes {
+                let pipeline = mesh.graphics_pipeline;
+                let pipeline_layout = mesh.pipeline_layout;
+                let vertex_buffer = mesh.vertex_buffer;
+
+                if pipeline != last_pipeline {
+                    logical_device.cmd_bind_pipeline(command_buffer, PipelineBindPoint::GRAPHICS, pipeline);
+                }
+
+                if !mesh.descriptor_sets.is_empty() {
+                    let descriptor_set = mesh.descriptor_sets[self.current_frame];
+
+                    //Pipeline layouts with different push constant ranges aren't compatible so sets need to be bound again
+                    if last_descriptor_set != descriptor_set || last_pipeline_layout != pipeline_layout {
+                        logical_device.cmd_bind_descriptor_sets(command_buffer, PipelineBindPoint::GRAPHICS, pipeline_layout, 0, 
+                            &[descriptor_set], &[]);
+                    }
+
+                    last_descriptor_set = descriptor_set;
+                }
+
+                if vertex_buffer != last_vertex_buffer {
+                    logical_device.cmd_bind_vertex_buffers(command_buffer, 0, &[vertex_buffer], &[0]);
+                }
+
+                if let Some(push_constants) = &mesh.push_constants {
+                    logical_device.cmd_push_constants(command_buffer, pipeline_layout, mesh.push_constant_stages, 0, push_constants.as_bytes());
+                }
+
+                let (first_instance, instance_count) = match mesh.instances {
+                    Some(instances) => {
+                        if instance_buffer != last_instance_buffer {
+                            logical_device.cmd_bind_vertex_buffers(command_buffer, 1, &[instance_buffer], &[0]);
+                            last_instance_buffer = instance_buffer;
+                        }
+
+                        instances
+                    },
+                    None => (0, 1)
+                };
+
+                //Index buffer is available, draw indexed
+                if mesh.index_buffer.is_some() {
+                    let index_buffer = mesh.index_buffer.unwrap();
+
+                    if index_buffer != last_index_buffer {
+                        logical_device.cmd_bind_index_buffer(command_buffer, mesh.index_buffer.unwrap(), 0, IndexType::UINT32);
+                    }
+
+                    logical_device.cmd_draw_indexed(command_buffer, mesh.indices_count, instance_count, 0, 0, first_instance);
+                    last_index_buffer = mesh.index_buffer.unwrap();
+                } 
+                else { //No index buffer, draw without it
+                    logical_device.cmd_draw(command_buffer, mesh.vertices_count, instance_count, 0, first_instance);
+                }
+
+                last_pipeline = mesh.graphics_pipeline;
+                last_pipeline_layout = mesh.pipeline_layout;
+                last_vertex_buffer = mesh.vertex_buffer;
+            }
         }
     }
 
-    pub fn render(&mut self) -> RenderResult {
+    pub fn render(&mut self) -> RenderResult {
+        self.upload_instance_data();
+        self.upload_skybox_transform();
+
+        let capture_swap_red_blue = match self.capture_requested {
+            true => self.capture_swap_red_blue(),
+            false => None
+        };
+
+        self.capture_requested = false;
+
         unsafe {
             let logical_device = &self.vulkan_context.logical_device;
             let swapchain_loader = &self.vulkan_context.swapchain_loader;
@@ -315,6 +999,14 @@ impl VulkanRenderer {
 
             logical_device.begin_command_buffer(command_buffer, &CommandBufferBeginInfo::default()).expect("Command buffer record failed.");
 
+            let width = self.vulkan_context.surface_resolution.width;
+            let height = self.vulkan_context.surface_resolution.height;
+
+            let capture_buffer = capture_swap_red_blue.map(|_| VulkanBuffer::new(logical_device, &mut self.vulkan_context.allocator, (width * height * 4) as u64, 
+                BufferUsageFlags::TRANSFER_DST, gpu_allocator::MemoryLocation::GpuToCpu, "Frame capture buffer"));
+
+            self.record_shadow_pass(command_buffer);
+
             let clear_values = &[
                 vk::ClearValue {
                     color: vk::ClearColorValue {
@@ -352,64 +1044,30 @@ impl VulkanRenderer {
             logical_device.cmd_set_viewport(command_buffer, 0, &viewports);
             logical_device.cmd_set_scissor(command_buffer, 0, &scissors);
 
-            //Store last used pipelines, descriptor sets and buffers to avoid binding same thing every time
-            let mut last_pipeline = Pipeline::null();
-            let mut last_descriptor_set = DescriptorSet::null();
-            let mut last_vertex_buffer = Buffer::null();
-            let mut last_index_buffer = Buffer::null();
-
-            for mesh in self.meshes_to_draw.iter() {
-                let pipeline = mesh.graphics_pipeline;
-                let pipeline_layout = mesh.pipeline_layout;
-                let vertex_buffer = mesh.vertex_buffer;
-
-                if pipeline != last_pipeline {
-                    logical_device.cmd_bind_pipeline(command_buffer, PipelineBindPoint::GRAPHICS, pipeline);
-                }
-
-                if !mesh.descriptor_sets.is_empty() {
-                    let descriptor_set = mesh.descriptor_sets[self.current_frame];
-
-                    if last_descriptor_set != descriptor_set {
-                        logical_device.cmd_bind_descriptor_sets(command_buffer, PipelineBindPoint::GRAPHICS, pipeline_layout, 0, 
-                            &[descriptor_set], &[]);
-                    }
-
-                    last_descriptor_set = descriptor_set;
-                }
-
-                if vertex_buffer != last_vertex_buffer {
-                    logical_device.cmd_bind_vertex_buffers(command_buffer, 0, &[vertex_buffer], &[0]);
-                }
-
-                let push_constant_bytes = std::slice::from_raw_parts(
-                    &mesh.push_constants as *const PushConstant as *const u8,
-                    size_of::<PushConstant>()
-                );
+            //Skybox is drawn after maze so it's shaded only where maze doesn't cover it
+            self.record_meshes(command_buffer, self.draw_queue.meshes.iter().chain(self.draw_queue.skybox.iter()));
 
-                logical_device.cmd_push_constants(command_buffer, pipeline_layout, ShaderStageFlags::VERTEX, 0, push_constant_bytes);
+            //Scene is drawn into swapchain image by post process subpass
+            if let Some(post_pipeline) = &self.post_pipeline {
+                logical_device.cmd_next_subpass(command_buffer, SubpassContents::INLINE);
 
-                //Index buffer is available, draw indexed
-                if mesh.index_buffer.is_some() {
-                    let index_buffer = mesh.index_buffer.unwrap();
+                logical_device.cmd_bind_pipeline(command_buffer, PipelineBindPoint::GRAPHICS, post_pipeline.graphics_pipeline);
+                logical_device.cmd_bind_descriptor_sets(command_buffer, PipelineBindPoint::GRAPHICS, post_pipeline.pipeline_layout, 0, 
+                    &[post_pipeline.descriptor_sets[self.current_frame]], &[]);
+                logical_device.cmd_draw(command_buffer, 3, 1, 0, 0);
+            }
 
-                    if index_buffer != last_index_buffer {
-                        logical_device.cmd_bind_index_buffer(command_buffer, mesh.index_buffer.unwrap(), 0, IndexType::UINT32);
-                    }
+            //Overlays are drawn last so they end up on top of the scene
+            self.record_meshes(command_buffer, self.draw_queue.overlays.iter());
 
-                    logical_device.cmd_draw_indexed(command_buffer, mesh.indices_count, 1, 0, 0, 0);
-                    last_index_buffer = mesh.index_buffer.unwrap();
-                } 
-                else { //No index buffer, draw without it
-                    logical_device.cmd_draw(command_buffer, mesh.vertices_count, 1, 0, 0);
-                }
+            logical_device.cmd_end_render_pass(command_buffer);
 
-                last_pipeline = mesh.graphics_pipeline;
-                last_vertex_buffer = mesh.vertex_buffer;
+            //Captured frame is copied from swapchain image before it's presented
+            if let Some(capture_buffer) = &capture_buffer {
+                VulkanImage::record_swapchain_image_copy(logical_device, command_buffer, self.vulkan_context.swapchain_images[image_index as usize], 
+                    width, height, capture_buffer);
             }
 
-            logical_device.cmd_end_render_pass(command_buffer);
-
             logical_device.end_command_buffer(command_buffer).expect("Recording command buffer failed.");
 
             let wait_sempahores = &[image_available_semaphore];
@@ -425,6 +1083,25 @@ impl VulkanRenderer {
 
             logical_device.queue_submit(self.vulkan_context.present_queue, &[submit_info.build()], in_flight_fence).unwrap();
 
+            if let (Some(mut capture_buffer), Some(swap_red_blue)) = (capture_buffer, capture_swap_red_blue) {
+                logical_device.wait_for_fences(&[in_flight_fence], true, u64::MAX).unwrap();
+
+                let mut pixels = vec![0u8; (width * height * 4) as usize];
+                std::ptr::copy_nonoverlapping(capture_buffer.memory.as_ptr().cast::<u8>(), pixels.as_mut_ptr(), pixels.len());
+
+                capture_buffer.free(logical_device, &mut self.vulkan_context.allocator);
+
+                for pixel in pixels.chunks_exact_mut(4) {
+                    if swap_red_blue {
+                        pixel.swap(0, 2);
+                    }
+
+                    pixel[3] = 255;
+                }
+
+                self.captured_frame = image::RgbaImage::from_raw(width, height, pixels);
+            }
+
             let wait_semaphores = &[render_finished_semaphore];
             let swapchains = &[self.vulkan_context.swapchain_khr];
             let image_indices = &[image_index];
@@ -442,34 +1119,94 @@ impl VulkanRenderer {
 
             self.current_frame = (self.current_frame + 1) & MAX_FRAMES_IN_FLIGHT;
 
-            self.meshes_to_draw.clear();
+            self.draw_queue.clear();
         }
 
         RenderResult::RenderFinished
     }
 
+    //Next rendered frame is copied from swapchain image before it's presented
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+        self.captured_frame = None;
+    }
+
+    pub fn capture_frame(&mut self) -> Option<image::RgbaImage> {
+        self.captured_frame.take()
+    }
+
+    //Swapchain stores already encoded values so sRGB formats don't need conversion, only channel order matters
+    //None if frame can't be captured
+    fn capture_swap_red_blue(&self) -> Option<bool> {
+        if !self.vulkan_context.swapchain_transfer_supported {
+            println!("Error: Swapchain doesn't support copying images, can't capture frame.");
+            return None;
+        }
+
+        match self.vulkan_context.surface_format.format {
+            Format::B8G8R8A8_SRGB | Format::B8G8R8A8_UNORM => Some(true),
+            Format::R8G8B8A8_SRGB | Format::R8G8B8A8_UNORM => Some(false),
+            format => {
+                println!("Error: Unsupported swapchain format {:?}, can't capture frame.", format);
+                None
+            }
+        }
+    }
+
     pub fn clear_color(&mut self, color: [f32; 4]) {
         self.clear_color = color;
     }
 
-    pub fn draw_mesh(&mut self, mesh: &VulkanMesh, render_pipeline: &RenderPipeline) {        
-        let index_buffer = match &mesh.index_buffer {
-            Some(value) => Some(value.buffer),
-            None => None,
+    //Copy instances of all meshes to instance buffer of current frame, buffer is recreated when it's too small
+    fn upload_instance_data(&mut self) {
+        if self.draw_queue.instances.is_empty() {
+            return;
+        }
+
+        let required_size = (self.draw_queue.instances.len() * mem::size_of::<InstanceData>()) as u64;
+
+        unsafe {
+            //Buffer can still be used by previous frame with the same index
+            let in_flight_fence = self.frame_data[self.current_frame].in_flight_fence;
+            self.vulkan_context.logical_device.wait_for_fences(&[in_flight_fence], true, u64::MAX).unwrap();
+        }
+
+        let buffer_too_small = match &self.instance_buffers[self.current_frame] {
+            Some(buffer) => buffer.size < required_size,
+            None => true
         };
 
-        let renderable_mesh = RenderableMesh {
-            vertex_buffer: mesh.vertex_buffer.as_ref().unwrap().buffer,
-            index_buffer: index_buffer,
-            vertices_count: mesh.vertex_input.as_ref().unwrap().vertex_data.len() as u32,
-            indices_count: mesh.vertex_indices.len() as u32,
-            push_constants: mesh.push_constant,
-            pipeline_layout: render_pipeline.pipeline_layout,
-            graphics_pipeline: render_pipeline.graphics_pipeline,
-            descriptor_sets: render_pipeline.descriptor_sets.clone()
+        if buffer_too_small {
+            if let Some(mut buffer) = self.instance_buffers[self.current_frame].take() {
+                buffer.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+            }
+
+            self.instance_buffers[self.current_frame] = Some(VulkanBuffer::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, 
+                required_size.next_power_of_two(), BufferUsageFlags::VERTEX_BUFFER, gpu_allocator::MemoryLocation::CpuToGpu, "Instance buffer"));
+        }
+
+        let instance_buffer = self.instance_buffers[self.current_frame].as_ref().unwrap();
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.draw_queue.instances.as_ptr(), instance_buffer.memory.as_ptr().cast(), self.draw_queue.instances.len());
+        }
+    }
+
+    //Skybox uniform buffer of current frame can still be used by previous frame with the same index
+    fn upload_skybox_transform(&mut self) {
+        let (Some(_), Some(skybox_descriptors)) = (&self.draw_queue.skybox, &self.skybox_descriptors) else {
+            return;
         };
 
-        self.meshes_to_draw.push(renderable_mesh);
+        let uniform_buffer_memory = skybox_descriptors.get_uniform_buffers_memory()[self.current_frame];
+
+        unsafe {
+            let in_flight_fence = self.frame_data[self.current_frame].in_flight_fence;
+            self.vulkan_context.logical_device.wait_for_fences(&[in_flight_fence], true, u64::MAX).unwrap();
+
+            let skybox_transform = &[self.draw_queue.skybox_transform];
+            std::ptr::copy_nonoverlapping(skybox_transform, uniform_buffer_memory.as_ptr().cast(), skybox_transform.len());
+        }
     }
 
     pub fn populate_vertex_buffer(&mut self, mesh: &mut VulkanMesh, vertex_data: Vec<VertexData>, vertex_indices: Vec<u32>) {
@@ -509,9 +1246,15 @@ impl VulkanRenderer {
         }
     }
 
-    pub fn create_texture(&mut self, texture_path: &str, texture_name: &str, generate_mipmaps: bool) -> VulkanImage {
-        let image_buffer = image::open(texture_path).expect("Loading texture file failed.").into_rgba8();
+    pub fn create_texture(&mut self, texture_path: &str, texture_name: &str, generate_mipmaps: bool, color_space: ColorSpace) -> Result<VulkanImage, RendererError> {
+        let image_buffer = image::open(texture_path)
+            .map_err(|error| RendererError::TextureLoad(format!("{}: {}", texture_path, error)))?
+            .into_rgba8();
+
+        Ok(self.create_texture_from_image(image_buffer, texture_name, generate_mipmaps, texture_format(color_space)))
+    }
 
+    pub fn create_texture_from_image(&mut self, image_buffer: image::RgbaImage, texture_name: &str, generate_mipmaps: bool, format: Format) -> VulkanImage {
         let mut texture_staging_buffer = VulkanBuffer::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, (image_buffer.width() * image_buffer.height() * 4) as u64, 
         BufferUsageFlags::TRANSFER_SRC, gpu_allocator::MemoryLocation::CpuToGpu, "Texture staging buffer");
 
@@ -522,9 +1265,10 @@ impl VulkanRenderer {
             std::ptr::copy_nonoverlapping(texture_memory, texture_buffer_memory.cast(), image_buffer.len());
         }
 
-        let format_properties = self.vulkan_context.get_physical_device_format_properties(Format::R8G8B8A8_SRGB);
+        let format_properties = self.vulkan_context.get_physical_device_format_properties(format);
 
-        let mut mipmapping = generate_mipmaps;
+        //Single pixel image (like flat normal map) has nothing to downscale
+        let mut mipmapping = generate_mipmaps && image_buffer.width().max(image_buffer.height()) > 1;
 
         if generate_mipmaps && (format_properties.optimal_tiling_features & FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR).is_empty() {
             println!("Error: Unsupported format property, mipmapping disabled");
@@ -532,7 +1276,7 @@ impl VulkanRenderer {
         }
 
         let mut texture_image = VulkanImage::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, texture_name, 
-            image_buffer.width(), image_buffer.height(), Format::R8G8B8A8_SRGB, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSFER_SRC | 
+            image_buffer.width(), image_buffer.height(), format, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSFER_SRC | 
             ImageUsageFlags::TRANSFER_DST | ImageUsageFlags::SAMPLED, ImageAspectFlags::COLOR, mipmapping, SampleCountFlags::TYPE_1);
 
         texture_image.transition_image_layout(&self.vulkan_context.logical_device, self.vulkan_context.present_queue, self.command_pool, ImageLayout::TRANSFER_DST_OPTIMAL);
@@ -551,20 +1295,78 @@ impl VulkanRenderer {
         texture_image
     }
 
+    //Faces are copied into one staging buffer one after another, every face is one layer of cubemap
+    pub fn create_cubemap(&mut self, faces: Vec<image::RgbaImage>, texture_name: &str) -> VulkanImage {
+        let face_size = faces[0].width();
+        let face_data: Vec<u8> = faces.into_iter().flat_map(|face| face.into_raw()).collect();
+
+        let mut texture_staging_buffer = VulkanBuffer::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, face_data.len() as u64, 
+            BufferUsageFlags::TRANSFER_SRC, gpu_allocator::MemoryLocation::CpuToGpu, "Cubemap staging buffer");
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(face_data.as_ptr(), texture_staging_buffer.memory.as_ptr().cast(), face_data.len());
+        }
+
+        let mut cubemap = VulkanImage::new_cubemap(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, texture_name, face_size, 
+            Format::R8G8B8A8_SRGB);
+
+        cubemap.transition_image_layout(&self.vulkan_context.logical_device, self.vulkan_context.present_queue, self.command_pool, ImageLayout::TRANSFER_DST_OPTIMAL);
+        cubemap.populate_from_buffer(&self.vulkan_context.logical_device, self.vulkan_context.present_queue, self.command_pool, &texture_staging_buffer);
+        cubemap.transition_image_layout(&self.vulkan_context.logical_device, self.vulkan_context.present_queue, self.command_pool, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+        texture_staging_buffer.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+
+        cubemap
+    }
+
     pub fn destroy_texture(&mut self, texture: &mut VulkanImage) {
         texture.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
     }
 
-    pub fn create_pipeline(&mut self, vertex_shader_location: &str, fragment_shader_location: &str, descriptor_set: Option<&VulkanDescriptor>) -> RenderPipeline {
-        let vertex_shader = Self::create_shader_module(&self.vulkan_context.logical_device, vertex_shader_location);
-        let fragment_shader = Self::create_shader_module(&self.vulkan_context.logical_device, fragment_shader_location);
+    pub fn create_pipeline(&self, vertex_shader_location: &str, fragment_shader_location: &str, descriptor_set: Option<&VulkanDescriptor>, 
+        pipeline_type: PipelineType) -> Result<RenderPipeline, RendererError> {
+        let vertex_shader = Self::create_shader_module(&self.vulkan_context.logical_device, vertex_shader_location)?;
 
-        let (pipeline_layout, graphics_pipeline) = match descriptor_set {
+        let fragment_shader = match Self::create_shader_module(&self.vulkan_context.logical_device, fragment_shader_location) {
+            Ok(fragment_shader) => fragment_shader,
+            Err(error) => {
+                unsafe {
+                    self.vulkan_context.logical_device.destroy_shader_module(vertex_shader, None);
+                }
+
+                return Err(error);
+            }
+        };
+
+        //Shadow pipeline renders into shadow map, everything else into swapchain images
+        let render_pass = match (pipeline_type, &self.shadow_map) {
+            (PipelineType::Shadow, Some(shadow_map)) => shadow_map.render_pass,
+            _ => self.render_pass
+        };
+
+        //Only maze is drawn as wireframe
+        let wireframe = self.wireframe && pipeline_type == PipelineType::Maze;
+
+        let post_process = self.post_image.is_some();
+
+        let pipeline_result = match descriptor_set {
             Some(descriptor_set) => Self::create_graphics_pipeline(&self.vulkan_context.logical_device, vertex_shader, fragment_shader, 
-                self.render_pass, Some(descriptor_set.descriptor_set_layout)),
+                render_pass, Some(descriptor_set.descriptor_set_layout), pipeline_type, wireframe, self.sample_count, post_process),
 
             None => Self::create_graphics_pipeline(&self.vulkan_context.logical_device, vertex_shader, fragment_shader, 
-                self.render_pass, None),
+                render_pass, None, pipeline_type, wireframe, self.sample_count, post_process),
+        };
+
+        let (pipeline_layout, graphics_pipeline) = match pipeline_result {
+            Ok(pipeline) => pipeline,
+            Err(error) => {
+                unsafe {
+                    self.vulkan_context.logical_device.destroy_shader_module(vertex_shader, None);
+                    self.vulkan_context.logical_device.destroy_shader_module(fragment_shader, None);
+                }
+
+                return Err(error);
+            }
         };
 
         let descriptor_sets = match descriptor_set {
@@ -572,12 +1374,35 @@ impl VulkanRenderer {
             None => Vec::new()
         };
 
-        RenderPipeline {
+        Ok(RenderPipeline {
             graphics_pipeline,
             pipeline_layout,
             vertex_shader,
             fragment_shader,
-            descriptor_sets
+            descriptor_sets,
+            push_constant_stages: Self::get_push_constant_stages(pipeline_type)
+        })
+    }
+
+    //Create pipeline again with selected shaders, old pipeline is kept if it fails
+    //Pipeline can't be in use, device needs to be idle
+    fn replace_pipeline(&mut self, mut render_pipeline: RenderPipeline, vertex_shader_location: &str, fragment_shader_location: &str, 
+        pipeline_type: PipelineType) -> RenderPipeline {
+        let descriptors = match pipeline_type {
+            PipelineType::Skybox => self.skybox_descriptors.as_ref(),
+            PipelineType::Post => self.post_descriptors.as_ref(),
+            _ => self.maze_descriptors.as_ref()
+        };
+
+        match self.create_pipeline(vertex_shader_location, fragment_shader_location, descriptors, pipeline_type) {
+            Ok(new_pipeline) => {
+                self.destroy_pipeline(&mut render_pipeline);
+                new_pipeline
+            },
+            Err(error) => {
+                println!("Error: Shader reload failed: {}", error);
+                render_pipeline
+            }
         }
     }
 
@@ -590,8 +1415,8 @@ impl VulkanRenderer {
         }
     }
 
-    pub fn create_descriptor(&mut self, uniform_buffer_size: u64, name: &str, image_views: Vec<ImageView>, sampler: Option<Sampler>) -> VulkanDescriptor {
-        VulkanDescriptor::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, MAX_FRAMES_IN_FLIGHT, uniform_buffer_size, name, sampler, image_views)
+    pub fn create_descriptor(&mut self, uniform_buffer_size: u64, name: &str, images: Option<DescriptorImages>) -> VulkanDescriptor {
+        VulkanDescriptor::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, MAX_FRAMES_IN_FLIGHT, uniform_buffer_size, name, images)
     }
 
     pub fn destroy_descriptor(&mut self, descriptor: &mut VulkanDescriptor) {
@@ -604,7 +1429,16 @@ impl VulkanRenderer {
         }
 
         self.depth_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
-        self.color_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+
+        if let Some(color_image) = self.color_image.as_mut() {
+            color_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+        }
+
+        let post_process = self.post_image.is_some();
+
+        if let Some(mut post_image) = self.post_image.take() {
+            post_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+        }
 
         unsafe {
             for &framebuffer in self.framebuffers.iter() {
@@ -614,55 +1448,159 @@ impl VulkanRenderer {
 
         self.vulkan_context.recreate_swapchain(window_width, window_height);
 
-        let color_image = VulkanImage::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, "Color image", self.vulkan_context.surface_resolution.width, 
-            self.vulkan_context.surface_resolution.height, self.vulkan_context.surface_format.format, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSIENT_ATTACHMENT | ImageUsageFlags::COLOR_ATTACHMENT, 
-            ImageAspectFlags::COLOR, false, SAMPLE_COUNT);
+        let (color_image, depth_image) = Self::create_render_targets(&mut self.vulkan_context, self.sample_count);
+        let post_image = post_process.then(|| Self::create_post_image(&mut self.vulkan_context));
+        let framebuffers = Self::create_framebuffers(&self.vulkan_context, self.render_pass, color_image.as_ref(), &depth_image, post_image.as_ref());
+
+        self.color_image = color_image;
+        self.depth_image = depth_image;
+        self.post_image = post_image;
+        self.framebuffers = framebuffers;
+
+        //Descriptor points to old post process image, layout of new one is the same so pipeline doesn't have to be rebuilt
+        if let Some(mut post_descriptors) = self.post_descriptors.take() {
+            self.destroy_descriptor(&mut post_descriptors);
+
+            let post_descriptors = self.create_post_descriptor();
+
+            if let Some(post_pipeline) = self.post_pipeline.as_mut() {
+                post_pipeline.descriptor_sets = post_descriptors.get_descriptor_sets();
+            }
+
+            self.post_descriptors = Some(post_descriptors);
+        }
+    }
+
+    //Create render pass and framebuffers again with or without post process subpass, nothing can use them
+    fn recreate_render_pass(&mut self, post_process: bool) {
+        unsafe {
+            for &framebuffer in self.framebuffers.iter() {
+                self.vulkan_context.logical_device.destroy_framebuffer(framebuffer, None);
+            }
+
+            self.vulkan_context.logical_device.destroy_render_pass(self.render_pass, None);
+        }
+
+        if let Some(mut post_image) = self.post_image.take() {
+            post_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+        }
+
+        if post_process {
+            self.post_image = Some(Self::create_post_image(&mut self.vulkan_context));
+        }
+
+        self.render_pass = Self::create_render_pass(self.vulkan_context.surface_format.format, &self.vulkan_context.logical_device, &self.depth_image, 
+            self.sample_count, post_process);
+        self.framebuffers = Self::create_framebuffers(&self.vulkan_context, self.render_pass, self.color_image.as_ref(), &self.depth_image, self.post_image.as_ref());
+    }
+
+    //Post shaders don't use uniform data but every descriptor has uniform buffer
+    fn create_post_descriptor(&mut self) -> VulkanDescriptor {
+        let post_image_view = self.post_image.as_ref().unwrap().image_view;
+
+        self.create_descriptor(mem::size_of::<glm::Vec4>() as u64, "Post process", Some(DescriptorImages::Input(post_image_view)))
+    }
+
+    //Pick highest supported sample count that isn't higher than requested one
+    fn select_sample_count(msaa_samples: u32, supported_sample_counts: SampleCountFlags) -> SampleCountFlags {
+        let sample_counts = [(8, SampleCountFlags::TYPE_8), (4, SampleCountFlags::TYPE_4), (2, SampleCountFlags::TYPE_2)];
+
+        let sample_count = sample_counts
+            .iter()
+            .find(|(samples, sample_count)| *samples <= msaa_samples && supported_sample_counts.contains(*sample_count))
+            .map_or((1, SampleCountFlags::TYPE_1), |&value| value);
+
+        if sample_count.0 < msaa_samples {
+            println!("Warning: {}x MSAA isn't supported, using {}x instead.", msaa_samples, sample_count.0);
+        }
+
+        sample_count.1
+    }
+
+    //Create color and depth images used as render targets
+    //Color image is needed only with MSAA, otherwise scene is rendered directly into swapchain image
+    fn create_render_targets(vulkan_context: &mut VulkanContext, sample_count: SampleCountFlags) -> (Option<VulkanImage>, VulkanImage) {
+        let color_image = match sample_count {
+            SampleCountFlags::TYPE_1 => None,
+            _ => Some(VulkanImage::new(&vulkan_context.logical_device, &mut vulkan_context.allocator, "Color image", vulkan_context.surface_resolution.width, 
+                vulkan_context.surface_resolution.height, vulkan_context.surface_format.format, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSIENT_ATTACHMENT | ImageUsageFlags::COLOR_ATTACHMENT, 
+                ImageAspectFlags::COLOR, false, sample_count))
+        };
+
+        let depth_image = VulkanImage::new(&vulkan_context.logical_device, &mut vulkan_context.allocator, "Depth buffer", vulkan_context.surface_resolution.width, 
+            vulkan_context.surface_resolution.height, Format::D32_SFLOAT, ImageTiling::OPTIMAL, ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT, 
+            ImageAspectFlags::DEPTH, false, sample_count);
+
+        (color_image, depth_image)
+    }
 
-        let depth_image = VulkanImage::new(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator, "Depth buffer", self.vulkan_context.surface_resolution.width, 
-            self.vulkan_context.surface_resolution.height, Format::D32_SFLOAT, ImageTiling::OPTIMAL, ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT, 
-            ImageAspectFlags::DEPTH, false, SAMPLE_COUNT);
+    //Scene is rendered (or resolved with MSAA) into this image and read by post process subpass, it has swapchain format
+    fn create_post_image(vulkan_context: &mut VulkanContext) -> VulkanImage {
+        VulkanImage::new(&vulkan_context.logical_device, &mut vulkan_context.allocator, "Post process image", vulkan_context.surface_resolution.width, 
+            vulkan_context.surface_resolution.height, vulkan_context.surface_format.format, ImageTiling::OPTIMAL, ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::INPUT_ATTACHMENT, 
+            ImageAspectFlags::COLOR, false, SampleCountFlags::TYPE_1)
+    }
 
-        let framebuffers: Vec<vk::Framebuffer> = self.vulkan_context
+    //Attachments order has to match render pass
+    fn create_framebuffers(vulkan_context: &VulkanContext, render_pass: RenderPass, color_image: Option<&VulkanImage>, depth_image: &VulkanImage, 
+        post_image: Option<&VulkanImage>) -> Vec<Framebuffer> {
+        vulkan_context
             .swapchain_image_views
             .iter()
             .map(|&swapchain_image_view| {
-                let framebuffer_attachments = [color_image.image_view, depth_image.image_view, swapchain_image_view];
+                let framebuffer_attachments = match (color_image, post_image) {
+                    (Some(color_image), Some(post_image)) => vec![color_image.image_view, depth_image.image_view, post_image.image_view, swapchain_image_view],
+                    (Some(color_image), None) => vec![color_image.image_view, depth_image.image_view, swapchain_image_view],
+                    (None, Some(post_image)) => vec![post_image.image_view, depth_image.image_view, swapchain_image_view],
+                    (None, None) => vec![swapchain_image_view, depth_image.image_view]
+                };
+
                 let frame_buffer_create_info = vk::FramebufferCreateInfo::builder()
-                    .render_pass(self.render_pass)
+                    .render_pass(render_pass)
                     .attachments(&framebuffer_attachments)
-                    .width(self.vulkan_context.surface_resolution.width)
-                    .height(self.vulkan_context.surface_resolution.height)
+                    .width(vulkan_context.surface_resolution.width)
+                    .height(vulkan_context.surface_resolution.height)
                     .layers(1);
 
                 unsafe {
-                    self.vulkan_context.logical_device
+                    vulkan_context.logical_device
                         .create_framebuffer(&frame_buffer_create_info, None)
                         .unwrap()
                 }
             })
-            .collect();
-
-        self.color_image = color_image;
-        self.depth_image = depth_image;
-        self.framebuffers = framebuffers;
+            .collect()
     }
 
-    fn create_render_pass(surface_format: Format, logical_device: &Device, depth_image: &VulkanImage) -> RenderPass {
-        let attachments = &[
+    //With MSAA scene is rendered into multisampled color image and resolved into swapchain image
+    //Without MSAA swapchain image is used as color attachment directly
+    //With post process post process image takes place of swapchain image and second subpass draws it into swapchain image
+    fn create_render_pass(surface_format: Format, logical_device: &Device, depth_image: &VulkanImage, sample_count: SampleCountFlags, post_process: bool) -> RenderPass {
+        let multisampled = sample_count != SampleCountFlags::TYPE_1;
+
+        //Post process image is last used as input of second subpass
+        let scene_final_layout = match post_process {
+            true => ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            false => ImageLayout::PRESENT_SRC_KHR
+        };
+
+        let mut attachments = vec![
             vk::AttachmentDescription {
                 format: surface_format,
-                samples: SAMPLE_COUNT,
+                samples: sample_count,
                 load_op: vk::AttachmentLoadOp::CLEAR,
                 store_op: vk::AttachmentStoreOp::STORE,
                 stencil_load_op: vk::AttachmentLoadOp::DONT_CARE,
                 stencil_store_op: vk::AttachmentStoreOp::DONT_CARE,
                 initial_layout: vk::ImageLayout::UNDEFINED,
-                final_layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                final_layout: match multisampled {
+                    true => vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    false => scene_final_layout
+                },
                 flags: AttachmentDescriptionFlags::empty()
             },
             vk::AttachmentDescription {
                 format: depth_image.format,
-                samples: SAMPLE_COUNT,
+                samples: sample_count,
                 load_op: vk::AttachmentLoadOp::CLEAR,
                 store_op: vk::AttachmentStoreOp::DONT_CARE,
                 stencil_load_op: vk::AttachmentLoadOp::DONT_CARE,
@@ -670,8 +1608,26 @@ impl VulkanRenderer {
                 initial_layout: ImageLayout::UNDEFINED,
                 final_layout: ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
                 flags: AttachmentDescriptionFlags::empty()
-            },
-            AttachmentDescription {
+            }
+        ];
+
+        if multisampled {
+            attachments.push(AttachmentDescription {
+                format: surface_format,
+                samples: SampleCountFlags::TYPE_1,
+                load_op: AttachmentLoadOp::DONT_CARE,
+                store_op: AttachmentStoreOp::STORE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: ImageLayout::UNDEFINED,
+                final_layout: scene_final_layout,
+                flags: AttachmentDescriptionFlags::empty()
+            });
+        }
+
+        //Fullscreen triangle covers whole swapchain image so it doesn't need to be cleared
+        if post_process {
+            attachments.push(AttachmentDescription {
                 format: surface_format,
                 samples: SampleCountFlags::TYPE_1,
                 load_op: AttachmentLoadOp::DONT_CARE,
@@ -681,8 +1637,8 @@ impl VulkanRenderer {
                 initial_layout: ImageLayout::UNDEFINED,
                 final_layout: ImageLayout::PRESENT_SRC_KHR,
                 flags: AttachmentDescriptionFlags::empty()
-            }
-        ];
+            });
+        }
 
         let color_attachment_ref = vk::AttachmentReference {
             attachment: 0,
@@ -699,13 +1655,29 @@ impl VulkanRenderer {
             layout: ImageLayout::COLOR_ATTACHMENT_OPTIMAL
         };
 
-        let subpass = vk::SubpassDescription::builder()
+        //Second subpass reads resolved scene when it's multisampled and writes into swapchain image which is always last
+        let post_input_ref = vk::AttachmentReference {
+            attachment: if multisampled { 2 } else { 0 },
+            layout: ImageLayout::SHADER_READ_ONLY_OPTIMAL
+        };
+
+        let post_color_ref = vk::AttachmentReference {
+            attachment: attachments.len() as u32 - 1,
+            layout: ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+        };
+
+        let mut subpass = vk::SubpassDescription::builder()
             .color_attachments(std::slice::from_ref(&color_attachment_ref))
             .depth_stencil_attachment(&depth_attachment_ref)
-            .resolve_attachments(std::slice::from_ref(&color_attachment_resolve_ref))
             .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS);
 
-        let dependencies = [vk::SubpassDependency {
+        if multisampled {
+            subpass = subpass.resolve_attachments(std::slice::from_ref(&color_attachment_resolve_ref));
+        }
+
+        let mut subpasses = vec![subpass.build()];
+
+        let mut dependencies = vec![vk::SubpassDependency {
             src_subpass: vk::SUBPASS_EXTERNAL,
             src_stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
             dst_stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
@@ -713,9 +1685,38 @@ impl VulkanRenderer {
             ..Default::default()
         }];
 
+        if post_process {
+            subpasses.push(vk::SubpassDescription::builder()
+                .input_attachments(std::slice::from_ref(&post_input_ref))
+                .color_attachments(std::slice::from_ref(&post_color_ref))
+                .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+                .build());
+
+            //Swapchain image is first used by second subpass so it has to wait for it to be acquired too
+            dependencies.push(vk::SubpassDependency {
+                src_subpass: vk::SUBPASS_EXTERNAL,
+                dst_subpass: 1,
+                src_stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                dst_access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                ..Default::default()
+            });
+
+            //Scene has to be written before it's read, every fragment reads only its own pixel
+            dependencies.push(vk::SubpassDependency {
+                src_subpass: 0,
+                dst_subpass: 1,
+                src_stage_mask: vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                dst_stage_mask: vk::PipelineStageFlags::FRAGMENT_SHADER,
+                src_access_mask: vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                dst_access_mask: vk::AccessFlags::INPUT_ATTACHMENT_READ,
+                dependency_flags: vk::DependencyFlags::BY_REGION
+            });
+        }
+
         let render_pass_create_info = vk::RenderPassCreateInfo::builder()
-            .attachments(attachments)
-            .subpasses(std::slice::from_ref(&subpass))
+            .attachments(&attachments)
+            .subpasses(&subpasses)
             .dependencies(&dependencies);
 
         let render_pass = unsafe {
@@ -725,28 +1726,39 @@ impl VulkanRenderer {
         render_pass
     }
 
-    fn create_shader_module(logical_device: &Device, filename: &str) -> ShaderModule {
-        let mut shader_file = File::open(&filename).expect("Failed to shader file.");
-        let spv_code = read_spv(&mut shader_file).expect("Reading shader file failed.");
+    fn create_shader_module(logical_device: &Device, filename: &str) -> Result<ShaderModule, RendererError> {
+        let mut shader_file = File::open(filename).map_err(|error| RendererError::ShaderLoad(format!("{}: {}", filename, error)))?;
+        let spv_code = read_spv(&mut shader_file).map_err(|error| RendererError::ShaderLoad(format!("{}: {}", filename, error)))?;
 
         let shader_module_info = ShaderModuleCreateInfo::builder()
             .code(&spv_code);
 
-        let shader_module = unsafe {
-            logical_device.create_shader_module(&shader_module_info, None).expect("Creating shader module failed.")
-        };
+        unsafe {
+            logical_device.create_shader_module(&shader_module_info, None).map_err(|error| RendererError::ShaderLoad(format!("{}: {}", filename, error)))
+        }
+    }
 
-        shader_module
+    fn get_push_constant_stages(pipeline_type: PipelineType) -> ShaderStageFlags {
+        match pipeline_type {
+            PipelineType::Maze | PipelineType::Shadow | PipelineType::Skybox | PipelineType::Post => ShaderStageFlags::empty(),
+            PipelineType::Overlay => ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT
+        }
     }
 
-    fn create_graphics_pipeline(logical_device: &Device, vertex_shader: ShaderModule, fragment_shader: ShaderModule, render_pass: RenderPass, descriptor_set_layout: Option<DescriptorSetLayout>) -> (PipelineLayout, Pipeline) {
-        let push_constant_ranges = &[
-            PushConstantRange::builder()
-            .offset(0)
-            .size(mem::size_of::<PushConstant>() as u32)
-            .stage_flags(ShaderStageFlags::VERTEX)
-            .build()
-        ];
+    fn create_graphics_pipeline(logical_device: &Device, vertex_shader: ShaderModule, fragment_shader: ShaderModule, render_pass: RenderPass, 
+        descriptor_set_layout: Option<DescriptorSetLayout>, pipeline_type: PipelineType, wireframe: bool, scene_sample_count: SampleCountFlags, 
+        post_process: bool) -> Result<(PipelineLayout, Pipeline), RendererError> {
+        //Maze data is uploaded with instances, only overlay uses push constants
+        let push_constant_ranges = match pipeline_type {
+            PipelineType::Maze | PipelineType::Shadow | PipelineType::Skybox | PipelineType::Post => Vec::new(),
+            PipelineType::Overlay => vec![
+                PushConstantRange::builder()
+                .offset(0)
+                .size(mem::size_of::<OverlayPushConstant>() as u32)
+                .stage_flags(Self::get_push_constant_stages(pipeline_type))
+                .build()
+            ]
+        };
         let descriptor_set_layout = match descriptor_set_layout {
             Some(descriptor_set_layout) => descriptor_set_layout,
             None => DescriptorSetLayout::null()
@@ -755,23 +1767,85 @@ impl VulkanRenderer {
         let set_layouts = &[descriptor_set_layout];
 
         let pipeline_layout_info = PipelineLayoutCreateInfo::builder()
-            .push_constant_ranges(push_constant_ranges)
+            .push_constant_ranges(&push_constant_ranges)
             .set_layouts(set_layouts);
 
         let pipeline_layout = unsafe {
-            logical_device.create_pipeline_layout(&pipeline_layout_info, None).expect("Pipeline layout creation failed.")
+            logical_device.create_pipeline_layout(&pipeline_layout_info, None)
+                .map_err(|error| RendererError::PipelineCreation(format!("Pipeline layout: {}", error)))?
         };
 
         let mut vulkan_pipeline = VulkanPipeline::new(PrimitiveTopology::TRIANGLE_LIST);
         vulkan_pipeline.add_shader_stage(ShaderStageFlags::VERTEX, vertex_shader);
         vulkan_pipeline.add_shader_stage(ShaderStageFlags::FRAGMENT, fragment_shader);
 
-        vulkan_pipeline.add_vertex_input_bindings(&mut VertexInput::get_binding_descriptions());
-        vulkan_pipeline.add_vertex_input_attributes(&mut VertexInput::get_attribute_descriptions());
+        //Fullscreen triangle is generated in vertex shader so post pipeline has no vertex input
+        if pipeline_type != PipelineType::Post {
+            vulkan_pipeline.add_vertex_input_bindings(&mut VertexInput::get_binding_descriptions());
+            vulkan_pipeline.add_vertex_input_attributes(&mut VertexInput::get_attribute_descriptions());
+        }
+
+        if pipeline_type == PipelineType::Maze || pipeline_type == PipelineType::Shadow {
+            vulkan_pipeline.add_vertex_input_bindings(&mut VertexInput::get_instance_binding_descriptions());
+            vulkan_pipeline.add_vertex_input_attributes(&mut VertexInput::get_instance_attribute_descriptions());
+        }
+
+        vulkan_pipeline.set_wireframe(wireframe);
+
+        //Overlay is drawn on top of everything with transparency
+        //Overlay quads aren't flipped like the scene so they are drawn without culling, same as on OpenGL
+        if pipeline_type == PipelineType::Overlay {
+            vulkan_pipeline.set_depth_test(false);
+            vulkan_pipeline.set_blending(true);
+            vulkan_pipeline.set_culling(false);
+        }
+
+        //Skybox is at far plane so it passes depth test only where depth buffer is still cleared, it doesn't write depth
+        if pipeline_type == PipelineType::Skybox {
+            vulkan_pipeline.set_depth_write(false);
+            vulkan_pipeline.set_depth_compare_op(CompareOp::LESS_OR_EQUAL);
+        }
+
+        //Post process subpass has no depth attachment and covers whole screen with one triangle
+        if pipeline_type == PipelineType::Post {
+            vulkan_pipeline.set_depth_test(false);
+            vulkan_pipeline.set_culling(false);
+        }
+
+        //Shadow map has only depth attachment and isn't multisampled
+        //With post process overlays are drawn on top of it in second subpass which isn't multisampled either
+        let sample_count = match pipeline_type {
+            PipelineType::Shadow => {
+                //Walls are single sided, light has to be blocked by walls seen from behind too
+                vulkan_pipeline.set_culling(false);
+                vulkan_pipeline.set_color_output(false);
+                vulkan_pipeline.set_depth_bias(true);
+
+                SampleCountFlags::TYPE_1
+            },
+            PipelineType::Post => {
+                vulkan_pipeline.set_subpass(1);
+
+                SampleCountFlags::TYPE_1
+            },
+            PipelineType::Overlay if post_process => {
+                vulkan_pipeline.set_subpass(1);
+
+                SampleCountFlags::TYPE_1
+            },
+            _ => scene_sample_count
+        };
         
-        let graphics_pipeline = vulkan_pipeline.build_pipeline(&logical_device, pipeline_layout, render_pass, SAMPLE_COUNT);
+        match vulkan_pipeline.build_pipeline(logical_device, pipeline_layout, render_pass, sample_count) {
+            Ok(graphics_pipeline) => Ok((pipeline_layout, graphics_pipeline)),
+            Err(error) => {
+                unsafe {
+                    logical_device.destroy_pipeline_layout(pipeline_layout, None);
+                }
 
-        (pipeline_layout, graphics_pipeline)
+                Err(RendererError::PipelineCreation(format!("Graphics pipeline: {}", error)))
+            }
+        }
     }
 
     fn create_commands(logical_device: &Device, queue_family_index: u32, command_buffer_count: u32) -> (CommandPool, Vec<CommandBuffer>) {
@@ -801,9 +1875,16 @@ impl Drop for VulkanRenderer {
         unsafe {
             self.vulkan_context.logical_device.device_wait_idle().unwrap();
 
-            self.color_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+            if let Some(color_image) = self.color_image.as_mut() {
+                color_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+            }
+
             self.depth_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
 
+            if let Some(post_image) = self.post_image.as_mut() {
+                post_image.free(&self.vulkan_context.logical_device, &mut self.vulkan_context.allocator);
+            }
+
             for n in self.frame_data.iter_mut() {
                 self.vulkan_context.logical_device.destroy_fence(n.in_flight_fence, None);
                 self.vulkan_context.logical_device.destroy_semaphore(n.render_finished_semaphore, None);
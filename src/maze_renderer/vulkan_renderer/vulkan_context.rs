@@ -1,17 +1,41 @@
 //Responsible for creating instance, debug utils messenger, creating surface, picking physical device,
 //creating logical device and creating (or recreating) swapchain
 
-use std::{ffi::CStr, mem::ManuallyDrop};
+use std::{error::Error, ffi::{c_char, c_void, CStr, CString}, fmt, mem::ManuallyDrop};
 
-use ash::{extensions::{ext::DebugUtils, khr::{Surface, Swapchain}}, vk::{self, ColorSpaceKHR, Extent2D, Format, FormatProperties, Image, ImageView, KhrPortabilitySubsetFn, KhrGetPhysicalDeviceProperties2Fn, 
-    KhrPortabilityEnumerationFn, PhysicalDevice, PhysicalDeviceProperties, Queue, SurfaceFormatKHR, SurfaceKHR, SwapchainKHR}, Device, Entry, Instance};
+use ash::{extensions::{ext::DebugUtils, khr::{Surface, Swapchain}}, vk::{self, ColorSpaceKHR, DebugUtilsMessengerEXT, Extent2D, Format, FormatProperties, Image, ImageView, 
+    KhrGetPhysicalDeviceProperties2Fn, KhrPortabilityEnumerationFn, KhrPortabilitySubsetFn, PhysicalDevice, PhysicalDeviceProperties, PhysicalDeviceType, PresentModeKHR, Queue, SurfaceFormatKHR, SurfaceKHR, SwapchainKHR}, Device, Entry, Instance};
 use gpu_allocator::vulkan::{Allocator, AllocatorCreateDesc};
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 use winit::window::Window;
 
+const VALIDATION_LAYER_NAME: &[u8] = b"VK_LAYER_KHRONOS_validation\0";
+
+//NVIDIA packs driver version differently than Vulkan versions
+const NVIDIA_VENDOR_ID: u32 = 0x10DE;
+
+//Physical device as listed on startup and by --list-gpus, index is used by -gpu
+pub struct PhysicalDeviceInfo {
+    pub index: usize,
+    pub name: String,
+    pub device_type: PhysicalDeviceType,
+    pub driver_version: String,
+    pub api_version: String
+}
+
+impl fmt::Display for PhysicalDeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {} ({}), driver {}, Vulkan {}", self.index, self.name, VulkanContext::device_type_name(self.device_type), 
+            self.driver_version, self.api_version)
+    }
+}
+
 pub struct VulkanContext {
     pub instance: Instance,
+    debug_messenger: Option<(DebugUtils, DebugUtilsMessengerEXT)>,
     vsync_enabled: bool,
+    triple_buffering: bool,
+    verbose: bool, //Swapchain configuration is printed again every time swapchain is recreated
     surface_loader: Surface,
     surface_khr: SurfaceKHR,
     pub physical_device: PhysicalDevice,
@@ -20,34 +44,88 @@ pub struct VulkanContext {
     pub present_queue: Queue,
     pub surface_format: SurfaceFormatKHR,
     pub surface_resolution: Extent2D,
+    pub present_mode: PresentModeKHR,
     pub swapchain_loader: Swapchain,
     pub swapchain_khr: SwapchainKHR,
-    _swapchain_images: Vec<Image>,
+    pub swapchain_images: Vec<Image>,
     pub swapchain_image_views: Vec<ImageView>,
+    pub swapchain_transfer_supported: bool, //Swapchain images can be copied (needed for screenshots)
+    pub wireframe_supported: bool, //Device supports line polygon mode (fillModeNonSolid feature)
     pub allocator: ManuallyDrop<Allocator>,
 }
 
 impl VulkanContext {
-    pub fn new(window: &Window, entry: &Entry, vsync_enabled: bool) -> Self {
-        let instance = Self::create_instance(window, entry);
+    pub fn new(window: &Window, entry: &Entry, app_name: &str, vsync_enabled: bool, triple_buffering: bool, gpu_index: Option<usize>, 
+        debug_enabled: bool, verbose: bool) -> Result<Self, Box<dyn Error>> {
+        //Validation layer is used only if it's installed
+        let validation_enabled = debug_enabled && Self::validation_layer_available(entry);
+
+        let surface_extensions = ash_window::enumerate_required_extensions(window.raw_display_handle())?;
+        let instance = Self::create_instance(entry, app_name, surface_extensions, validation_enabled)?;
+
+        let debug_messenger = match validation_enabled {
+            true => Some(Self::create_debug_messenger(entry, &instance)),
+            false => None
+        };
+
         let surface_loader = Surface::new(entry, &instance);
 
-        let surface_khr = unsafe {
-            ash_window::create_surface(&entry, &instance, window.raw_display_handle(), window.raw_window_handle(), None).expect("SurfaceKHR creation failed.")
+        //Context doesn't exist until every step succeeds so its Drop can't clean up, objects created before failed step are destroyed here
+        let surface_khr = match unsafe { ash_window::create_surface(&entry, &instance, window.raw_display_handle(), window.raw_window_handle(), None) } {
+            Ok(surface_khr) => surface_khr,
+            Err(error) => {
+                Self::destroy_instance(&instance, debug_messenger);
+                return Err(format!("SurfaceKHR creation failed: {}", error).into());
+            }
         };
 
-        let (physical_device, queue_family_index) = Self::pick_physical_device(&instance, &surface_loader, surface_khr);
+        let destroy_surface = |debug_messenger| unsafe {
+            surface_loader.destroy_surface(surface_khr, None);
+            Self::destroy_instance(&instance, debug_messenger);
+        };
 
-        let logical_device = Self::create_logical_device(&instance, physical_device, queue_family_index);
+        let (physical_device, queue_family_index) = match Self::pick_physical_device(&instance, &surface_loader, surface_khr, gpu_index) {
+            Ok(physical_device) => physical_device,
+            Err(error) => {
+                destroy_surface(debug_messenger);
+                return Err(error);
+            }
+        };
+
+        let logical_device = match Self::create_logical_device(&instance, physical_device, queue_family_index) {
+            Ok(logical_device) => logical_device,
+            Err(error) => {
+                destroy_surface(debug_messenger);
+                return Err(error);
+            }
+        };
+
+        let wireframe_supported = unsafe {
+            instance.get_physical_device_features(physical_device).fill_mode_non_solid == vk::TRUE
+        };
 
         let present_queue = unsafe {
             logical_device.get_device_queue(queue_family_index, 0)
         };
 
-        let (surface_format, surface_resolution, swapchain_loader, swapchain_khr) = Self::create_swapchain(&instance, &logical_device, physical_device, 
-            &surface_loader, surface_khr, window.inner_size().width, window.inner_size().height, vsync_enabled);
+        let swapchain = Self::create_swapchain(&instance, &logical_device, physical_device, &surface_loader, surface_khr, window.inner_size().width, 
+            window.inner_size().height, vsync_enabled, triple_buffering);
+
+        let (surface_format, surface_resolution, present_mode, swapchain_loader, swapchain_khr, swapchain_transfer_supported) = match swapchain {
+            Ok(swapchain) => swapchain,
+            Err(error) => {
+                unsafe {
+                    logical_device.destroy_device(None);
+                }
+
+                destroy_surface(debug_messenger);
+                return Err(error);
+            }
+        };
+
+        let (swapchain_images, swapchain_image_views) = Self::get_swapchain_image_imageviews(&swapchain_loader, swapchain_khr, &logical_device, surface_format);
 
-        let (_swapchain_images, swapchain_image_views) = Self::get_swapchain_image_imageviews(&swapchain_loader, swapchain_khr, &logical_device, surface_format);
+        Self::print_swapchain_info(surface_format, surface_resolution, present_mode, swapchain_images.len());
 
         let allocator = Allocator::new(&AllocatorCreateDesc {
             instance: instance.clone(),
@@ -56,13 +134,33 @@ impl VulkanContext {
             debug_settings: Default::default(),
             buffer_device_address: false,
             allocation_sizes: Default::default()
-        }).expect("Allocator creation failed");
+        });
+
+        let allocator = match allocator {
+            Ok(allocator) => allocator,
+            Err(error) => {
+                unsafe {
+                    for &image_view in swapchain_image_views.iter() {
+                        logical_device.destroy_image_view(image_view, None);
+                    }
+
+                    swapchain_loader.destroy_swapchain(swapchain_khr, None);
+                    logical_device.destroy_device(None);
+                }
+
+                destroy_surface(debug_messenger);
+                return Err(format!("Allocator creation failed: {}", error).into());
+            }
+        };
 
         let allocator = ManuallyDrop::new(allocator);
         
-        Self {
+        Ok(Self {
             instance,
+            debug_messenger,
             vsync_enabled,
+            triple_buffering,
+            verbose,
             surface_loader,
             surface_khr,
             physical_device,
@@ -71,12 +169,15 @@ impl VulkanContext {
             present_queue,
             surface_format,
             surface_resolution,
+            present_mode,
             swapchain_loader,
             swapchain_khr,
-            _swapchain_images,
+            swapchain_images,
             swapchain_image_views,
+            swapchain_transfer_supported,
+            wireframe_supported,
             allocator
-        }
+        })
     }
 
     pub fn get_physical_device_properties(&self) -> PhysicalDeviceProperties {
@@ -94,43 +195,73 @@ impl VulkanContext {
     pub fn recreate_swapchain(&mut self, window_width: u32, window_height: u32) {
         self.destroy_swapchain();
 
-        let (surface_format, surface_resolution, swapchain_loader, swapchain_khr) = Self::create_swapchain(&self.instance, &self.logical_device, self.physical_device, 
-            &self.surface_loader, self.surface_khr, window_width, window_height, self.vsync_enabled);
+        let (surface_format, surface_resolution, present_mode, swapchain_loader, swapchain_khr, swapchain_transfer_supported) = Self::create_swapchain(&self.instance, 
+            &self.logical_device, self.physical_device, &self.surface_loader, self.surface_khr, window_width, window_height, self.vsync_enabled, 
+            self.triple_buffering)
+            .expect("Swapchain recreation failed.");
+
+        let (swapchain_images, swapchain_image_views) = Self::get_swapchain_image_imageviews(&swapchain_loader, swapchain_khr, &self.logical_device, surface_format);
 
-        let (_swapchain_images, swapchain_image_views) = Self::get_swapchain_image_imageviews(&swapchain_loader, swapchain_khr, &self.logical_device, surface_format);
+        if self.verbose {
+            Self::print_swapchain_info(surface_format, surface_resolution, present_mode, swapchain_images.len());
+        }
         
         self.surface_format = surface_format;
         self.surface_resolution = surface_resolution;
+        self.present_mode = present_mode;
         self.swapchain_loader = swapchain_loader;
         self.swapchain_khr = swapchain_khr;
+        self.swapchain_images = swapchain_images;
         self.swapchain_image_views = swapchain_image_views;
+        self.swapchain_transfer_supported = swapchain_transfer_supported;
+    }
+
+    //Every Vulkan device with its properties, instance is created without surface so it works without display
+    pub fn list_devices(entry: &Entry, app_name: &str) -> Result<Vec<PhysicalDeviceInfo>, Box<dyn Error>> {
+        let instance = Self::create_instance(entry, app_name, &[], false)?;
+        let devices = Self::enumerate_devices(&instance);
+
+        unsafe {
+            instance.destroy_instance(None);
+        }
+
+        Ok(devices?.into_iter().map(|(_, device_info)| device_info).collect())
     }
 
-    fn create_instance(window: &Window , entry: &Entry) -> Instance {
-        let app_name = unsafe {
+    fn create_instance(entry: &Entry, app_name: &str, required_extensions: &[*const c_char], validation_enabled: bool) -> Result<Instance, Box<dyn Error>> {
+        //Null characters can't be inside C string so they are removed
+        let app_name = CString::new(app_name.replace('\0', ""))?;
+
+        let engine_name = unsafe {
             CStr::from_bytes_with_nul_unchecked(b"maze_renderer_vk\0")
         };
 
         let app_info = vk::ApplicationInfo::builder()
-            .application_name(app_name)
+            .application_name(&app_name)
             .application_version(0)
-            .engine_name(app_name)
+            .engine_name(engine_name)
             .engine_version(0)
             .api_version(vk::make_api_version(0, 1, 0, 0));
 
-        let mut extension_names = ash_window::enumerate_required_extensions(window.raw_display_handle())
-            .unwrap()
-            .to_vec();
+        let mut extension_names = required_extensions.to_vec();
+
+        //MoltenVK is portability driver, loader lists it only when instance enables portability enumeration
+        //Older loaders don't know the extension and list MoltenVK without it
+        let portability_enabled = cfg!(target_os = "macos") && Self::instance_extension_available(entry, KhrPortabilityEnumerationFn::name());
 
-        #[cfg(any(target_os = "macos"))]
-        {
+        if portability_enabled {
             extension_names.push(KhrPortabilityEnumerationFn::name().as_ptr());
             extension_names.push(KhrGetPhysicalDeviceProperties2Fn::name().as_ptr());
         }
 
-        extension_names.push(DebugUtils::name().as_ptr());
+        let mut layer_names = Vec::new();
+
+        if validation_enabled {
+            extension_names.push(DebugUtils::name().as_ptr());
+            layer_names.push(VALIDATION_LAYER_NAME.as_ptr() as *const c_char);
+        }
 
-        let instance_flags = if cfg!(any(target_os = "macos")) {
+        let instance_flags = if portability_enabled {
             vk::InstanceCreateFlags::ENUMERATE_PORTABILITY_KHR
         } else {
             vk::InstanceCreateFlags::default()
@@ -139,62 +270,192 @@ impl VulkanContext {
         let create_info = vk::InstanceCreateInfo::builder()
             .application_info(&app_info)
             .enabled_extension_names(&extension_names)
+            .enabled_layer_names(&layer_names)
             .flags(instance_flags);
 
         let instance = unsafe {
-            entry.create_instance(&create_info, None).expect("Instance creation failed.")
+            entry.create_instance(&create_info, None).map_err(|error| format!("Instance creation failed: {}", error))?
         };
 
-        instance
+        Ok(instance)
     }    
 
-    fn pick_physical_device(instance: &Instance, surface_loader: &Surface, surface_khr: SurfaceKHR) -> (PhysicalDevice, u32) {
-        let devices = unsafe {
-            instance.enumerate_physical_devices().expect("Device enumeration failed.")
+    fn instance_extension_available(entry: &Entry, extension_name: &CStr) -> bool {
+        entry.enumerate_instance_extension_properties(None)
+            .unwrap_or_default()
+            .iter()
+            .any(|extension| unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) } == extension_name)
+    }
+
+    fn device_extension_available(instance: &Instance, physical_device: PhysicalDevice, extension_name: &CStr) -> bool {
+        unsafe { instance.enumerate_device_extension_properties(physical_device) }
+            .unwrap_or_default()
+            .iter()
+            .any(|extension| unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) } == extension_name)
+    }
+
+    fn validation_layer_available(entry: &Entry) -> bool {
+        let layers = entry.enumerate_instance_layer_properties().unwrap_or_default();
+
+        let validation_layer_name = unsafe {
+            CStr::from_bytes_with_nul_unchecked(VALIDATION_LAYER_NAME)
         };
 
-        let (selected_device, queue_index) = unsafe {
-            devices
-                .iter()
-                .find_map(|device| {
-                    instance
-                        .get_physical_device_queue_family_properties(*device)
-                        .iter()
-                        .enumerate()
-                        .find_map(|(index, info)| {
-                            let supports_graphic_and_surface =
-                                info.queue_flags.contains(vk::QueueFlags::GRAPHICS)
-                                    && surface_loader
-                                        .get_physical_device_surface_support(
-                                            *device,
-                                            index as u32,
-                                            surface_khr,
-                                        )
-                                        .unwrap();
-                            if supports_graphic_and_surface {
-                                Some((*device, index))
-                            } else {
-                                None
-                            }
-                        })
-                })
-                .expect("Couldn't find suitable device.")
+        let available = layers.iter().any(|layer| unsafe {
+            CStr::from_ptr(layer.layer_name.as_ptr()) == validation_layer_name
+        });
+
+        if !available {
+            println!("Warning: Validation layer not found, make sure Vulkan SDK is installed.");
+        }
+
+        available
+    }
+
+    fn create_debug_messenger(entry: &Entry, instance: &Instance) -> (DebugUtils, DebugUtilsMessengerEXT) {
+        let debug_utils = DebugUtils::new(entry, instance);
+
+        let messenger_info = vk::DebugUtilsMessengerCreateInfoEXT::builder()
+            .message_severity(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING | 
+                vk::DebugUtilsMessageSeverityFlagsEXT::INFO)
+            .message_type(vk::DebugUtilsMessageTypeFlagsEXT::GENERAL | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION | 
+                vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE)
+            .pfn_user_callback(Some(Self::debug_callback));
+
+        let debug_messenger = unsafe {
+            debug_utils.create_debug_utils_messenger(&messenger_info, None).expect("Debug messenger creation failed.")
         };
 
-        (selected_device, queue_index as u32)
+        println!("Vulkan validation enabled.");
+
+        (debug_utils, debug_messenger)
     }
 
-    fn create_logical_device(instance: &Instance, physical_device: PhysicalDevice, queue_index: u32) -> Device {
-        let device_extension_names_raw = [
-            Swapchain::name().as_ptr(),
-            #[cfg(any(target_os = "macos"))]
-            KhrPortabilitySubsetFn::name().as_ptr(),
-        ];
+    //Print validation messages to stderr
+    unsafe extern "system" fn debug_callback(message_severity: vk::DebugUtilsMessageSeverityFlagsEXT, message_type: vk::DebugUtilsMessageTypeFlagsEXT, 
+        callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT, _user_data: *mut c_void) -> vk::Bool32 {
+        let message = if callback_data.is_null() || (*callback_data).p_message.is_null() {
+            std::borrow::Cow::from("")
+        } else {
+            CStr::from_ptr((*callback_data).p_message).to_string_lossy()
+        };
+
+        eprintln!("[Vulkan {:?} {:?}] {}", message_severity, message_type, message);
+
+        vk::FALSE
+    }
+
+    //Pick device that supports graphics and presenting to surface
+    //Device selected by user is used if it's suitable, otherwise discrete GPU is preferred over other types
+    fn enumerate_devices(instance: &Instance) -> Result<Vec<(PhysicalDevice, PhysicalDeviceInfo)>, Box<dyn Error>> {
+        let devices = unsafe {
+            instance.enumerate_physical_devices().map_err(|error| format!("Device enumeration failed: {}", error))?
+        };
+
+        let device_infos = devices.into_iter().enumerate().map(|(index, device)| {
+            let properties = unsafe {
+                instance.get_physical_device_properties(device)
+            };
+
+            let name = unsafe {
+                CStr::from_ptr(properties.device_name.as_ptr()).to_string_lossy().into_owned()
+            };
+
+            let driver_version = match properties.vendor_id {
+                NVIDIA_VENDOR_ID => format!("{}.{}.{}.{}", properties.driver_version >> 22, (properties.driver_version >> 14) & 0xFF, 
+                    (properties.driver_version >> 6) & 0xFF, properties.driver_version & 0x3F),
+                _ => Self::format_version(properties.driver_version)
+            };
+
+            (device, PhysicalDeviceInfo {
+                index,
+                name,
+                device_type: properties.device_type,
+                driver_version,
+                api_version: Self::format_version(properties.api_version)
+            })
+        }).collect();
+
+        Ok(device_infos)
+    }
+
+    fn format_version(version: u32) -> String {
+        format!("{}.{}.{}", vk::api_version_major(version), vk::api_version_minor(version), vk::api_version_patch(version))
+    }
+
+    fn pick_physical_device(instance: &Instance, surface_loader: &Surface, surface_khr: SurfaceKHR, gpu_index: Option<usize>) -> Result<(PhysicalDevice, u32), Box<dyn Error>> {
+        //Suitable devices with their index on devices list and queue family index
+        let mut candidates: Vec<(usize, PhysicalDevice, u32, PhysicalDeviceType)> = Vec::new();
+
+        println!("Available devices:");
+
+        for (device, device_info) in Self::enumerate_devices(instance)? {
+            let queue_index = unsafe {
+                instance
+                    .get_physical_device_queue_family_properties(device)
+                    .iter()
+                    .enumerate()
+                    .position(|(index, info)| {
+                        info.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+                            && surface_loader
+                                .get_physical_device_surface_support(device, index as u32, surface_khr)
+                                .unwrap_or(false)
+                    })
+            };
+
+            match queue_index {
+                Some(queue_index) => {
+                    println!("[{}] {} ({})", device_info.index, device_info.name, Self::device_type_name(device_info.device_type));
+                    candidates.push((device_info.index, device, queue_index as u32, device_info.device_type));
+                },
+                None => println!("[{}] {} ({}, not suitable)", device_info.index, device_info.name, Self::device_type_name(device_info.device_type))
+            }
+        }
+
+        if let Some(gpu_index) = gpu_index {
+            match candidates.iter().find(|candidate| candidate.0 == gpu_index) {
+                Some(&(_, device, queue_index, _)) => return Ok((device, queue_index)),
+                None => println!("Warning: Device {} isn't available or suitable, selecting device automatically.", gpu_index)
+            }
+        }
+
+        let &(_, selected_device, queue_index, _) = candidates
+            .iter()
+            .find(|candidate| candidate.3 == PhysicalDeviceType::DISCRETE_GPU)
+            .or(candidates.first())
+            .ok_or("Couldn't find suitable device.")?;
+
+        Ok((selected_device, queue_index))
+    }
+
+    fn device_type_name(device_type: PhysicalDeviceType) -> &'static str {
+        match device_type {
+            PhysicalDeviceType::DISCRETE_GPU => "Discrete GPU",
+            PhysicalDeviceType::INTEGRATED_GPU => "Integrated GPU",
+            PhysicalDeviceType::VIRTUAL_GPU => "Virtual GPU",
+            PhysicalDeviceType::CPU => "CPU",
+            _ => "Other"
+        }
+    }
+
+    fn create_logical_device(instance: &Instance, physical_device: PhysicalDevice, queue_index: u32) -> Result<Device, Box<dyn Error>> {
+        let mut device_extension_names_raw = vec![Swapchain::name().as_ptr()];
+
+        //Portability subset has to be enabled on devices that support it (MoltenVK)
+        if Self::device_extension_available(instance, physical_device, KhrPortabilitySubsetFn::name()) {
+            device_extension_names_raw.push(KhrPortabilitySubsetFn::name().as_ptr());
+        }
+
+        //Wireframe rendering is optional, feature is enabled only when device supports it
+        let supported_features = unsafe {
+            instance.get_physical_device_features(physical_device)
+        };
 
         let features = vk::PhysicalDeviceFeatures {
             shader_clip_distance: 1,
             sampler_anisotropy: vk::TRUE,
             sample_rate_shading: vk::TRUE,
+            fill_mode_non_solid: supported_features.fill_mode_non_solid,
             ..Default::default()
         };
         
@@ -210,17 +471,17 @@ impl VulkanContext {
             .enabled_features(&features);
 
         let device = unsafe {
-            instance.create_device(physical_device, &device_create_info, None).expect("Logical device creation failed.")
+            instance.create_device(physical_device, &device_create_info, None).map_err(|error| format!("Logical device creation failed: {}", error))?
         };
 
-        device
+        Ok(device)
     }
 
     fn create_swapchain(instance: &Instance, logical_device: &Device, physical_device: PhysicalDevice, surface_loader: &Surface, 
-        surface_khr: SurfaceKHR, window_width: u32, window_height: u32, vsync_enabled: bool) -> (SurfaceFormatKHR, Extent2D, Swapchain, SwapchainKHR) {
+        surface_khr: SurfaceKHR, window_width: u32, window_height: u32, vsync_enabled: bool, triple_buffering: bool) -> Result<(SurfaceFormatKHR, Extent2D, PresentModeKHR, Swapchain, SwapchainKHR, bool), Box<dyn Error>> {
 
         let surface_format =  unsafe {
-            let supported_surface_formats = surface_loader.get_physical_device_surface_formats(physical_device, surface_khr).unwrap();
+            let supported_surface_formats = surface_loader.get_physical_device_surface_formats(physical_device, surface_khr)?;
 
             supported_surface_formats
                 .iter()
@@ -233,11 +494,32 @@ impl VulkanContext {
         };
 
         let surface_capabilities = unsafe {
-            surface_loader.get_physical_device_surface_capabilities(physical_device, surface_khr).unwrap()
+            surface_loader.get_physical_device_surface_capabilities(physical_device, surface_khr)?
+        };
+
+        let present_modes = unsafe {
+            surface_loader.get_physical_device_surface_present_modes(physical_device, surface_khr)?
+        };
+
+        let mailbox_supported = present_modes.contains(&vk::PresentModeKHR::MAILBOX);
+
+        //Mailbox is vertically synchronized too so with V-Sync it's used only when triple buffering is requested
+        let present_mode = match (vsync_enabled, triple_buffering, mailbox_supported) {
+            (false, _, true) | (true, true, true) => vk::PresentModeKHR::MAILBOX,
+            _ => vk::PresentModeKHR::FIFO
         };
 
+        if vsync_enabled && triple_buffering && !mailbox_supported {
+            println!("Warning: Mailbox present mode not supported, falling back to FIFO");
+        }
+
         let mut desired_image_count = surface_capabilities.min_image_count + 1;
 
+        //Mailbox needs image being presented, image waiting in queue and image being rendered to
+        if present_mode == vk::PresentModeKHR::MAILBOX {
+            desired_image_count = desired_image_count.max(3);
+        }
+
         if surface_capabilities.max_image_count > 0 && desired_image_count > surface_capabilities.max_image_count {
             desired_image_count = surface_capabilities.max_image_count;
         }
@@ -256,19 +538,13 @@ impl VulkanContext {
             surface_capabilities.current_transform
         };
 
-        let present_modes = unsafe {
-            surface_loader.get_physical_device_surface_present_modes(physical_device, surface_khr).unwrap()
-        };
-
-        let mut present_mode = present_modes
-            .iter()
-            .cloned()
-            .find(|&mode| mode == vk::PresentModeKHR::MAILBOX)
-            .unwrap_or(vk::PresentModeKHR::FIFO);
+        //Copying from swapchain images is optional, it's only used for screenshots
+        let transfer_supported = surface_capabilities.supported_usage_flags.contains(vk::ImageUsageFlags::TRANSFER_SRC);
 
-        if vsync_enabled {
-            present_mode = vk::PresentModeKHR::FIFO;
-        }
+        let image_usage = match transfer_supported {
+            true => vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+            false => vk::ImageUsageFlags::COLOR_ATTACHMENT
+        };
 
         let swapchain_loader = Swapchain::new(&instance, &logical_device);
 
@@ -278,7 +554,7 @@ impl VulkanContext {
             .image_color_space(surface_format.color_space)
             .image_format(surface_format.format)
             .image_extent(surface_resolution)
-            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_usage(image_usage)
             .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
             .pre_transform(pre_transform)
             .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
@@ -287,10 +563,16 @@ impl VulkanContext {
             .image_array_layers(1);
 
         let swapchain_khr = unsafe {
-            swapchain_loader.create_swapchain(&swapchain_create_info, None).expect("Swapchain creation failed")
+            swapchain_loader.create_swapchain(&swapchain_create_info, None).map_err(|error| format!("Swapchain creation failed: {}", error))?
         };
 
-        (surface_format, surface_resolution, swapchain_loader, swapchain_khr)
+        Ok((surface_format, surface_resolution, present_mode, swapchain_loader, swapchain_khr, transfer_supported))
+    }
+
+    //Driver can create more images than requested so image count is taken from created swapchain
+    fn print_swapchain_info(surface_format: SurfaceFormatKHR, surface_resolution: Extent2D, present_mode: PresentModeKHR, image_count: usize) {
+        println!("Swapchain: {}x{}, {} images, present mode {:?}, format {:?} ({:?})", surface_resolution.width, surface_resolution.height, image_count, 
+            present_mode, surface_format.format, surface_format.color_space);
     }
 
     fn get_swapchain_image_imageviews(swapchain_loader: &Swapchain, swapchain_khr: SwapchainKHR, logical_device: &Device, surface_format: SurfaceFormatKHR) -> (Vec<Image>, Vec<ImageView>) {
@@ -327,6 +609,17 @@ impl VulkanContext {
         (swapchain_images, swapchain_image_views)
     }
 
+    //Debug messenger has to be destroyed before instance it was created with
+    fn destroy_instance(instance: &Instance, debug_messenger: Option<(DebugUtils, DebugUtilsMessengerEXT)>) {
+        unsafe {
+            if let Some((debug_utils, debug_messenger)) = debug_messenger {
+                debug_utils.destroy_debug_utils_messenger(debug_messenger, None);
+            }
+
+            instance.destroy_instance(None);
+        }
+    }
+
     fn destroy_swapchain(&mut self) {
         unsafe {
             for &image_view in self.swapchain_image_views.iter() {
@@ -345,7 +638,8 @@ impl Drop for VulkanContext {
             ManuallyDrop::drop(&mut self.allocator);
             self.logical_device.destroy_device(None);
             self.surface_loader.destroy_surface(self.surface_khr, None);
-            self.instance.destroy_instance(None);
+
+            Self::destroy_instance(&self.instance, self.debug_messenger.take());
         }
     }
 }
@@ -1,6 +1,8 @@
 //Descriptor sets management
 //Allocating buffers, descriptor pool and sets
-//Allocates one set of uniform buffers (each for frame in flight), optionally with images for texture array
+//Allocates one set of uniform buffers (each for frame in flight), optionally with sampler and arrays of images (like textures or normal maps)
+//Every image array gets its own binding, starting after sampler binding
+//Single image can be bound together with sampler instead (like skybox cubemap) or as input attachment read from previous subpass
 
 use std::{os::raw::c_void, ptr::NonNull, str::FromStr};
 
@@ -11,6 +13,13 @@ use gpu_allocator::vulkan::Allocator;
 
 use super::vulkan_buffer::VulkanBuffer;
 
+//Images bound after uniform buffer
+pub enum DescriptorImages {
+    Separate(Sampler, Vec<Vec<ImageView>>), //Sampler in binding 1, image arrays in bindings 2 and up
+    Combined(Sampler, ImageView), //Image with sampler in binding 1
+    Input(ImageView) //Input attachment in binding 1
+}
+
 pub struct VulkanDescriptor {
     pub descriptor_set_layout: DescriptorSetLayout,
     uniform_buffers: Vec<VulkanBuffer>,
@@ -19,9 +28,12 @@ pub struct VulkanDescriptor {
 }
 
 impl VulkanDescriptor {
-    pub fn new(logical_device: &Device, allocator: &mut Allocator, frames_in_flight: usize, uniform_buffer_size: u64, name: &str, sampler: Option<Sampler>, image_views: Vec<ImageView>) -> Self {
-        if sampler.is_some() && image_views.len() == 0 {
-            panic!("Attempted to use sampler without images.");
+    pub fn new(logical_device: &Device, allocator: &mut Allocator, frames_in_flight: usize, uniform_buffer_size: u64, name: &str, 
+        images: Option<DescriptorImages>) -> Self {
+        if let Some(DescriptorImages::Separate(_, image_arrays)) = &images {
+            if image_arrays.iter().all(|image_views| image_views.is_empty()) {
+                panic!("Attempted to use sampler without images.");
+            }
         }
         
         let mut descriptor_set_layout_binding: Vec<DescriptorSetLayoutBinding> = Vec::new();
@@ -30,13 +42,13 @@ impl VulkanDescriptor {
             .binding(0)
             .descriptor_type(DescriptorType::UNIFORM_BUFFER)
             .descriptor_count(1)
-            .stage_flags(ShaderStageFlags::VERTEX)
+            .stage_flags(ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT)
             .build();
 
         descriptor_set_layout_binding.push(uniform_buffer_binding);
 
-        match sampler {
-            Some(_) => {
+        match &images {
+            Some(DescriptorImages::Separate(_, image_arrays)) => {
                 let sampler_binding = DescriptorSetLayoutBinding::builder()
                     .binding(1)
                     .descriptor_type(DescriptorType::SAMPLER)
@@ -44,15 +56,38 @@ impl VulkanDescriptor {
                     .stage_flags(ShaderStageFlags::FRAGMENT)
                     .build();
 
-                let texture_binding = DescriptorSetLayoutBinding::builder()
-                    .binding(2)
-                    .descriptor_type(DescriptorType::SAMPLED_IMAGE)
-                    .descriptor_count(image_views.len() as u32)
+                descriptor_set_layout_binding.push(sampler_binding);
+
+                for (array_index, image_views) in image_arrays.iter().enumerate() {
+                    let images_binding = DescriptorSetLayoutBinding::builder()
+                        .binding(2 + array_index as u32)
+                        .descriptor_type(DescriptorType::SAMPLED_IMAGE)
+                        .descriptor_count(image_views.len() as u32)
+                        .stage_flags(ShaderStageFlags::FRAGMENT)
+                        .build();
+
+                    descriptor_set_layout_binding.push(images_binding);
+                }
+            },
+            Some(DescriptorImages::Combined(..)) => {
+                let combined_binding = DescriptorSetLayoutBinding::builder()
+                    .binding(1)
+                    .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+                    .descriptor_count(1)
+                    .stage_flags(ShaderStageFlags::FRAGMENT)
+                    .build();
+
+                descriptor_set_layout_binding.push(combined_binding);
+            },
+            Some(DescriptorImages::Input(_)) => {
+                let input_binding = DescriptorSetLayoutBinding::builder()
+                    .binding(1)
+                    .descriptor_type(DescriptorType::INPUT_ATTACHMENT)
+                    .descriptor_count(1)
                     .stage_flags(ShaderStageFlags::FRAGMENT)
                     .build();
 
-                descriptor_set_layout_binding.push(sampler_binding);
-                descriptor_set_layout_binding.push(texture_binding);
+                descriptor_set_layout_binding.push(input_binding);
             },
             None => ()
         };
@@ -83,16 +118,31 @@ impl VulkanDescriptor {
             .descriptor_count(frames_in_flight as u32)
             .build());
 
-        if sampler.is_some() {
-            descriptor_pool_sizes.push(DescriptorPoolSize::builder()
-                .ty(DescriptorType::SAMPLER)
-                .descriptor_count(frames_in_flight as u32)
-                .build());
-
-            descriptor_pool_sizes.push(DescriptorPoolSize::builder()
-                .ty(DescriptorType::SAMPLED_IMAGE)
-                .descriptor_count((frames_in_flight * image_views.len()) as u32)
-                .build());
+        match &images {
+            Some(DescriptorImages::Separate(_, image_arrays)) => {
+                descriptor_pool_sizes.push(DescriptorPoolSize::builder()
+                    .ty(DescriptorType::SAMPLER)
+                    .descriptor_count(frames_in_flight as u32)
+                    .build());
+
+                descriptor_pool_sizes.push(DescriptorPoolSize::builder()
+                    .ty(DescriptorType::SAMPLED_IMAGE)
+                    .descriptor_count((frames_in_flight * image_arrays.iter().map(|image_views| image_views.len()).sum::<usize>()) as u32)
+                    .build());
+            },
+            Some(DescriptorImages::Combined(..)) => {
+                descriptor_pool_sizes.push(DescriptorPoolSize::builder()
+                    .ty(DescriptorType::COMBINED_IMAGE_SAMPLER)
+                    .descriptor_count(frames_in_flight as u32)
+                    .build());
+            },
+            Some(DescriptorImages::Input(_)) => {
+                descriptor_pool_sizes.push(DescriptorPoolSize::builder()
+                    .ty(DescriptorType::INPUT_ATTACHMENT)
+                    .descriptor_count(frames_in_flight as u32)
+                    .build());
+            },
+            None => ()
         }
 
         let descriptor_pool_create_info = DescriptorPoolCreateInfo::builder()
@@ -119,18 +169,36 @@ impl VulkanDescriptor {
             logical_device.allocate_descriptor_sets(&descriptor_set_allocate_info).expect("Allocating descriptor sets failed.")
         };
 
-        let mut descriptor_image_infos: Vec<DescriptorImageInfo> = Vec::new();
-
-        if sampler.is_some() {
-            for n in 0..image_views.len() {
-                let descriptor_image_info = DescriptorImageInfo::builder()
-                    .image_view(image_views[n])
-                    .sampler(sampler.unwrap())
+        let mut descriptor_image_infos: Vec<Vec<DescriptorImageInfo>> = Vec::new();
+
+        match &images {
+            Some(DescriptorImages::Separate(sampler, image_arrays)) => {
+                for image_views in image_arrays.iter() {
+                    let image_infos = image_views.iter()
+                        .map(|&image_view| DescriptorImageInfo::builder()
+                            .image_view(image_view)
+                            .sampler(*sampler)
+                            .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                            .build())
+                        .collect();
+
+                    descriptor_image_infos.push(image_infos);
+                }
+            },
+            Some(DescriptorImages::Combined(sampler, image_view)) => {
+                descriptor_image_infos.push(vec![DescriptorImageInfo::builder()
+                    .image_view(*image_view)
+                    .sampler(*sampler)
                     .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
-                    .build();
-    
-                descriptor_image_infos.push(descriptor_image_info);
-            }
+                    .build()]);
+            },
+            Some(DescriptorImages::Input(image_view)) => {
+                descriptor_image_infos.push(vec![DescriptorImageInfo::builder()
+                    .image_view(*image_view)
+                    .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                    .build()]);
+            },
+            None => ()
         }
 
         for n in 0..frames_in_flight {
@@ -152,30 +220,60 @@ impl VulkanDescriptor {
                 ..Default::default()
             });
 
-            if sampler.is_some() {
-                let sampler_info = DescriptorImageInfo::builder()
-                    .sampler(sampler.unwrap())
-                    .build();
-
-                write_descriptor_sets.push(WriteDescriptorSet {
-                    dst_set: descriptor_sets[n],
-                    dst_binding: 1,
-                    dst_array_element: 0,
-                    descriptor_type: DescriptorType::SAMPLER,
-                    descriptor_count: 1,
-                    p_image_info: &sampler_info,
-                    ..Default::default()
-                });
-
-                write_descriptor_sets.push(WriteDescriptorSet {
-                    dst_set: descriptor_sets[n],
-                    dst_binding: 2,
-                    dst_array_element: 0,
-                    descriptor_type: DescriptorType::SAMPLED_IMAGE,
-                    descriptor_count: image_views.len() as u32,
-                    p_image_info: descriptor_image_infos.as_ptr(),
-                    ..Default::default()
-                });
+            //Sampler info has to live until descriptor sets are updated
+            let sampler_info;
+
+            match &images {
+                Some(DescriptorImages::Separate(sampler, _)) => {
+                    sampler_info = DescriptorImageInfo::builder()
+                        .sampler(*sampler)
+                        .build();
+
+                    write_descriptor_sets.push(WriteDescriptorSet {
+                        dst_set: descriptor_sets[n],
+                        dst_binding: 1,
+                        dst_array_element: 0,
+                        descriptor_type: DescriptorType::SAMPLER,
+                        descriptor_count: 1,
+                        p_image_info: &sampler_info,
+                        ..Default::default()
+                    });
+
+                    for (array_index, image_infos) in descriptor_image_infos.iter().enumerate() {
+                        write_descriptor_sets.push(WriteDescriptorSet {
+                            dst_set: descriptor_sets[n],
+                            dst_binding: 2 + array_index as u32,
+                            dst_array_element: 0,
+                            descriptor_type: DescriptorType::SAMPLED_IMAGE,
+                            descriptor_count: image_infos.len() as u32,
+                            p_image_info: image_infos.as_ptr(),
+                            ..Default::default()
+                        });
+                    }
+                },
+                Some(DescriptorImages::Combined(..)) => {
+                    write_descriptor_sets.push(WriteDescriptorSet {
+                        dst_set: descriptor_sets[n],
+                        dst_binding: 1,
+                        dst_array_element: 0,
+                        descriptor_type: DescriptorType::COMBINED_IMAGE_SAMPLER,
+                        descriptor_count: 1,
+                        p_image_info: descriptor_image_infos[0].as_ptr(),
+                        ..Default::default()
+                    });
+                },
+                Some(DescriptorImages::Input(_)) => {
+                    write_descriptor_sets.push(WriteDescriptorSet {
+                        dst_set: descriptor_sets[n],
+                        dst_binding: 1,
+                        dst_array_element: 0,
+                        descriptor_type: DescriptorType::INPUT_ATTACHMENT,
+                        descriptor_count: 1,
+                        p_image_info: descriptor_image_infos[0].as_ptr(),
+                        ..Default::default()
+                    });
+                },
+                None => ()
             }
 
             unsafe {
@@ -1,8 +1,8 @@
 //Image management
 //Allocating, loading, transitioning layout, generating mipmaps etc.
 
-use ash::{vk::{self, AccessFlags, BufferImageCopy, CommandBufferAllocateInfo, CommandBufferBeginInfo, CommandBufferLevel, CommandPool, DependencyFlags, Extent3D, Fence, Filter, Format, Image, 
-    ImageAspectFlags, ImageBlit, ImageCreateInfo, ImageLayout, ImageMemoryBarrier, ImageSubresourceLayers, ImageSubresourceRange, ImageTiling, ImageType, ImageUsageFlags, ImageView, ImageViewCreateInfo, 
+use ash::{vk::{self, AccessFlags, BufferImageCopy, BufferMemoryBarrier, CommandBuffer, CommandBufferAllocateInfo, CommandBufferBeginInfo, CommandBufferLevel, CommandPool, DependencyFlags, Extent3D, Fence, Filter, Format, Image, 
+    ImageAspectFlags, ImageBlit, ImageCreateFlags, ImageCreateInfo, ImageLayout, ImageMemoryBarrier, ImageSubresourceLayers, ImageSubresourceRange, ImageTiling, ImageType, ImageUsageFlags, ImageView, ImageViewCreateInfo, 
     ImageViewType, Offset3D, PipelineStageFlags, Queue, SampleCountFlags, SharingMode, SubmitInfo}, Device};
 
 use gpu_allocator::vulkan::{Allocation, AllocationCreateDesc, Allocator};
@@ -18,12 +18,29 @@ pub struct VulkanImage {
     pub height: u32,
     pub aspect_flags: ImageAspectFlags,
     layout: ImageLayout,
-    mip_levels: u32
+    mip_levels: u32,
+    layer_count: u32 //6 for cubemaps, 1 for everything else
 }
 
 impl VulkanImage {
     pub fn new(logical_device: &Device, allocator: &mut Allocator, name: &str, width: u32, height: u32, format: Format, tiling: ImageTiling, 
             usage: ImageUsageFlags, aspect_flags: ImageAspectFlags, enable_mipmapping: bool, sample_count: SampleCountFlags) -> Self {
+        Self::create(logical_device, allocator, name, width, height, format, tiling, usage, aspect_flags, enable_mipmapping, sample_count, false)
+    }
+
+    //Cubemap has six square layers (one for every face) viewed as one cube image, it's used only for sampling so it has no mipmaps
+    pub fn new_cubemap(logical_device: &Device, allocator: &mut Allocator, name: &str, size: u32, format: Format) -> Self {
+        Self::create(logical_device, allocator, name, size, size, format, ImageTiling::OPTIMAL, ImageUsageFlags::TRANSFER_DST | ImageUsageFlags::SAMPLED, 
+            ImageAspectFlags::COLOR, false, SampleCountFlags::TYPE_1, true)
+    }
+
+    fn create(logical_device: &Device, allocator: &mut Allocator, name: &str, width: u32, height: u32, format: Format, tiling: ImageTiling, 
+            usage: ImageUsageFlags, aspect_flags: ImageAspectFlags, enable_mipmapping: bool, sample_count: SampleCountFlags, cubemap: bool) -> Self {
+
+        let (layer_count, create_flags, view_type) = match cubemap {
+            true => (6, ImageCreateFlags::CUBE_COMPATIBLE, ImageViewType::CUBE),
+            false => (1, ImageCreateFlags::empty(), ImageViewType::TYPE_2D)
+        };
 
         let mip_levels = match enable_mipmapping {
             true => {
@@ -36,6 +53,7 @@ impl VulkanImage {
         };
         
         let image_create_info = ImageCreateInfo::builder()
+            .flags(create_flags)
             .image_type(ImageType::TYPE_2D)
             .extent(Extent3D {
                 width,
@@ -43,7 +61,7 @@ impl VulkanImage {
                 depth: 1
             })
             .mip_levels(mip_levels)
-            .array_layers(1)
+            .array_layers(layer_count)
             .format(format)
             .tiling(tiling)
             .initial_layout(ImageLayout::UNDEFINED)
@@ -73,14 +91,14 @@ impl VulkanImage {
 
         let image_view_create_info = ImageViewCreateInfo::builder()
             .image(image)
-            .view_type(ImageViewType::TYPE_2D)
+            .view_type(view_type)
             .format(format)
             .subresource_range(ImageSubresourceRange {
                 aspect_mask: aspect_flags,
                 base_mip_level: 0,
                 level_count: mip_levels,
                 base_array_layer: 0,
-                layer_count: 1
+                layer_count
             });
 
         let image_view = unsafe {
@@ -96,7 +114,8 @@ impl VulkanImage {
             height,
             aspect_flags,
             layout: ImageLayout::UNDEFINED,
-            mip_levels: mip_levels
+            mip_levels: mip_levels,
+            layer_count
         }
     }
 
@@ -154,7 +173,7 @@ impl VulkanImage {
                     base_mip_level: 0,
                     level_count: self.mip_levels,
                     base_array_layer: 0,
-                    layer_count: 1
+                    layer_count: self.layer_count
                 })
                 .src_access_mask(src_access_mask) 
                 .dst_access_mask(dst_access_mask)
@@ -179,6 +198,7 @@ impl VulkanImage {
         self.layout = new_layout;
     }
 
+    //Layers of image are expected to follow each other in buffer
     pub fn populate_from_buffer(&mut self, logical_device: &Device, present_queue: Queue, command_pool: CommandPool, src_buffer: &VulkanBuffer) {
         let command_buffer_info = CommandBufferAllocateInfo::builder()
             .command_pool(command_pool)
@@ -202,7 +222,7 @@ impl VulkanImage {
                     aspect_mask: self.aspect_flags,
                     mip_level: 0,
                     base_array_layer: 0,
-                    layer_count: 1
+                    layer_count: self.layer_count
                 })
                 .image_offset(Offset3D {
                     x: 0,
@@ -232,6 +252,83 @@ impl VulkanImage {
         }
     }
 
+    //Record copy of image owned by swapchain (which isn't VulkanImage) to buffer into frame command buffer after render pass
+    //Render pass leaves image in present layout, it's moved to transfer layout after rendering finishes and back to present layout after copying
+    pub fn record_swapchain_image_copy(logical_device: &Device, command_buffer: CommandBuffer, image: Image, width: u32, height: u32, dst_buffer: &VulkanBuffer) {
+        let subresource_range = ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1
+        };
+
+        unsafe {
+            let transfer_barrier = ImageMemoryBarrier::builder()
+                .old_layout(ImageLayout::PRESENT_SRC_KHR)
+                .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(subresource_range)
+                .src_access_mask(AccessFlags::COLOR_ATTACHMENT_WRITE)
+                .dst_access_mask(AccessFlags::TRANSFER_READ)
+                .build();
+
+            logical_device.cmd_pipeline_barrier(command_buffer, PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT, PipelineStageFlags::TRANSFER, DependencyFlags::empty(), 
+                &[], &[], &[transfer_barrier]);
+
+            let image_copy_region = BufferImageCopy::builder()
+                .buffer_offset(0)
+                .buffer_row_length(0)
+                .buffer_image_height(0)
+                .image_subresource(ImageSubresourceLayers {
+                    aspect_mask: ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1
+                })
+                .image_offset(Offset3D {
+                    x: 0,
+                    y: 0,
+                    z: 0
+                })
+                .image_extent(Extent3D {
+                    width,
+                    height,
+                    depth: 1
+                })
+                .build();
+
+            logical_device.cmd_copy_image_to_buffer(command_buffer, image, ImageLayout::TRANSFER_SRC_OPTIMAL, dst_buffer.buffer, &[image_copy_region]);
+
+            //Buffer is read on host after frame fence is signaled
+            let host_barrier = BufferMemoryBarrier::builder()
+                .src_access_mask(AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(AccessFlags::HOST_READ)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .buffer(dst_buffer.buffer)
+                .offset(0)
+                .size(vk::WHOLE_SIZE)
+                .build();
+
+            let present_barrier = ImageMemoryBarrier::builder()
+                .old_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+                .new_layout(ImageLayout::PRESENT_SRC_KHR)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(subresource_range)
+                .src_access_mask(AccessFlags::TRANSFER_READ)
+                .dst_access_mask(AccessFlags::empty())
+                .build();
+
+            logical_device.cmd_pipeline_barrier(command_buffer, PipelineStageFlags::TRANSFER, PipelineStageFlags::HOST | PipelineStageFlags::BOTTOM_OF_PIPE, 
+                DependencyFlags::empty(), &[], &[host_barrier], &[present_barrier]);
+        }
+    }
+
     pub fn generate_mipmaps(&mut self, logical_device: &Device, present_queue: Queue, command_pool: CommandPool) {
         if self.mip_levels == 1 {
             panic!("Attempted to generate mipmaps on image without mipmaping enabled.");
@@ -1,5 +1,5 @@
 //Data related to one mesh (like vertex buffer, index buffer etc.)
-//Every mesh have its own data (model matrix and texture index) uploaded to shaders with push constants
+//Model matrix and texture index of maze quads are uploaded as instance data, overlay quads use push constants
 
 use std::mem;
 
@@ -7,18 +7,36 @@ use ash::vk::{BufferUsageFlags, CommandPool};
 
 use super::{vulkan_buffer::VulkanBuffer, vulkan_context::VulkanContext, vulkan_vertex_input::{VertexData, VertexInput}};
 
+//Data for quads drawn on top of the scene, used in both vertex and fragment shader
+#[repr(C)]
 #[derive(Copy, Clone)]
-pub struct PushConstant {
-    pub model_matrix: glm::Mat4,
+pub struct OverlayPushConstant {
+    pub transform: glm::Mat4,
+    pub color: glm::Vec4,
     pub texture_index: i32
 }
 
+//Push constant uploaded with mesh, type depends on pipeline used for drawing
+#[derive(Copy, Clone)]
+pub enum PushConstantData {
+    Overlay(OverlayPushConstant)
+}
+
+impl PushConstantData {
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            match self {
+                PushConstantData::Overlay(data) => std::slice::from_raw_parts(data as *const OverlayPushConstant as *const u8, mem::size_of::<OverlayPushConstant>())
+            }
+        }
+    }
+}
+
 pub struct VulkanMesh {
     pub vertex_buffer: Option<VulkanBuffer>,
     pub index_buffer: Option<VulkanBuffer>,
     pub vertex_indices: Vec<u32>,
-    pub vertex_input: Option<VertexInput>,
-    pub push_constant: PushConstant
+    pub vertex_input: Option<VertexInput>
 }
 
 impl VulkanMesh {
@@ -27,11 +45,7 @@ impl VulkanMesh {
             vertex_buffer: None,
             index_buffer: None,
             vertex_indices: Vec::new(),
-            vertex_input: None,
-            push_constant: PushConstant {
-                model_matrix: glm::Mat4::identity(),
-                texture_index: 0
-            }
+            vertex_input: None
         }
     }
 
@@ -68,10 +82,6 @@ impl VulkanMesh {
         }
     }
 
-    pub fn set_mesh_data(&mut self, data: PushConstant) {
-        self.push_constant = data;
-    }
-
     fn create_buffers(vulkan_context: &mut VulkanContext, vertex_input: &VertexInput, vertex_indices: &Vec<u32>, command_pool: CommandPool) -> (VulkanBuffer, Option<VulkanBuffer>) {
         let logical_device = &vulkan_context.logical_device;
         let allocator = &mut vulkan_context.allocator;
@@ -2,7 +2,7 @@
 
 use std::ffi::CStr;
 
-use ash::{vk::{self, ColorComponentFlags, CompareOp, CullModeFlags, DynamicState, FrontFace, GraphicsPipelineCreateInfo, LogicOp, Pipeline, PipelineColorBlendAttachmentState, PipelineColorBlendStateCreateInfo, 
+use ash::{vk::{self, BlendFactor, BlendOp, ColorComponentFlags, CompareOp, CullModeFlags, DynamicState, FrontFace, GraphicsPipelineCreateInfo, LogicOp, Pipeline, PipelineColorBlendAttachmentState, PipelineColorBlendStateCreateInfo, 
     PipelineDepthStencilStateCreateInfo, PipelineDynamicStateCreateInfo, PipelineInputAssemblyStateCreateInfo, PipelineLayout, PipelineMultisampleStateCreateInfo, PipelineRasterizationStateCreateInfo, 
     PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo, PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology, RenderPass, SampleCountFlags, ShaderModule, ShaderStageFlags, 
     VertexInputAttributeDescription, VertexInputBindingDescription}, Device};
@@ -11,7 +11,16 @@ pub struct VulkanPipeline {
     shader_stages: Vec<PipelineShaderStageCreateInfo>,
     topology: PrimitiveTopology,
     vertex_input_bindings: Vec<VertexInputBindingDescription>,
-    vertex_input_attributes: Vec<VertexInputAttributeDescription>
+    vertex_input_attributes: Vec<VertexInputAttributeDescription>,
+    depth_test: bool,
+    depth_write: bool,
+    depth_compare_op: CompareOp,
+    blending: bool,
+    color_output: bool,
+    depth_bias: bool,
+    polygon_mode: PolygonMode,
+    culling: bool,
+    subpass: u32
 }
 
 impl VulkanPipeline {
@@ -20,11 +29,20 @@ impl VulkanPipeline {
             shader_stages: Vec::new(),
             topology,
             vertex_input_bindings: Vec::new(),
-            vertex_input_attributes: Vec::new()
+            vertex_input_attributes: Vec::new(),
+            depth_test: true,
+            depth_write: true,
+            depth_compare_op: CompareOp::LESS,
+            blending: false,
+            color_output: true,
+            depth_bias: false,
+            polygon_mode: PolygonMode::FILL,
+            culling: true,
+            subpass: 0
         }
     }
 
-    pub fn build_pipeline(&mut self, logical_device: &Device, pipeline_layout: PipelineLayout, render_pass: RenderPass, sample_count: SampleCountFlags) -> Pipeline {
+    pub fn build_pipeline(&mut self, logical_device: &Device, pipeline_layout: PipelineLayout, render_pass: RenderPass, sample_count: SampleCountFlags) -> Result<Pipeline, vk::Result> {
         if self.shader_stages.is_empty() {
             panic!("Attempted to build pipeline without shader stages.");
         }
@@ -33,17 +51,19 @@ impl VulkanPipeline {
             .topology(self.topology)
             .primitive_restart_enable(false);
 
+        //Quads are counter clockwise when looking at them from the side of their normal, same as OpenGL default
+        //Projection matrix inverts Y so winding on screen stays the same as with OpenGL
         let rasterization_state = PipelineRasterizationStateCreateInfo::builder()
             .depth_clamp_enable(false)
             .rasterizer_discard_enable(false)
-            .polygon_mode(PolygonMode::FILL)
+            .polygon_mode(self.polygon_mode)
             .line_width(1.0)
-            .cull_mode(CullModeFlags::NONE)
-            .front_face(FrontFace::CLOCKWISE)
-            .depth_bias_enable(false)
-            .depth_bias_constant_factor(0.0)
+            .cull_mode(if self.culling { CullModeFlags::BACK } else { CullModeFlags::NONE })
+            .front_face(FrontFace::COUNTER_CLOCKWISE)
+            .depth_bias_enable(self.depth_bias)
+            .depth_bias_constant_factor(if self.depth_bias { 1.25 } else { 0.0 })
             .depth_bias_clamp(0.0)
-            .depth_bias_constant_factor(0.0);
+            .depth_bias_slope_factor(if self.depth_bias { 1.75 } else { 0.0 });
 
         let multisample_state = PipelineMultisampleStateCreateInfo::builder()
             .sample_shading_enable(true)
@@ -54,7 +74,13 @@ impl VulkanPipeline {
 
         let color_blend_attachment_state = PipelineColorBlendAttachmentState::builder()
             .color_write_mask(ColorComponentFlags::R | ColorComponentFlags::G | ColorComponentFlags::B | ColorComponentFlags::A)
-            .blend_enable(false)
+            .blend_enable(self.blending)
+            .src_color_blend_factor(BlendFactor::SRC_ALPHA)
+            .dst_color_blend_factor(BlendFactor::ONE_MINUS_SRC_ALPHA)
+            .color_blend_op(BlendOp::ADD)
+            .src_alpha_blend_factor(BlendFactor::ONE)
+            .dst_alpha_blend_factor(BlendFactor::ZERO)
+            .alpha_blend_op(BlendOp::ADD)
             .build();
 
         let pipeline_dynamic_states = PipelineDynamicStateCreateInfo::builder()
@@ -67,12 +93,12 @@ impl VulkanPipeline {
         let pipeline_color_blend_state = PipelineColorBlendStateCreateInfo::builder()
             .logic_op_enable(false)
             .logic_op(LogicOp::COPY)
-            .attachments(std::slice::from_ref(&color_blend_attachment_state));
+            .attachments(if self.color_output { std::slice::from_ref(&color_blend_attachment_state) } else { &[] });
 
         let pipeline_depth_stencil_state = PipelineDepthStencilStateCreateInfo::builder()
-            .depth_test_enable(true)
-            .depth_write_enable(true)
-            .depth_compare_op(CompareOp::LESS)
+            .depth_test_enable(self.depth_test)
+            .depth_write_enable(self.depth_test && self.depth_write)
+            .depth_compare_op(self.depth_compare_op)
             .depth_bounds_test_enable(false)
             .stencil_test_enable(false);
 
@@ -92,13 +118,13 @@ impl VulkanPipeline {
             .depth_stencil_state(&pipeline_depth_stencil_state)
             .layout(pipeline_layout)
             .render_pass(render_pass)
-            .subpass(0);
+            .subpass(self.subpass);
 
         let graphics_pipeline = unsafe {
-            logical_device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_create_info.build()], None).expect("Graphics pipeline creation failed.")
+            logical_device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_create_info.build()], None).map_err(|(_, error)| error)?
         };
 
-        graphics_pipeline[0]
+        Ok(graphics_pipeline[0])
     }
 
     pub fn add_shader_stage(&mut self, stage: ShaderStageFlags, shader_module: ShaderModule) {
@@ -115,6 +141,51 @@ impl VulkanPipeline {
         self.shader_stages.push(shader_stage_info);
     }
 
+    pub fn set_depth_test(&mut self, depth_test: bool) {
+        self.depth_test = depth_test;
+    }
+
+    //Depth is written only when depth test is enabled
+    pub fn set_depth_write(&mut self, depth_write: bool) {
+        self.depth_write = depth_write;
+    }
+
+    pub fn set_depth_compare_op(&mut self, depth_compare_op: CompareOp) {
+        self.depth_compare_op = depth_compare_op;
+    }
+
+    pub fn set_blending(&mut self, blending: bool) {
+        self.blending = blending;
+    }
+
+    //Back faces are culled by default
+    pub fn set_culling(&mut self, culling: bool) {
+        self.culling = culling;
+    }
+
+    //Pipeline without color output is used for depth only passes
+    pub fn set_color_output(&mut self, color_output: bool) {
+        self.color_output = color_output;
+    }
+
+    //Depth bias reduces self shadowing artifacts when rendering shadow maps
+    pub fn set_depth_bias(&mut self, depth_bias: bool) {
+        self.depth_bias = depth_bias;
+    }
+
+    //Index of render pass subpass pipeline is used in
+    pub fn set_subpass(&mut self, subpass: u32) {
+        self.subpass = subpass;
+    }
+
+    //Line polygon mode requires fillModeNonSolid device feature
+    pub fn set_wireframe(&mut self, wireframe: bool) {
+        self.polygon_mode = match wireframe {
+            true => PolygonMode::LINE,
+            false => PolygonMode::FILL
+        };
+    }
+
     pub fn add_vertex_input_bindings(&mut self, bindings: &mut Vec<VertexInputBindingDescription>) {
         self.vertex_input_bindings.append(bindings);
     }
@@ -0,0 +1,110 @@
+//Shadow map with its own depth only render pass and framebuffer
+//After render pass ends depth image is ready to be sampled in fragment shader
+
+use ash::{vk::{self, AccessFlags, AttachmentDescription, AttachmentDescriptionFlags, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp, DependencyFlags, Format, Framebuffer,
+    ImageAspectFlags, ImageLayout, ImageTiling, ImageUsageFlags, PipelineBindPoint, PipelineStageFlags, RenderPass, RenderPassCreateInfo, SampleCountFlags, SubpassDependency,
+    SubpassDescription}, Device};
+
+use gpu_allocator::vulkan::Allocator;
+
+use super::vulkan_image::VulkanImage;
+
+pub struct VulkanShadowMap {
+    pub depth_image: VulkanImage,
+    pub render_pass: RenderPass,
+    pub framebuffer: Framebuffer,
+    pub size: u32
+}
+
+impl VulkanShadowMap {
+    pub fn new(logical_device: &Device, allocator: &mut Allocator, size: u32) -> Self {
+        let depth_image = VulkanImage::new(logical_device, allocator, "Shadow map", size, size, Format::D32_SFLOAT, ImageTiling::OPTIMAL,
+            ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT | ImageUsageFlags::SAMPLED, ImageAspectFlags::DEPTH, false, SampleCountFlags::TYPE_1);
+
+        let render_pass = Self::create_render_pass(logical_device, depth_image.format);
+
+        let framebuffer_attachments = [depth_image.image_view];
+        let framebuffer_create_info = vk::FramebufferCreateInfo::builder()
+            .render_pass(render_pass)
+            .attachments(&framebuffer_attachments)
+            .width(size)
+            .height(size)
+            .layers(1);
+
+        let framebuffer = unsafe {
+            logical_device.create_framebuffer(&framebuffer_create_info, None).expect("Shadow map framebuffer creation failed.")
+        };
+
+        Self {
+            depth_image,
+            render_pass,
+            framebuffer,
+            size
+        }
+    }
+
+    pub fn free(&mut self, logical_device: &Device, allocator: &mut Allocator) {
+        unsafe {
+            logical_device.destroy_framebuffer(self.framebuffer, None);
+            logical_device.destroy_render_pass(self.render_pass, None);
+        }
+
+        self.depth_image.free(logical_device, allocator);
+    }
+
+    fn create_render_pass(logical_device: &Device, depth_format: Format) -> RenderPass {
+        let attachments = [
+            AttachmentDescription {
+                format: depth_format,
+                samples: SampleCountFlags::TYPE_1,
+                load_op: AttachmentLoadOp::CLEAR,
+                store_op: AttachmentStoreOp::STORE,
+                stencil_load_op: AttachmentLoadOp::DONT_CARE,
+                stencil_store_op: AttachmentStoreOp::DONT_CARE,
+                initial_layout: ImageLayout::UNDEFINED,
+                final_layout: ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                flags: AttachmentDescriptionFlags::empty()
+            }
+        ];
+
+        let depth_attachment_reference = AttachmentReference {
+            attachment: 0,
+            layout: ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+        };
+
+        let subpass = SubpassDescription::builder()
+            .pipeline_bind_point(PipelineBindPoint::GRAPHICS)
+            .depth_stencil_attachment(&depth_attachment_reference);
+
+        //Previous frame could still read shadow map and next pass can't read it until it's written
+        let dependencies = [
+            SubpassDependency {
+                src_subpass: vk::SUBPASS_EXTERNAL,
+                dst_subpass: 0,
+                src_stage_mask: PipelineStageFlags::FRAGMENT_SHADER,
+                dst_stage_mask: PipelineStageFlags::EARLY_FRAGMENT_TESTS,
+                src_access_mask: AccessFlags::SHADER_READ,
+                dst_access_mask: AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                dependency_flags: DependencyFlags::BY_REGION
+            },
+            SubpassDependency {
+                src_subpass: 0,
+                dst_subpass: vk::SUBPASS_EXTERNAL,
+                src_stage_mask: PipelineStageFlags::LATE_FRAGMENT_TESTS,
+                dst_stage_mask: PipelineStageFlags::FRAGMENT_SHADER,
+                src_access_mask: AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                dst_access_mask: AccessFlags::SHADER_READ,
+                dependency_flags: DependencyFlags::BY_REGION
+            }
+        ];
+
+        let render_pass_create_info = RenderPassCreateInfo::builder()
+            .attachments(&attachments)
+            .subpasses(std::slice::from_ref(&subpass))
+            .dependencies(&dependencies);
+
+        unsafe {
+            logical_device.create_render_pass(&render_pass_create_info, None).expect("Shadow map render pass creation failed.")
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //Definition of vertex binding and attributes
-//Every vertex is supposed to have position, color, normal and texture uv
+//Every vertex is supposed to have position, normal, texture uv and tangent (used for normal mapping)
+//Instanced drawing uses second binding with model matrix and texture index for every instance
 
 use std::{hash::{Hasher, Hash}, mem};
 
@@ -9,7 +10,16 @@ use ash::vk::{Format, VertexInputAttributeDescription, VertexInputBindingDescrip
 pub struct VertexData {
     vertex_position: glm::Vec3,
     vertex_normal: glm::Vec3,
-    texture_uv: glm::Vec2
+    texture_uv: glm::Vec2,
+    vertex_tangent: glm::Vec3
+}
+
+//Data of one instance, matrix is uploaded as four vec4 attributes
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct InstanceData {
+    pub model_matrix: glm::Mat4,
+    pub texture_index: i32
 }
 
 pub struct VertexInput {
@@ -17,11 +27,12 @@ pub struct VertexInput {
 }
 
 impl VertexData {
-    pub fn new(vertex_position: glm::Vec3, vertex_normal: glm::Vec3, texture_uv: glm::Vec2) -> Self {
+    pub fn new(vertex_position: glm::Vec3, vertex_normal: glm::Vec3, texture_uv: glm::Vec2, vertex_tangent: glm::Vec3) -> Self {
         Self {
             vertex_position,
             vertex_normal,
-            texture_uv
+            texture_uv,
+            vertex_tangent
         }
     }
 }
@@ -29,6 +40,7 @@ impl VertexData {
 impl PartialEq for VertexData {
     fn eq(&self, other: &Self) -> bool {
         self.vertex_position == other.vertex_position && self.vertex_normal == other.vertex_normal && self.texture_uv == other.texture_uv
+            && self.vertex_tangent == other.vertex_tangent
     }
 }
 
@@ -42,6 +54,9 @@ impl Hash for VertexData {
         self.vertex_normal[2].to_bits().hash(state);
         self.texture_uv[0].to_bits().hash(state);
         self.texture_uv[1].to_bits().hash(state);
+        self.vertex_tangent[0].to_bits().hash(state);
+        self.vertex_tangent[1].to_bits().hash(state);
+        self.vertex_tangent[2].to_bits().hash(state);
     }
 }
 
@@ -83,10 +98,59 @@ impl VertexInput {
             .offset(mem::offset_of!(VertexData, texture_uv) as u32)
             .build();
 
+        //Locations 3 to 7 are used by instance attributes
+        let tangent_attribute = VertexInputAttributeDescription::builder()
+            .binding(0)
+            .location(8)
+            .format(Format::R32G32B32_SFLOAT)
+            .offset(mem::offset_of!(VertexData, vertex_tangent) as u32)
+            .build();
+
         let mut attribute_descriptions = Vec::new();
         attribute_descriptions.push(position_attribute);
         attribute_descriptions.push(normal_attribute);
         attribute_descriptions.push(texture_attribute);
+        attribute_descriptions.push(tangent_attribute);
+
+        attribute_descriptions
+    }
+
+    pub fn get_instance_binding_descriptions() -> Vec<VertexInputBindingDescription> {
+        let binding_description = VertexInputBindingDescription::builder()
+            .binding(1)
+            .stride(mem::size_of::<InstanceData>() as u32)
+            .input_rate(VertexInputRate::INSTANCE)
+            .build();
+
+        let mut binding_descriptions = Vec::new();
+        binding_descriptions.push(binding_description);
+
+        binding_descriptions
+    }
+
+    pub fn get_instance_attribute_descriptions() -> Vec<VertexInputAttributeDescription> {
+        let mut attribute_descriptions = Vec::new();
+
+        //Model matrix columns at locations 3 to 6
+        for column in 0..4 {
+            let matrix_attribute = VertexInputAttributeDescription::builder()
+                .binding(1)
+                .location(3 + column)
+                .format(Format::R32G32B32A32_SFLOAT)
+                .offset(mem::offset_of!(InstanceData, model_matrix) as u32 + column * mem::size_of::<glm::Vec4>() as u32)
+                .build();
+
+            attribute_descriptions.push(matrix_attribute);
+        }
+
+        let texture_index_attribute = VertexInputAttributeDescription::builder()
+            .binding(1)
+            .location(7)
+            .format(Format::R32_SINT)
+            .offset(mem::offset_of!(InstanceData, texture_index) as u32)
+            .build();
+
+        attribute_descriptions.push(texture_index_attribute);
 
         attribute_descriptions
     }
@@ -0,0 +1,532 @@
+//Program configuration and config file
+//Config file is stored as TOML, missing keys get default values so new options don't break older files
+//Older INI config file is migrated to TOML on first start
+
+use std::{error::Error, fmt, fs, path::Path, str::FromStr};
+
+use ini::{Ini, Properties};
+use serde::{Deserialize, Serialize};
+
+use dsdmaze::maze_generator::{SelectedGenerator, StartPlacement};
+
+use crate::maze_renderer::{PostEffect, RenderingAPI, TextureFilter};
+
+//Version of config file format, increase it when meaning of existing keys changes
+const CONFIG_VERSION: u32 = 1;
+
+pub const DEFAULT_SKY_COLOR: [f32; 3] = [0.4, 0.6, 0.9];
+pub const DEFAULT_EXIT_LIGHT_COLOR: [f32; 3] = [0.2, 1.0, 0.3];
+pub const DEFAULT_COLLISION_MARGIN: f32 = 0.7;
+
+//Presets bundling maze options for players who don't know good combinations
+#[derive(Clone, Copy)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Nightmare
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Normal => write!(f, "normal"),
+            Difficulty::Hard => write!(f, "hard"),
+            Difficulty::Nightmare => write!(f, "nightmare")
+        }
+    }
+}
+
+//Cells shown on minimap, explored mode hides cells player hasn't been near to yet
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum MinimapReveal {
+    #[serde(rename = "Full")]
+    Full,
+    #[serde(rename = "Explored")]
+    Explored
+}
+
+impl fmt::Display for MinimapReveal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MinimapReveal::Full => write!(f, "full"),
+            MinimapReveal::Explored => write!(f, "explored")
+        }
+    }
+}
+
+//Options that only make sense for single run (portable mode, maze files) aren't stored in config file
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProgramConfig {
+    pub window_width: u32,
+    pub window_height: u32,
+    pub window_position: Option<(i32, i32)>, //Position of window saved in config file, placed by system if it's missing
+    pub maze_size: usize,
+    pub enable_collisions: bool,
+    pub collision_margin: f32, //Distance from wall center player is stopped at, walls are 0.5 from center
+    #[serde(rename = "fullscreen")]
+    pub set_fullscreen: bool,
+    pub exclusive_fullscreen: bool, //Exclusive fullscreen uses video mode closest to window size instead of desktop resolution
+    #[serde(skip)]
+    pub set_portable: bool,
+    pub title: String,
+    pub mouse_enabled: bool,
+    pub free_cursor: bool, //Cursor stays visible and isn't grabbed by window
+    pub fly_movement: bool, //Walking follows camera pitch instead of only yaw
+    pub mouse_smoothing: f32, //Part of mouse movement left for next frames, 0 means raw input
+    pub audio_enabled: bool,
+    pub gamepad_enabled: bool,
+    pub acceleration: f32, //Movement acceleration and friction in fields per second squared
+    pub friction: f32,
+    pub time_step: f32, //Fixed physics time step in seconds
+    pub seed: String, //Empty seed means random one is generated
+    pub selected_generator: SelectedGenerator,
+    #[serde(skip)]
+    pub difficulty: Option<Difficulty>, //Preset that set maze options, only used for printing them
+    pub start_placement: StartPlacement,
+    pub memory_budget: usize, //Memory in MB maze generation can use, bigger mazes are shrunk to fit it
+    pub threads: usize, //Threads used by parallel generators, 1 keeps serial generation
+    #[serde(skip)]
+    pub stress: bool, //Generate the biggest maze fitting memory budget, report generation time and exit
+    #[serde(skip)]
+    pub load_maze: Option<String>, //Maze file used instead of generating maze
+    #[serde(skip)]
+    pub load_ascii: Option<String>, //Text maze file used instead of generating maze
+    #[serde(skip)]
+    pub save_maze: Option<String>,
+    #[serde(skip)]
+    pub resume: bool, //Maze options and player state are restored from saved session
+    #[serde(skip)]
+    pub flythrough: bool,
+    #[serde(skip)]
+    pub flythrough_capture: bool, //Every flythrough frame is saved to PNG file
+    pub rendering_api: RenderingAPI,
+    pub vsync_enabled: bool,
+    pub triple_buffering: bool,
+    pub max_fps: u32, //Frame rate cap used without V-Sync, 0 means uncapped
+    pub msaa_samples: u32,
+    pub texture_filter: TextureFilter,
+    pub exit_beacon: bool,
+    pub exit_marker: bool, //Accessibility outline on exits, decoy exits get different pattern
+    pub noclip: bool,
+    pub wireframe: bool,
+    pub debug_collision: bool, //Draw collision rectangles of nearby walls and points tested by collision check
+    pub minimap_enabled: bool,
+    pub minimap_reveal: MinimapReveal,
+    pub render_distance: i32,
+    pub show_fps: bool,
+    pub textures_dir: Option<String>, //Texture pack directory, textures missing in it are loaded from assets
+    pub wall_variety: usize,
+    pub rooms: usize,
+    pub weave_crossings: usize, //0 means maze isn't weave maze
+    pub decoy_exits: usize,
+    pub gamma: f32,
+    pub no_ceiling: bool,
+    pub wall_height: f32,
+    pub sky_color: [f32; 3],
+    pub skybox_dir: Option<String>, //Directory with cubemap faces drawn instead of sky color
+    pub wall_tint: [f32; 3], //Colors multiplied with textures of surfaces, white leaves texture unchanged
+    pub floor_tint: [f32; 3],
+    pub ceiling_tint: [f32; 3],
+    pub fov: f32,
+    pub zoom_factor: f32, //Field of view is divided by it while zooming with mouse wheel
+    pub near_plane: f32,
+    pub far_plane: f32, //0 means far plane is calculated from render distance
+    pub gpu_index: Option<usize>,
+    #[serde(skip)]
+    pub list_gpus: bool,
+    #[serde(skip)]
+    pub print_version: bool,
+    pub debug_enabled: bool,
+    pub verbose: bool,
+    pub normal_maps: bool,
+    pub shadows: bool,
+    pub post_effect: Option<PostEffect>, //Effect applied to the scene before overlays are drawn, None draws scene directly
+    pub light_radius: f32,
+    pub torch_flicker: bool,
+    pub flicker_amplitude: f32,
+    pub exit_light_color: [f32; 3]
+}
+
+impl Default for ProgramConfig {
+    fn default() -> Self {
+        Self {
+            window_width: 800,
+            window_height: 600,
+            window_position: None,
+            maze_size: 20,
+            enable_collisions: true,
+            collision_margin: DEFAULT_COLLISION_MARGIN,
+            set_fullscreen: false,
+            exclusive_fullscreen: false,
+            set_portable: false,
+            title: String::from("dsdmaze"),
+            mouse_enabled: true,
+            free_cursor: false,
+            fly_movement: false,
+            mouse_smoothing: 0.0,
+            audio_enabled: true,
+            gamepad_enabled: false,
+            acceleration: 8.0,
+            friction: 10.0,
+            time_step: 0.01,
+            seed: String::new(),
+            selected_generator: SelectedGenerator::RD,
+            difficulty: None,
+            start_placement: StartPlacement::Random,
+            memory_budget: 1024,
+            threads: 1,
+            stress: false,
+            load_maze: None,
+            load_ascii: None,
+            save_maze: None,
+            resume: false,
+            flythrough: false,
+            flythrough_capture: false,
+            rendering_api: RenderingAPI::VULKAN,
+            vsync_enabled: true,
+            triple_buffering: false,
+            max_fps: 0,
+            msaa_samples: 4,
+            texture_filter: TextureFilter::LINEAR,
+            exit_beacon: false,
+            exit_marker: false,
+            noclip: false,
+            wireframe: false,
+            debug_collision: false,
+            minimap_enabled: false,
+            minimap_reveal: MinimapReveal::Full,
+            render_distance: 10,
+            show_fps: false,
+            textures_dir: None,
+            wall_variety: 1,
+            rooms: 0,
+            weave_crossings: 0,
+            decoy_exits: 0,
+            gamma: 1.0,
+            no_ceiling: false,
+            wall_height: 1.0,
+            sky_color: DEFAULT_SKY_COLOR,
+            skybox_dir: None,
+            wall_tint: [1.0; 3],
+            floor_tint: [1.0; 3],
+            ceiling_tint: [1.0; 3],
+            fov: 45.0,
+            zoom_factor: 2.0,
+            near_plane: 0.1,
+            far_plane: 0.0,
+            gpu_index: None,
+            list_gpus: false,
+            print_version: false,
+            debug_enabled: false,
+            verbose: false,
+            normal_maps: false,
+            shadows: false,
+            post_effect: None,
+            light_radius: 8.0,
+            torch_flicker: false,
+            flicker_amplitude: 0.15,
+            exit_light_color: DEFAULT_EXIT_LIGHT_COLOR
+        }
+    }
+}
+
+//Layout of config file, options are stored in [config] table
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    version: u32,
+    config: ProgramConfig
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            config: ProgramConfig::default()
+        }
+    }
+}
+
+//Same layout as ConfigFile, used for writing config without copying it
+#[derive(Serialize)]
+struct ConfigFileRef<'a> {
+    version: u32,
+    config: &'a ProgramConfig
+}
+
+//Parse color in "r,g,b" format with components in 0.0 - 1.0 range
+pub fn parse_color(value: &str) -> Option<[f32; 3]> {
+    let components: Vec<f32> = value.split(',').filter_map(|component| component.trim().parse::<f32>().ok()).collect();
+
+    if components.len() != 3 || components.iter().any(|component| !(0.0..=1.0).contains(component)) {
+        return None;
+    }
+
+    Some([components[0], components[1], components[2]])
+}
+
+//Parse color in hex "RRGGBB" format, "#" prefix is optional
+pub fn parse_hex_color(value: &str) -> Option<[f32; 3]> {
+    let hex = value.trim().trim_start_matches('#');
+
+    if hex.len() != 6 || !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok().map(|value| value as f32 / 255.0);
+
+    Some([component(0)?, component(2)?, component(4)?])
+}
+
+pub fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    match value.trim().to_lowercase().as_str() {
+        "easy" => Some(Difficulty::Easy),
+        "normal" => Some(Difficulty::Normal),
+        "hard" => Some(Difficulty::Hard),
+        "nightmare" => Some(Difficulty::Nightmare),
+        _ => None
+    }
+}
+
+//Set maze size, generator, rooms, weave crossings and decoy exits from difficulty preset
+//Rooms add loops to the maze (there is no braiding) while weave crossings make it harder to keep track of position
+pub fn apply_difficulty(config: &mut ProgramConfig, difficulty: Difficulty) {
+    (config.maze_size, config.selected_generator, config.rooms, config.weave_crossings, config.decoy_exits) = match difficulty {
+        Difficulty::Easy => (15, SelectedGenerator::RD, 4, 0, 0),
+        Difficulty::Normal => (20, SelectedGenerator::RD, 0, 0, 0),
+        Difficulty::Hard => (40, SelectedGenerator::Eller, 0, 10, 2),
+        Difficulty::Nightmare => (80, SelectedGenerator::Wilson, 0, 20, 5)
+    };
+
+    config.difficulty = Some(difficulty);
+}
+
+//Load config from TOML file
+//If it doesn't exist it's created, from INI file in the same directory if there is one or with default values otherwise
+//Files with .ini extension (presets in old format) are read as INI and never created or written
+pub fn load_config_file(config_path: &Path) -> ProgramConfig {
+    if is_ini_file(config_path) {
+        return match load_ini_config(config_path) {
+            Ok(config) => config,
+            Err(error) => {
+                println!("Warning: Couldn't load config file {} ({}), using default options.", config_path.display(), error);
+                ProgramConfig::default()
+            }
+        };
+    }
+
+    if config_path.exists() {
+        return match read_config_file(config_path) {
+            Ok(config) => config,
+            Err(error) => {
+                println!("Warning: Couldn't load config file {} ({}), using default options.", config_path.display(), error);
+                ProgramConfig::default()
+            }
+        };
+    }
+
+    let ini_path = config_path.with_extension("ini");
+
+    let config = match ini_path.exists() {
+        true => match load_ini_config(&ini_path) {
+            Ok(config) => {
+                println!("Migrating config file {} to {}.", ini_path.display(), config_path.display());
+                config
+            },
+            Err(error) => {
+                println!("Warning: Couldn't load old config file {} ({}), using default options.", ini_path.display(), error);
+                ProgramConfig::default()
+            }
+        },
+        false => ProgramConfig::default()
+    };
+
+    if let Err(error) = write_config_file(config_path, &config) {
+        println!("Warning: Couldn't write config file {}: {}", config_path.display(), error);
+    }
+
+    config
+}
+
+pub fn is_ini_file(config_path: &Path) -> bool {
+    config_path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("ini"))
+}
+
+//Write window size and position to config file, other options are kept as they are in file
+pub fn save_window_geometry(config_path: &Path, window_size: (u32, u32), window_position: Option<(i32, i32)>) -> Result<(), Box<dyn Error>> {
+    let mut config = read_config_file(config_path)?;
+
+    (config.window_width, config.window_height) = window_size;
+
+    if window_position.is_some() {
+        config.window_position = window_position;
+    }
+
+    write_config_file(config_path, &config)
+}
+
+fn read_config_file(config_path: &Path) -> Result<ProgramConfig, Box<dyn Error>> {
+    let config_file: ConfigFile = toml::from_str(&fs::read_to_string(config_path)?)?;
+
+    if config_file.version > CONFIG_VERSION {
+        println!("Warning: Config file {} was created by newer version, some options may be ignored.", config_path.display());
+    }
+
+    Ok(config_file.config)
+}
+
+fn write_config_file(config_path: &Path, config: &ProgramConfig) -> Result<(), Box<dyn Error>> {
+    let config_file = toml::to_string_pretty(&ConfigFileRef {
+        version: CONFIG_VERSION,
+        config
+    })?;
+
+    fs::write(config_path, config_file)?;
+
+    Ok(())
+}
+
+//Read options from INI config file used by older versions
+//Keys were added over time so every one of them can be missing
+fn load_ini_config(ini_path: &Path) -> Result<ProgramConfig, Box<dyn Error>> {
+    let mut config = ProgramConfig::default();
+
+    let conf = Ini::load_from_file(ini_path)?;
+    let section = conf.section(Some("Config")).ok_or("Config section is missing")?;
+
+    let get_flag = |key: &str| section.get(key).map(|value| value == "1");
+
+    if let Some(fullscreen) = get_flag("Fullscreen") {
+        config.set_fullscreen = fullscreen;
+    }
+
+    if let Some(window_width) = get_ini_value(section, "Width") {
+        config.window_width = window_width;
+    }
+
+    if let Some(window_height) = get_ini_value(section, "Height") {
+        config.window_height = window_height;
+    }
+
+    if let (Some(position_x), Some(position_y)) = (get_ini_value(section, "PositionX"), get_ini_value(section, "PositionY")) {
+        config.window_position = Some((position_x, position_y));
+    }
+
+    if let Some(maze_size) = get_ini_value(section, "Size") {
+        config.maze_size = maze_size;
+    }
+
+    if let Some(generator) = section.get("Generator") {
+        config.selected_generator = match generator {
+            "DFS" => SelectedGenerator::DFS,
+            "Eller" => SelectedGenerator::Eller,
+            "Wilson" => SelectedGenerator::Wilson,
+            _ => SelectedGenerator::RD
+        };
+    }
+
+    if let Some(rendering_api) = section.get("RenderingAPI") {
+        config.rendering_api = match rendering_api {
+            "Vulkan" => RenderingAPI::VULKAN,
+            _ => RenderingAPI::OPENGL
+        };
+    }
+
+    if let Some(enable_collisions) = get_flag("Collisions") {
+        config.enable_collisions = enable_collisions;
+    }
+
+    if let Some(mouse_enabled) = get_flag("Mouse") {
+        config.mouse_enabled = mouse_enabled;
+    }
+
+    if let Some(audio_enabled) = get_flag("Audio") {
+        config.audio_enabled = audio_enabled;
+    }
+
+    if let Some(gamepad_enabled) = get_flag("Gamepad") {
+        config.gamepad_enabled = gamepad_enabled;
+    }
+
+    if let Some(vsync_enabled) = get_flag("VSync") {
+        config.vsync_enabled = vsync_enabled;
+    }
+
+    if let Some(msaa_samples) = get_ini_value(section, "MSAA") {
+        config.msaa_samples = msaa_samples;
+    }
+
+    if let Some(render_distance) = get_ini_value(section, "RenderDistance") {
+        config.render_distance = render_distance;
+    }
+
+    if let Some(wall_variety) = get_ini_value(section, "WallVariety") {
+        config.wall_variety = wall_variety;
+    }
+
+    if let Some(rooms) = get_ini_value(section, "Rooms") {
+        config.rooms = rooms;
+    }
+
+    if let Some(gamma) = get_ini_value(section, "Gamma") {
+        config.gamma = gamma;
+    }
+
+    if let Some(fov) = get_ini_value(section, "Fov") {
+        config.fov = fov;
+    }
+
+    if let Some(near_plane) = get_ini_value(section, "NearPlane") {
+        config.near_plane = near_plane;
+    }
+
+    if let Some(far_plane) = get_ini_value(section, "FarPlane") {
+        config.far_plane = far_plane;
+    }
+
+    if let Some(no_ceiling) = get_flag("NoCeiling") {
+        config.no_ceiling = no_ceiling;
+    }
+
+    if let Some(wall_height) = get_ini_value(section, "WallHeight") {
+        config.wall_height = wall_height;
+    }
+
+    if let Some(normal_maps) = get_flag("NormalMaps") {
+        config.normal_maps = normal_maps;
+    }
+
+    if let Some(shadows) = get_flag("Shadows") {
+        config.shadows = shadows;
+    }
+
+    if let Some(light_radius) = get_ini_value(section, "LightRadius") {
+        config.light_radius = light_radius;
+    }
+
+    if let Some(torch_flicker) = get_flag("TorchFlicker") {
+        config.torch_flicker = torch_flicker;
+    }
+
+    if let Some(flicker_amplitude) = get_ini_value(section, "FlickerAmplitude") {
+        config.flicker_amplitude = flicker_amplitude;
+    }
+
+    if let Some(sky_color) = section.get("SkyColor").and_then(parse_color) {
+        config.sky_color = sky_color;
+    }
+
+    if let Some(exit_light_color) = section.get("ExitLightColor").and_then(parse_color) {
+        config.exit_light_color = exit_light_color;
+    }
+
+    Ok(config)
+}
+
+//Value of INI key, None if it's missing or can't be parsed
+fn get_ini_value<T: FromStr>(section: &Properties, key: &str) -> Option<T> {
+    section.get(key).and_then(|value| value.parse::<T>().ok())
+}
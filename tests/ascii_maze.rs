@@ -0,0 +1,73 @@
+//Maze drawn as text is loaded with start, exit and exit border taken from it
+use dsdmaze::maze_generator::{Direction, MazeGenerator, SelectedGenerator};
+
+const MAZE: &str = "#######\n#S    #\n##### #\n#     #\n# #####\n#    E#\n#######\n";
+
+#[test]
+fn text_maze_is_loaded() {
+    let maze_generator = MazeGenerator::from_ascii(MAZE).expect("Text maze can't be loaded");
+
+    assert_eq!(maze_generator.get_maze_size(), 7);
+    assert_eq!((maze_generator.get_start_position().0, maze_generator.get_start_position().1), (1, 1));
+    assert_eq!((maze_generator.get_exit().0, maze_generator.get_exit().1), (5, 5));
+    assert!(maze_generator.get_end_border() == Direction::Bottom);
+
+    //Exit hole is carved in the border, other fields are like in text
+    let hole = maze_generator.get_exit_hole();
+    assert_eq!((hole.0, hole.1), (5, 6));
+
+    for (y, line) in MAZE.lines().enumerate() {
+        for (x, field) in line.chars().enumerate() {
+            assert_eq!(maze_generator.is_wall(x, y), field == '#' && (x, y) != (5, 6));
+        }
+    }
+}
+
+#[test]
+fn open_exit_hole_is_accepted() {
+    let maze_generator = MazeGenerator::from_ascii("#####\n#S  #\n#   #\n#  E \n#####").expect("Text maze with open exit hole can't be loaded");
+    let hole = maze_generator.get_exit_hole();
+
+    assert!(maze_generator.get_end_border() == Direction::Right);
+    assert_eq!((hole.0, hole.1), (4, 3));
+    assert!(!maze_generator.is_wall(4, 3));
+}
+
+#[test]
+fn text_dump_is_loaded_back() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::DFS, 21, String::from("ascii"));
+    maze_generator.generate_maze();
+
+    let text = maze_generator.to_ascii();
+    let loaded = MazeGenerator::from_ascii(&text).expect("Text dump can't be loaded");
+
+    assert_eq!(loaded.get_maze_size(), maze_generator.get_maze_size());
+    assert_eq!((loaded.get_start_position().0, loaded.get_start_position().1), (maze_generator.get_start_position().0, maze_generator.get_start_position().1));
+    assert_eq!((loaded.get_exit().0, loaded.get_exit().1), (maze_generator.get_exit().0, maze_generator.get_exit().1));
+    assert!(loaded.get_end_border() == maze_generator.get_end_border());
+    assert!(loaded.cells().eq(maze_generator.cells()));
+    assert_eq!(loaded.to_ascii(), text);
+}
+
+#[test]
+fn windows_line_endings_are_accepted() {
+    assert!(MazeGenerator::from_ascii(&MAZE.replace('\n', "\r\n")).is_ok());
+}
+
+#[test]
+fn invalid_text_mazes_are_rejected() {
+    let invalid_mazes = [
+        "#####\n#S  #\n#  #\n# E#\n#####",       //Row is shorter than others
+        "######\n#S  E#\n######",                 //Not square
+        "#####\n#S S#\n#   #\n#  E#\n#####",     //Two starts
+        "#####\n#S  #\n#   #\n#   #\n#####",     //No exit
+        "#####\n#S  #\n# E #\n#   #\n#####",     //Exit isn't next to border
+        "#####\n#S  #\n#    \n#  E#\n#####",     //Border isn't closed
+        "#####\n#S  #\n#   #\n#  E \n### #",     //Exit has two holes
+        "#####\n#S  #\n# x #\n#  E#\n#####"      //Unknown field
+    ];
+
+    for maze in invalid_mazes {
+        assert!(MazeGenerator::from_ascii(maze).is_err(), "Invalid maze was loaded:\n{}", maze);
+    }
+}
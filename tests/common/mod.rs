@@ -0,0 +1,54 @@
+//Helpers shared by integration tests, every test file uses only some of them
+#![allow(dead_code)]
+
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+pub const GENERATORS: [SelectedGenerator; 4] = [SelectedGenerator::DFS, SelectedGenerator::RD, SelectedGenerator::Eller, SelectedGenerator::Wilson];
+
+pub fn create_maze(selected_generator: SelectedGenerator, maze_size: usize, seed: &str) -> MazeGenerator {
+    create_maze_with(selected_generator, maze_size, seed, |_| ())
+}
+
+//Generation options are set before maze is generated
+pub fn create_maze_with(selected_generator: SelectedGenerator, maze_size: usize, seed: &str, setup: impl FnOnce(&mut MazeGenerator)) -> MazeGenerator {
+    let mut maze_generator = MazeGenerator::new(selected_generator, maze_size, String::from(seed));
+    setup(&mut maze_generator);
+    maze_generator.generate_maze();
+
+    maze_generator
+}
+
+//Every maze field is empty, wall corners are walls and fields in between are either wall or passage
+//Connected maze without loops is a tree so it has one passage less than fields, connectivity itself is checked by solvability tests
+pub fn assert_perfect_maze(maze_generator: &MazeGenerator) {
+    let maze_size = maze_generator.get_maze_size();
+    let maze_fields = (maze_size - 1) / 2;
+    let mut passages = 0;
+
+    for y in 1..(maze_size - 1) {
+        for x in 1..(maze_size - 1) {
+            let is_field = x % 2 == 1 && y % 2 == 1;
+            let is_corner = x % 2 == 0 && y % 2 == 0;
+
+            if is_field {
+                assert!(!maze_generator.is_wall(x, y), "Maze field {}x{} is wall (size {})", x, y, maze_size);
+            }
+            else if is_corner {
+                assert!(maze_generator.is_wall(x, y), "Wall corner {}x{} is empty (size {})", x, y, maze_size);
+            }
+            else if !maze_generator.is_wall(x, y) {
+                passages += 1;
+            }
+        }
+    }
+
+    assert_eq!(passages, maze_fields * maze_fields - 1, "Maze of size {} isn't perfect", maze_size);
+}
+
+//Whole maze including border, start, exit and its border has to be the same
+pub fn assert_same_maze(first: &MazeGenerator, second: &MazeGenerator) {
+    assert!(first.cells().eq(second.cells()), "Maze layouts differ");
+    assert_eq!((first.get_start_position().0, first.get_start_position().1), (second.get_start_position().0, second.get_start_position().1));
+    assert_eq!((first.get_exit().0, first.get_exit().1), (second.get_exit().0, second.get_exit().1));
+    assert!(first.get_end_border() == second.get_end_border(), "Exit borders differ");
+}
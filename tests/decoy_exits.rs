@@ -0,0 +1,69 @@
+//Decoy exits are border walls next to empty fields, they don't change maze layout or the real exit
+use dsdmaze::maze_generator::{Direction, MazeGenerator, SelectedGenerator};
+
+mod common;
+
+#[test]
+fn decoys_are_on_border() {
+    for selected_generator in common::GENERATORS {
+        let mut maze_generator = common::create_maze(selected_generator, 31, "decoys");
+        maze_generator.add_decoy_exits(5);
+
+        let maze_size = maze_generator.get_maze_size();
+        let exit = maze_generator.get_exit();
+
+        assert_eq!(maze_generator.get_decoy_exits().len(), 5);
+
+        for &(position, border) in maze_generator.get_decoy_exits() {
+            let (x, y) = (position.0 as usize, position.1 as usize);
+
+            let (border_x, border_y) = match border {
+                Direction::Top => (x, y - 1),
+                Direction::Bottom => (x, y + 1),
+                Direction::Left => (x - 1, y),
+                Direction::Right => (x + 1, y)
+            };
+
+            assert!(!maze_generator.is_wall(x, y));
+            assert!(maze_generator.is_wall(border_x, border_y));
+            assert!(border_x == 0 || border_y == 0 || border_x == maze_size - 1 || border_y == maze_size - 1);
+            assert!((x, y) != (exit.0 as usize, exit.1 as usize) || border != maze_generator.get_end_border());
+        }
+    }
+}
+
+#[test]
+fn decoys_keep_maze_layout() {
+    let mut maze_generator = common::create_maze(SelectedGenerator::RD, 31, "layout");
+    let cells: Vec<(usize, usize, bool)> = maze_generator.cells().collect();
+    let shortest_path_length = maze_generator.get_shortest_path_length();
+
+    maze_generator.add_decoy_exits(10);
+
+    assert!(maze_generator.cells().eq(cells));
+    assert_eq!(maze_generator.get_shortest_path_length(), shortest_path_length);
+}
+
+#[test]
+fn decoys_are_saved_with_maze() {
+    let mut maze_generator = common::create_maze(SelectedGenerator::Eller, 31, "saved");
+    maze_generator.add_decoy_exits(3);
+
+    let loaded_generator = MazeGenerator::from_maze(maze_generator.to_maze()).unwrap();
+
+    assert_eq!(loaded_generator.get_decoy_exits().len(), 3);
+
+    for &(position, border) in maze_generator.get_decoy_exits() {
+        assert!(loaded_generator.is_decoy_exit(position.0 as usize, position.1 as usize, border));
+    }
+}
+
+#[test]
+fn small_maze_has_fewer_decoys() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::RD, 5, String::from("small"));
+    maze_generator.generate_maze();
+    maze_generator.add_decoy_exits(100);
+
+    //Border of 5x5 maze has 3 fields on every side, one of them is the real exit
+    assert!(maze_generator.get_decoy_exits().len() < 12);
+}
@@ -0,0 +1,51 @@
+//Game ends only when player gets close to center of exit field, not when it's near the exit field or in the border hole
+use dsdmaze::maze_generator::SelectedGenerator;
+
+mod common;
+
+//Default collision margin
+const COLLISION_MARGIN: f32 = 0.7;
+
+#[test]
+fn exit_reached_at_center() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "exit");
+    let exit = maze_generator.get_exit();
+    let (exit_x, exit_z) = (exit.0 as f32, exit.1 as f32);
+
+    assert!(maze_generator.is_exit_reached(exit_x, exit_z, COLLISION_MARGIN));
+    assert!(maze_generator.is_exit_reached(exit_x + 0.34, exit_z - 0.34, COLLISION_MARGIN));
+}
+
+#[test]
+fn exit_not_reached_near_exit_field() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "exit");
+    let exit = maze_generator.get_exit();
+    let (exit_x, exit_z) = (exit.0 as f32, exit.1 as f32);
+
+    //Old trigger fired anywhere within 0.7 of the center, so even from neighbouring field
+    assert!(!maze_generator.is_exit_reached(exit_x + 0.5, exit_z, COLLISION_MARGIN));
+    assert!(!maze_generator.is_exit_reached(exit_x, exit_z - 0.69, COLLISION_MARGIN));
+    assert!(!maze_generator.is_exit_reached(exit_x + 0.36, exit_z, COLLISION_MARGIN));
+}
+
+#[test]
+fn walking_into_exit_reaches_it() {
+    for seed in ["exit", "hole", "border"] {
+        let maze_generator = common::create_maze(SelectedGenerator::RD, 21, seed);
+        let exit = maze_generator.get_exit();
+        let hole = maze_generator.get_exit_hole();
+
+        //Hole is empty border field next to exit
+        assert!(!maze_generator.is_wall(hole.0 as usize, hole.1 as usize));
+        assert_eq!((exit.0 as i64 - hole.0 as i64).abs() + (exit.1 as i64 - hole.1 as i64).abs(), 1);
+
+        //Collisions stop player at collision margin from center of blocked hole
+        for collision_margin in [0.55, COLLISION_MARGIN, 0.95] {
+            let stop_x = exit.0 as f32 + (hole.0 as f32 - exit.0 as f32) * (1.0 - collision_margin);
+            let stop_z = exit.1 as f32 + (hole.1 as f32 - exit.1 as f32) * (1.0 - collision_margin);
+
+            assert!(maze_generator.is_exit_reached(stop_x, stop_z, collision_margin));
+            assert!(!maze_generator.is_exit_reached(hole.0 as f32, hole.1 as f32, collision_margin));
+        }
+    }
+}
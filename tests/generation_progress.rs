@@ -0,0 +1,54 @@
+//Generation progress has to grow from 0 to 1 and reporting it can't change generated maze
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+mod common;
+
+fn generate_with_progress(generator: SelectedGenerator, maze_size: usize, threads: usize) -> (MazeGenerator, Vec<f32>) {
+    let mut maze_generator = MazeGenerator::new(generator, maze_size, String::from("progress"));
+    let mut reports = Vec::new();
+
+    maze_generator.set_threads(threads);
+    maze_generator.generate_maze_with_progress(&mut |progress| reports.push(progress));
+
+    (maze_generator, reports)
+}
+
+fn check_reports(reports: &[f32], name: &str) {
+    assert!(reports.len() > 10, "{} reported progress only {} times", name, reports.len());
+    assert!(reports.iter().all(|progress| (0.0..=1.0).contains(progress)), "{} reported progress outside of 0 - 1", name);
+    assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]), "{} progress went back", name);
+    assert_eq!(reports.last(), Some(&1.0), "{} didn't finish with 1", name);
+}
+
+#[test]
+fn progress_grows_to_one() {
+    for generator in common::GENERATORS {
+        let (_, reports) = generate_with_progress(generator, 201, 1);
+
+        check_reports(&reports, &generator.to_string());
+    }
+}
+
+#[test]
+fn parallel_progress_grows_to_one() {
+    //Parallel progress is sampled from separate thread so maze has to be big enough to be checked few times
+    let (_, reports) = generate_with_progress(SelectedGenerator::RD, 4001, 4);
+
+    assert!(reports.iter().all(|progress| (0.0..=1.0).contains(progress)));
+    assert!(reports.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(reports.last(), Some(&1.0));
+}
+
+#[test]
+fn progress_doesnt_change_maze() {
+    for generator in common::GENERATORS {
+        let (with_progress, _) = generate_with_progress(generator, 101, 1);
+
+        let mut without_progress = MazeGenerator::new(generator, 101, String::from("progress"));
+        without_progress.generate_maze();
+
+        let cells = |maze_generator: &MazeGenerator| -> Vec<bool> { maze_generator.cells().map(|(_, _, is_wall)| is_wall).collect() };
+
+        assert!(cells(&with_progress) == cells(&without_progress), "{} maze changed with progress callback", generator);
+    }
+}
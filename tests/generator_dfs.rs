@@ -0,0 +1,36 @@
+//DFS generator used to be recursive and overflowed stack on big mazes
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+mod common;
+
+#[test]
+fn generates_large_maze() {
+    let maze_size = 2000;
+
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::DFS, maze_size, String::from("large"));
+    maze_generator.generate_maze();
+
+    assert_eq!(maze_generator.get_maze_size(), maze_size);
+    assert_eq!(maze_generator.cells().count(), maze_size * maze_size);
+
+    //Border has to stay intact except for exit hole
+    let border_holes = maze_generator.cells()
+        .filter(|&(x, y, is_wall)| !is_wall && (x == 0 || y == 0 || x == maze_size - 1 || y == maze_size - 1))
+        .count();
+    assert_eq!(border_holes, 1);
+
+    //Start position has to be on empty field
+    let start = maze_generator.get_start_position();
+    assert!(!maze_generator.is_wall(start.0 as usize, start.1 as usize));
+}
+
+#[test]
+fn same_seed_gives_same_maze() {
+    let mut first = MazeGenerator::new(SelectedGenerator::DFS, 101, String::from("seed"));
+    let mut second = MazeGenerator::new(SelectedGenerator::DFS, 101, String::from("seed"));
+    first.generate_maze();
+    second.generate_maze();
+
+    //Exit is placed with seeded random engine too so whole maze is compared
+    common::assert_same_maze(&first, &second);
+}
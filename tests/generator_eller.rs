@@ -0,0 +1,25 @@
+//Eller generator has to create perfect maze (every field reachable by exactly one path)
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+mod common;
+
+#[test]
+fn generates_perfect_maze() {
+    for maze_size in [5, 11, 51, 201] {
+        let mut maze_generator = MazeGenerator::new(SelectedGenerator::Eller, maze_size, String::from("eller"));
+        maze_generator.generate_maze();
+
+        common::assert_perfect_maze(&maze_generator);
+    }
+}
+
+#[test]
+fn same_seed_gives_same_maze() {
+    let mut first = MazeGenerator::new(SelectedGenerator::Eller, 101, String::from("seed"));
+    let mut second = MazeGenerator::new(SelectedGenerator::Eller, 101, String::from("seed"));
+    first.generate_maze();
+    second.generate_maze();
+
+    //Exit is placed with seeded random engine too so whole maze is compared
+    common::assert_same_maze(&first, &second);
+}
@@ -0,0 +1,57 @@
+//Parallel RD generator has to create perfect maze which doesn't depend on number of threads
+//Serial generation (one thread) has to stay the same as before parallel version was added
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+mod common;
+
+//Serial RD maze generated with seed "serial", exit hole is open in the border
+const SERIAL_MAZE: &str = "\
+###############\n\
+#   #   # #   #\n\
+# # ### # ### #\n\
+# # #   #     #\n\
+### ### # ### #\n\
+#         #   #\n\
+### ###########\n\
+# #   #     # #\n\
+# ### ### ### #\n\
+# # S #     # #\n\
+# ### # ### # #\n\
+#     #   # # #\n\
+# ### ### ### #\n\
+# #          E#\n\
+############# #\n";
+
+fn generate(maze_size: usize, seed: &str, threads: usize) -> MazeGenerator {
+    common::create_maze_with(SelectedGenerator::RD, maze_size, seed, |maze_generator| maze_generator.set_threads(threads))
+}
+
+fn cells(maze_generator: &MazeGenerator) -> Vec<bool> {
+    maze_generator.cells().map(|(_, _, is_wall)| is_wall).collect()
+}
+
+#[test]
+fn generates_perfect_maze() {
+    for maze_size in [5, 11, 201, 501] {
+        common::assert_perfect_maze(&generate(maze_size, "parallel", 4));
+    }
+}
+
+#[test]
+fn thread_count_doesnt_change_maze() {
+    let reference = generate(501, "threads", 2);
+
+    for threads in [3, 8] {
+        let maze_generator = generate(501, "threads", threads);
+
+        assert!(cells(&reference) == cells(&maze_generator), "Maze generated on {} threads differs from maze generated on 2 threads", threads);
+        assert_eq!(reference.get_start_position().0, maze_generator.get_start_position().0);
+        assert_eq!(reference.get_start_position().1, maze_generator.get_start_position().1);
+    }
+}
+
+#[test]
+fn one_thread_uses_serial_generator() {
+    assert_eq!(generate(15, "serial", 1).to_ascii(), SERIAL_MAZE);
+    assert_ne!(generate(15, "serial", 2).to_ascii(), SERIAL_MAZE, "Parallel maze should use its own random engines");
+}
@@ -0,0 +1,61 @@
+//Wilson generator is based on random walks, it has to finish and connect every maze field for any seed
+use std::collections::VecDeque;
+
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+const SEEDS: [&str; 6] = ["", "wilson", "12345", "loop erased", "uniform", "Seed with spaces"];
+const SIZES: [usize; 4] = [5, 11, 31, 101];
+
+//Number of maze fields (odd indices) reachable from first field
+fn count_reachable_fields(maze_array: &[bool], maze_size: usize) -> usize {
+    let mut visited = vec![false; maze_size * maze_size];
+    let mut queue = VecDeque::new();
+    let mut reachable_fields = 0;
+
+    visited[maze_size + 1] = true;
+    queue.push_back((1, 1));
+
+    while let Some((x, y)) = queue.pop_front() {
+        if x % 2 == 1 && y % 2 == 1 {
+            reachable_fields += 1;
+        }
+
+        //Border is never empty inside generator output so neighbours can't leave array
+        for (neighbour_x, neighbour_y) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            let index = neighbour_y * maze_size + neighbour_x;
+
+            if !maze_array[index] && !visited[index] {
+                visited[index] = true;
+                queue.push_back((neighbour_x, neighbour_y));
+            }
+        }
+    }
+
+    reachable_fields
+}
+
+#[test]
+fn generates_connected_maze() {
+    for seed in SEEDS {
+        for maze_size in SIZES {
+            let mut maze_generator = MazeGenerator::new(SelectedGenerator::Wilson, maze_size, String::from(seed));
+            maze_generator.generate_maze();
+
+            let maze_size = maze_generator.get_maze_size();
+            let maze_fields = (maze_size - 1) / 2;
+
+            //Exit hole is made in border so it's filled again for the search
+            let mut maze_array: Vec<bool> = maze_generator.cells().map(|(_, _, is_wall)| is_wall).collect();
+
+            for n in 0..maze_size {
+                maze_array[n] = true;
+                maze_array[n * maze_size] = true;
+                maze_array[n * maze_size + maze_size - 1] = true;
+                maze_array[(maze_size - 1) * maze_size + n] = true;
+            }
+
+            assert_eq!(count_reachable_fields(&maze_array, maze_size), maze_fields * maze_fields,
+                "Not every field is connected (seed \"{}\", size {})", seed, maze_size);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//Maze cells are read only through accessors, saved maze keeps the same flat array layout
+use dsdmaze::maze_generator::{Direction, MazeGenerator, SelectedGenerator, MAX_WALL_MATERIALS};
+
+#[test]
+fn cells_match_is_wall() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::RD, 21, String::from("cells"));
+    maze_generator.generate_maze();
+
+    let maze_size = maze_generator.get_maze_size();
+    let cells: Vec<_> = maze_generator.cells().collect();
+
+    assert_eq!(cells.len(), maze_size * maze_size);
+
+    for (index, &(x, y, is_wall)) in cells.iter().enumerate() {
+        assert_eq!((x, y), (index % maze_size, index / maze_size), "Cells aren't ordered row by row");
+        assert_eq!(is_wall, maze_generator.is_wall(x, y));
+    }
+}
+
+#[test]
+fn saved_maze_keeps_layout() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::Wilson, 31, String::from("saved"));
+    maze_generator.generate_maze();
+
+    let maze = maze_generator.to_maze();
+    assert!(maze_generator.cells().all(|(x, y, is_wall)| maze.maze_array[y * maze.maze_size + x] == is_wall));
+
+    let loaded = MazeGenerator::from_maze(maze).expect("Saved maze can't be loaded");
+    assert!(maze_generator.cells().eq(loaded.cells()));
+}
+
+#[test]
+fn broken_saved_maze_is_rejected() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::RD, 21, String::from("broken"));
+    maze_generator.generate_maze();
+    maze_generator.assign_wall_materials(4);
+
+    let mut maze = maze_generator.to_maze();
+    maze.wall_materials[0] = MAX_WALL_MATERIALS as u8;
+    assert!(MazeGenerator::from_maze(maze).is_err(), "Wall material without texture was loaded");
+
+    let mut maze = maze_generator.to_maze();
+    maze.maze_size = usize::MAX;
+    assert!(MazeGenerator::from_maze(maze).is_err(), "Overflowing maze size was loaded");
+
+    //Exit moved to other border keeps its old border
+    let mut maze = maze_generator.to_maze();
+    maze.end_border = match maze.end_border {
+        Direction::Top => Direction::Bottom,
+        Direction::Bottom => Direction::Top,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left
+    };
+    assert!(MazeGenerator::from_maze(maze).is_err(), "Exit which isn't next to its border was loaded");
+}
+
+#[test]
+fn saved_maze_with_open_border_is_rejected() {
+    let maze_generator = MazeGenerator::from_ascii("#######\n#  S  #\n# ### #\n#    E#\n# ### #\n#     #\n#######").unwrap();
+
+    //Exit hole alone is fine
+    assert!(MazeGenerator::from_maze(maze_generator.to_maze()).is_ok());
+
+    //Opening in top border above start would let player and shortest path get outside of maze
+    let mut maze = maze_generator.to_maze();
+    maze.maze_array[3] = false;
+    assert!(MazeGenerator::from_maze(maze).is_err(), "Maze with open border was loaded");
+}
+
+#[test]
+fn wall_materials_are_limited_to_variety() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::RD, 101, String::from("materials"));
+    maze_generator.generate_maze();
+    maze_generator.assign_wall_materials(4);
+
+    let mut loaded = MazeGenerator::from_maze(maze_generator.to_maze()).unwrap();
+
+    assert!(loaded.limit_wall_materials(2));
+    assert!(!loaded.limit_wall_materials(2));
+
+    for (x, y, _) in maze_generator.cells() {
+        let expected = match maze_generator.get_wall_material(x, y) {
+            0 | 1 => maze_generator.get_wall_material(x, y),
+            _ => 0
+        };
+
+        assert_eq!(loaded.get_wall_material(x, y), expected);
+    }
+}
@@ -0,0 +1,53 @@
+//Area around player used for rendering, minimap and collisions has to stay inside maze
+//Cells outside of maze are walls so lookups at the border never panic
+use dsdmaze::maze_generator::SelectedGenerator;
+
+mod common;
+
+#[test]
+fn outside_of_maze_is_wall() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "area");
+    let maze_size = maze_generator.get_maze_size();
+
+    for n in 0..maze_size {
+        assert!(maze_generator.is_wall(maze_size, n));
+        assert!(maze_generator.is_wall(n, maze_size));
+        assert!(maze_generator.is_wall(usize::MAX, n));
+        assert!(maze_generator.is_wall(n, 0usize.wrapping_sub(1)));
+    }
+}
+
+#[test]
+fn area_inside_maze() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "area");
+
+    assert_eq!(maze_generator.get_area(10.0, 10.0, 3), (7..14, 7..14));
+
+    //Point is rounded to the cell containing it, cells span half a cell around their centers
+    assert_eq!(maze_generator.get_area(10.4, 9.6, 3), (7..14, 7..14));
+    assert_eq!(maze_generator.get_area(10.6, 9.4, 3), (8..15, 6..13));
+}
+
+#[test]
+fn area_clamped_at_border() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "area");
+
+    assert_eq!(maze_generator.get_area(1.0, 19.0, 5), (0..7, 14..21));
+    assert_eq!(maze_generator.get_area(0.0, 20.0, 100), (0..21, 0..21));
+}
+
+#[test]
+fn area_outside_of_maze_is_empty() {
+    let maze_generator = common::create_maze(SelectedGenerator::RD, 21, "area");
+
+    //Player can walk out through exit hole or fly anywhere with noclip
+    for (x, y) in [(-50.0, 10.0), (10.0, -50.0), (80.0, 10.0), (10.0, 80.0), (f32::NAN, 10.0)] {
+        let (columns, rows) = maze_generator.get_area(x, y, 10);
+
+        assert!(columns.is_empty() || rows.is_empty(), "Area around {}x{} isn't empty", x, y);
+        assert!(columns.end <= 21 && rows.end <= 21);
+    }
+
+    //Area partly outside still contains cells on the border
+    assert_eq!(maze_generator.get_area(-3.0, 10.0, 5), (0..3, 5..16));
+}
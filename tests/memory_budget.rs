@@ -0,0 +1,42 @@
+//Mazes over memory budget are shrunk instead of allocating huge arrays
+use dsdmaze::maze_generator::{MazeGenerator, DEFAULT_MEMORY_BUDGET};
+
+mod common;
+
+#[test]
+fn maximum_size_fits_budget() {
+    for generator in common::GENERATORS {
+        for memory_budget in [1024, 1024 * 1024, DEFAULT_MEMORY_BUDGET] {
+            let maximum_size = generator.get_maximum_size(memory_budget);
+
+            assert!(generator.estimate_memory(maximum_size) <= memory_budget);
+            assert!(generator.estimate_memory(maximum_size + 1) > memory_budget);
+        }
+    }
+}
+
+#[test]
+fn estimate_doesnt_overflow() {
+    for generator in common::GENERATORS {
+        assert!(generator.estimate_memory(usize::MAX) >= generator.estimate_memory(100000));
+    }
+}
+
+#[test]
+fn maze_over_budget_is_shrunk() {
+    let memory_budget = 64 * 1024;
+
+    for generator in common::GENERATORS {
+        let mut maze_generator = MazeGenerator::new(generator, 100000, String::from("budget"));
+        maze_generator.set_memory_budget(memory_budget);
+        let warnings = maze_generator.generate_maze();
+
+        //Changed size is reported to caller instead of being printed
+        assert_eq!(warnings.len(), 1);
+
+        //Odd size needed by some generators can add one row and column
+        let maximum_size = generator.get_maximum_size(memory_budget);
+        assert!(maze_generator.get_maze_size() <= maximum_size + 1);
+        assert_eq!(maze_generator.cells().count(), maze_generator.get_maze_size() * maze_generator.get_maze_size());
+    }
+}
@@ -0,0 +1,33 @@
+//Maze is fully determined by seed and generation options, resumed sessions rely on it
+use dsdmaze::maze_generator::{MazeGenerator, SelectedGenerator};
+
+mod common;
+
+//Maze with every generation step applied after generator
+fn create_full_maze(selected_generator: SelectedGenerator, seed: &str) -> MazeGenerator {
+    let mut maze_generator = common::create_maze(selected_generator, 41, seed);
+    maze_generator.carve_rooms(3);
+    maze_generator.assign_wall_materials(4);
+    maze_generator.add_weave_crossings(5);
+    maze_generator.add_decoy_exits(3);
+
+    maze_generator
+}
+
+#[test]
+fn same_seed_gives_same_maze() {
+    for selected_generator in common::GENERATORS {
+        for seed in ["first", "second", "third"] {
+            let maze = create_full_maze(selected_generator, seed).to_maze();
+            let other_maze = create_full_maze(selected_generator, seed).to_maze();
+
+            assert_eq!(maze.maze_array, other_maze.maze_array);
+            assert_eq!((maze.start_position.0, maze.start_position.1), (other_maze.start_position.0, other_maze.start_position.1));
+            assert_eq!((maze.end_position.0, maze.end_position.1), (other_maze.end_position.0, other_maze.end_position.1));
+            assert!(maze.end_border == other_maze.end_border);
+            assert_eq!(maze.wall_materials, other_maze.wall_materials);
+            assert_eq!(maze.crossings.len(), other_maze.crossings.len());
+            assert_eq!(maze.decoy_exits.len(), other_maze.decoy_exits.len());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//Every generated maze has to be solvable
+//Exit has to be reachable from start position and exit hole has to lead to empty field inside maze
+use std::collections::VecDeque;
+
+use dsdmaze::maze_generator::{Direction, MazeGenerator};
+
+mod common;
+
+const SEEDS: [&str; 5] = ["", "dsdmaze", "12345", "solvable", "Seed with spaces"];
+const SIZES: [usize; 5] = [7, 10, 21, 50, 101];
+
+//Breadth first search from start position over empty fields
+fn is_reachable(maze_generator: &MazeGenerator, from: (usize, usize), to: (usize, usize)) -> bool {
+    let maze_size = maze_generator.get_maze_size();
+    let mut visited = vec![false; maze_size * maze_size];
+    let mut queue = VecDeque::new();
+
+    visited[from.1 * maze_size + from.0] = true;
+    queue.push_back(from);
+
+    while let Some((x, y)) = queue.pop_front() {
+        if (x, y) == to {
+            return true;
+        }
+
+        let neighbours = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+
+        for (neighbour_x, neighbour_y) in neighbours {
+            if neighbour_x >= maze_size || neighbour_y >= maze_size {
+                continue;
+            }
+
+            let index = neighbour_y * maze_size + neighbour_x;
+
+            if !maze_generator.is_wall(neighbour_x, neighbour_y) && !visited[index] {
+                visited[index] = true;
+                queue.push_back((neighbour_x, neighbour_y));
+            }
+        }
+    }
+
+    false
+}
+
+//Position of hole in the border next to exit field
+fn get_exit_hole(maze_generator: &MazeGenerator) -> (usize, usize) {
+    let exit = maze_generator.get_exit();
+    let (x, y) = (exit.0 as usize, exit.1 as usize);
+
+    match maze_generator.get_end_border() {
+        Direction::Top => (x, y - 1),
+        Direction::Bottom => (x, y + 1),
+        Direction::Left => (x - 1, y),
+        Direction::Right => (x + 1, y),
+    }
+}
+
+#[test]
+fn exit_is_reachable_from_start() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            for size in SIZES {
+                let mut maze_generator = MazeGenerator::new(generator, size, String::from(seed));
+                maze_generator.generate_maze();
+
+                let start = maze_generator.get_start_position();
+                let exit = maze_generator.get_exit();
+
+                assert!(is_reachable(&maze_generator, (start.0 as usize, start.1 as usize), (exit.0 as usize, exit.1 as usize)),
+                    "Exit not reachable in {} maze of size {} with seed \"{}\"", generator, size, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn exit_hole_leads_inside_maze() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            for size in SIZES {
+                let mut maze_generator = MazeGenerator::new(generator, size, String::from(seed));
+                maze_generator.generate_maze();
+
+                let maze_size = maze_generator.get_maze_size();
+                let exit = maze_generator.get_exit();
+                let (hole_x, hole_y) = get_exit_hole(&maze_generator);
+
+                //Hole has to be on the border and exit field has to be inside maze
+                assert!(hole_x == 0 || hole_y == 0 || hole_x == maze_size - 1 || hole_y == maze_size - 1,
+                    "Exit hole not on border in {} maze of size {} with seed \"{}\"", generator, size, seed);
+                assert!((1..maze_size - 1).contains(&(exit.0 as usize)) && (1..maze_size - 1).contains(&(exit.1 as usize)),
+                    "Exit not inside {} maze of size {} with seed \"{}\"", generator, size, seed);
+
+                assert!(!maze_generator.is_wall(hole_x, hole_y),
+                    "Exit hole is a wall in {} maze of size {} with seed \"{}\"", generator, size, seed);
+                assert!(!maze_generator.is_wall(exit.0 as usize, exit.1 as usize),
+                    "Exit hole leads to wall in {} maze of size {} with seed \"{}\"", generator, size, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn start_is_not_exit_and_can_move() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            for size in SIZES {
+                let mut maze_generator = MazeGenerator::new(generator, size, String::from(seed));
+                maze_generator.generate_maze();
+
+                let start = maze_generator.get_start_position();
+                let exit = maze_generator.get_exit();
+                let (x, y) = (start.0 as usize, start.1 as usize);
+
+                assert!((start.0, start.1) != (exit.0, exit.1),
+                    "Start is placed at exit in {} maze of size {} with seed \"{}\"", generator, size, seed);
+                assert!(!maze_generator.is_wall(x - 1, y) || !maze_generator.is_wall(x + 1, y) ||
+                    !maze_generator.is_wall(x, y - 1) || !maze_generator.is_wall(x, y + 1),
+                    "Start has no empty neighbour in {} maze of size {} with seed \"{}\"", generator, size, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn shortest_path_reaches_exit() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            for size in SIZES {
+                let mut maze_generator = MazeGenerator::new(generator, size, String::from(seed));
+                maze_generator.generate_maze();
+
+                let start = maze_generator.get_start_position();
+                let exit = maze_generator.get_exit();
+                let distance = start.0.abs_diff(exit.0) + start.1.abs_diff(exit.1);
+
+                //Path can't be shorter than distance without walls
+                let path_length = maze_generator.get_shortest_path_length()
+                    .unwrap_or_else(|| panic!("No shortest path in {} maze of size {} with seed \"{}\"", generator, size, seed));
+
+                assert!(path_length >= distance as usize,
+                    "Shortest path is shorter than distance to exit in {} maze of size {} with seed \"{}\"", generator, size, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn shortest_path_goes_through_empty_fields() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            let mut maze_generator = MazeGenerator::new(generator, 51, String::from(seed));
+            maze_generator.generate_maze();
+
+            let path = maze_generator.get_shortest_path().expect("Exit can't be reached");
+
+            let (start, exit) = (maze_generator.get_start_position(), maze_generator.get_exit());
+
+            assert_eq!((path[0].0, path[0].1), (start.0, start.1));
+            assert_eq!((path[path.len() - 1].0, path[path.len() - 1].1), (exit.0, exit.1));
+
+            for (field, next_field) in path.iter().zip(path.iter().skip(1)) {
+                assert_eq!(field.0.abs_diff(next_field.0) + field.1.abs_diff(next_field.1), 1,
+                    "Path jumps from {}x{} to {}x{} ({}, seed \"{}\")", field.0, field.1, next_field.0, next_field.1, generator, seed);
+                assert!(!maze_generator.is_wall(next_field.0 as usize, next_field.1 as usize),
+                    "Path goes through wall at {}x{} ({}, seed \"{}\")", next_field.0, next_field.1, generator, seed);
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//Center start is the empty field closest to the center of the maze and doesn't depend on seed
+use dsdmaze::maze_generator::StartPlacement;
+
+mod common;
+
+#[test]
+fn center_start_is_closest_empty_field() {
+    for selected_generator in common::GENERATORS {
+        for seed in ["center", "start", "placement"] {
+            let maze_generator = common::create_maze_with(selected_generator, 31, seed, |maze_generator| maze_generator.set_start_placement(StartPlacement::Center));
+            let center = maze_generator.get_maze_size() / 2;
+            let start_position = maze_generator.get_start_position();
+            let (start_x, start_y) = (start_position.0 as usize, start_position.1 as usize);
+
+            let distance = |x: usize, y: usize| x.abs_diff(center).pow(2) + y.abs_diff(center).pow(2);
+
+            assert!(!maze_generator.is_wall(start_x, start_y));
+
+            for (x, y, wall) in maze_generator.cells() {
+                assert!(wall || distance(x, y) >= distance(start_x, start_y), "Field ({}, {}) is closer to center than start", x, y);
+            }
+        }
+    }
+}
+
@@ -0,0 +1,87 @@
+//Weave crossings are placed only where ramps and tunnel fit and they don't change ground level of maze
+use dsdmaze::maze_generator::{Direction, MazeGenerator, SelectedGenerator, WeaveField};
+
+mod common;
+
+const SEEDS: [&str; 4] = ["", "weave", "12345", "Seed with spaces"];
+
+#[test]
+fn crossings_have_ramps_and_entrances() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            let mut maze_generator = MazeGenerator::new(generator, 51, String::from(seed));
+            maze_generator.generate_maze();
+
+            let cells: Vec<_> = maze_generator.cells().collect();
+            maze_generator.add_weave_crossings(10);
+
+            let is_wall = |x: usize, y: usize| maze_generator.is_wall(x, y);
+
+            assert!(cells == maze_generator.cells().collect::<Vec<_>>(), "Crossings changed maze array ({}, seed \"{}\")", generator, seed);
+
+            //DFS maze doesn't have regular fields and walls so it can have only few places for crossing or none
+            if !matches!(generator, SelectedGenerator::DFS) {
+                assert!(!maze_generator.get_crossings().is_empty(), "No crossing was placed ({}, seed \"{}\")", generator, seed);
+            }
+
+            for crossing in maze_generator.get_crossings() {
+                let (x, y) = (crossing.position.0 as usize, crossing.position.1 as usize);
+                let (tunnel_x, tunnel_y) = if crossing.vertical_tunnel { (0, 1) } else { (1, 0) };
+
+                assert!(matches!(maze_generator.get_weave_field(x, y), Some(WeaveField::Crossing(_))));
+                assert!(!is_wall(x, y) && !is_wall(x - tunnel_y, y - tunnel_x) && !is_wall(x + tunnel_y, y + tunnel_x),
+                    "Bridge at {}x{} isn't straight corridor ({}, seed \"{}\")", x, y, generator, seed);
+
+                //Both ramps go down towards the crossing and lead from empty entrance
+                let ramps = [(x - tunnel_x, y - tunnel_y, x - 2 * tunnel_x, y - 2 * tunnel_y), (x + tunnel_x, y + tunnel_y, x + 2 * tunnel_x, y + 2 * tunnel_y)];
+
+                for (ramp_x, ramp_y, entrance_x, entrance_y) in ramps {
+                    let expected_x = ramp_x as i32 + match maze_generator.get_weave_field(ramp_x, ramp_y) {
+                        Some(WeaveField::Ramp(Direction::Left)) => -1,
+                        Some(WeaveField::Ramp(Direction::Right)) => 1,
+                        _ => 0
+                    };
+
+                    let expected_y = ramp_y as i32 + match maze_generator.get_weave_field(ramp_x, ramp_y) {
+                        Some(WeaveField::Ramp(Direction::Top)) => -1,
+                        Some(WeaveField::Ramp(Direction::Bottom)) => 1,
+                        _ => 0
+                    };
+
+                    assert!(is_wall(ramp_x, ramp_y), "Ramp at {}x{} isn't wall on the ground", ramp_x, ramp_y);
+                    assert_eq!((expected_x, expected_y), (x as i32, y as i32), "Ramp at {}x{} doesn't go down to crossing", ramp_x, ramp_y);
+                    assert!(!is_wall(entrance_x, entrance_y), "Tunnel entrance at {}x{} is wall", entrance_x, entrance_y);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn crossings_are_saved_with_maze() {
+    let mut maze_generator = MazeGenerator::new(SelectedGenerator::RD, 51, String::from("weave"));
+    maze_generator.generate_maze();
+    maze_generator.add_weave_crossings(5);
+
+    let loaded_generator = MazeGenerator::from_maze(maze_generator.to_maze()).expect("Saved maze couldn't be loaded");
+
+    assert_eq!(loaded_generator.get_crossings().len(), maze_generator.get_crossings().len());
+}
+
+#[test]
+fn tunnels_dont_make_shortest_path_longer() {
+    for generator in common::GENERATORS {
+        for seed in SEEDS {
+            let mut maze_generator = MazeGenerator::new(generator, 51, String::from(seed));
+            maze_generator.generate_maze();
+
+            let path_length = maze_generator.get_shortest_path_length();
+            maze_generator.add_weave_crossings(20);
+
+            let weave_path_length = maze_generator.get_shortest_path_length();
+
+            assert!(weave_path_length.is_some() && weave_path_length <= path_length,
+                "Tunnels made shortest path longer ({}, seed \"{}\")", generator, seed);
+        }
+    }
+}